@@ -138,6 +138,7 @@ pub struct TransactionTemplate<'a> {
   pub outputs: usize,
   pub p2tr: bool,
   pub receiver: Option<Address>,
+  pub script_sigs: &'a [ScriptBuf],
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -187,6 +188,7 @@ impl<'a> Default for TransactionTemplate<'a> {
       outputs: 1,
       p2tr: false,
       receiver: None,
+      script_sigs: &[],
     }
   }
 }
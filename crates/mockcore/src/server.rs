@@ -181,10 +181,29 @@ impl Api for Server {
   }
 
   fn get_block_stats(&self, height: usize) -> Result<GetBlockStatsResult, jsonrpc_core::Error> {
-    let Some(block_hash) = self.state().hashes.get(height).cloned() else {
+    let state = self.state();
+
+    let Some(block_hash) = state.hashes.get(height).cloned() else {
       return Err(Self::not_found());
     };
 
+    let total_fee = state.blocks[&block_hash]
+      .txdata
+      .iter()
+      .skip(1)
+      .map(|tx| {
+        tx.input
+          .iter()
+          .map(|txin| {
+            state.transactions[&txin.previous_output.txid].output
+              [txin.previous_output.vout as usize]
+              .value
+          })
+          .sum::<u64>()
+          - tx.output.iter().map(|txout| txout.value).sum::<u64>()
+      })
+      .sum::<u64>();
+
     Ok(GetBlockStatsResult {
       avg_fee: Amount::ZERO,
       avg_fee_rate: Amount::ZERO,
@@ -217,7 +236,7 @@ impl Api for Server {
       total_out: Amount::ZERO,
       total_size: 0,
       total_weight: 0,
-      total_fee: Amount::ZERO,
+      total_fee: Amount::from_sat(total_fee),
       txs: 0,
       utxo_increase: 0,
       utxo_size_inc: 0,
@@ -198,7 +198,7 @@ impl State {
   pub(crate) fn broadcast_tx(&mut self, template: TransactionTemplate) -> Txid {
     let mut total_value = 0;
     let mut input = Vec::new();
-    for (height, tx, vout, witness) in template.inputs.iter() {
+    for (i, (height, tx, vout, witness)) in template.inputs.iter().enumerate() {
       let block_hash = self
         .hashes
         .get(*height)
@@ -223,7 +223,7 @@ impl State {
 
       input.push(TxIn {
         previous_output: OutPoint::new(tx.txid(), *vout as u32),
-        script_sig: ScriptBuf::new(),
+        script_sig: template.script_sigs.get(i).cloned().unwrap_or_default(),
         sequence: Sequence::MAX,
         witness: witness.clone(),
       });
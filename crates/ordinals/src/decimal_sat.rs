@@ -9,7 +9,7 @@ pub struct DecimalSat {
 impl DecimalSat {
   pub fn from_sat(sat: Sat, network: Network) -> Self {
     Self {
-      height: sat.height(),
+      height: sat.height(network),
       offset: sat.third(network),
     }
   }
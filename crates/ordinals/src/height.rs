@@ -12,8 +12,8 @@ impl Height {
     get_block_subsidy(self.0, network) * COIN_VALUE
   }
 
-  pub fn starting_sat(self) -> Sat {
-    SatsSubsidy::sat_from_height(self)
+  pub fn starting_sat(self, network: Network) -> Sat {
+    SatsSubsidy::sat_from_height(self, network)
   }
 }
 
@@ -81,10 +81,10 @@ mod tests {
 
   #[test]
   fn starting_sat() {
-    assert_eq!(Height(0).starting_sat(), 0);
-    assert_eq!(Height(1).starting_sat(), 5000000000);
+    assert_eq!(Height(0).starting_sat(Network::Bellscoin), 0);
+    assert_eq!(Height(1).starting_sat(Network::Bellscoin), 5000000000);
     assert_eq!(
-      Height(u32::MAX).starting_sat(),
+      Height(u32::MAX).starting_sat(Network::Bellscoin),
       *Epoch::STARTING_SATS.last().unwrap()
     );
   }
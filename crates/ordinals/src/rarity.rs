@@ -43,10 +43,10 @@ impl Display for Rarity {
   }
 }
 
-impl From<Sat> for Rarity {
-  fn from(sat: Sat) -> Self {
+impl Rarity {
+  pub fn from_sat(sat: Sat, network: Network) -> Self {
     let epoch = Epoch::from(sat);
-    let block_first_sat = SatsSubsidy::height_from_sat(sat).starting_sat();
+    let block_first_sat = sat.height(network).starting_sat(network);
 
     if sat.0 == 0 {
       Self::Mythic
@@ -80,15 +80,39 @@ mod tests {
 
   #[test]
   fn rarity() {
-    assert_eq!(Sat(0).rarity(), Rarity::Mythic);
-    assert_eq!(Sat(1).rarity(), Rarity::Common);
+    assert_eq!(Sat(0).rarity(Network::Bellscoin), Rarity::Mythic);
+    assert_eq!(Sat(1).rarity(Network::Bellscoin), Rarity::Common);
+
+    assert_eq!(
+      Sat(50 * COIN_VALUE - 1).rarity(Network::Bellscoin),
+      Rarity::Common
+    );
+    assert_eq!(
+      Sat(50 * COIN_VALUE).rarity(Network::Bellscoin),
+      Rarity::Uncommon
+    );
+    assert_eq!(
+      Sat(50 * COIN_VALUE + 1).rarity(Network::Bellscoin),
+      Rarity::Common
+    );
+
+    assert_eq!(
+      Sat(2067187500000000 - 1).rarity(Network::Bellscoin),
+      Rarity::Common
+    );
+    assert_eq!(
+      Sat(2067187500000000 + 1).rarity(Network::Bellscoin),
+      Rarity::Common
+    );
+  }
 
-    assert_eq!(Sat(50 * COIN_VALUE - 1).rarity(), Rarity::Common);
-    assert_eq!(Sat(50 * COIN_VALUE).rarity(), Rarity::Uncommon);
-    assert_eq!(Sat(50 * COIN_VALUE + 1).rarity(), Rarity::Common);
+  #[test]
+  fn rarity_depends_on_network() {
+    let sat = Height(144_002).starting_sat(Network::Regtest);
 
-    assert_eq!(Sat(2067187500000000 - 1).rarity(), Rarity::Common);
-    assert_eq!(Sat(2067187500000000 + 1).rarity(), Rarity::Common);
+    assert_ne!(sat, Height(144_002).starting_sat(Network::Bellscoin));
+    assert_eq!(sat.rarity(Network::Regtest), Rarity::Uncommon);
+    assert_eq!(sat.rarity(Network::Bellscoin), Rarity::Common);
   }
 
   #[test]
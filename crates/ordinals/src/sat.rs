@@ -12,8 +12,8 @@ impl Sat {
     self.0
   }
 
-  pub fn height(self) -> Height {
-    SatsSubsidy::height_from_sat(self)
+  pub fn height(self, network: Network) -> Height {
+    SatsSubsidy::height_from_sat(self, network)
   }
 
   pub fn cycle(self) -> u32 {
@@ -33,8 +33,7 @@ impl Sat {
   }
 
   pub fn third(self, network: Network) -> u64 {
-    self.epoch_position()
-      % (get_block_subsidy(SatsSubsidy::height_from_sat(self).0, network) * COIN_VALUE)
+    self.epoch_position() % self.height(network).subsidy(network)
   }
 
   pub fn epoch_position(self) -> u64 {
@@ -45,16 +44,14 @@ impl Sat {
     DecimalSat::from_sat(self, network)
   }
 
-  pub fn rarity(self) -> Rarity {
-    self.into()
+  pub fn rarity(self, network: Network) -> Rarity {
+    Rarity::from_sat(self, network)
   }
 
   /// Is this sat common or not?  Much faster than `Sat::rarity()`.
   pub fn common(self, network: Network) -> bool {
     let epoch = self.epoch();
-    (self.0 - epoch.starting_sat().0)
-      % (get_block_subsidy(SatsSubsidy::height_from_sat(self).0, network) * COIN_VALUE)
-      != 0
+    (self.0 - epoch.starting_sat().0) % self.height(network).subsidy(network) != 0
   }
 
   pub fn coin(self) -> bool {
@@ -76,7 +73,7 @@ impl Sat {
     name.chars().rev().collect()
   }
 
-  pub fn charms(self) -> u16 {
+  pub fn charms(self, network: Network) -> u16 {
     let mut charms = 0;
 
     if self.nineball() {
@@ -87,7 +84,7 @@ impl Sat {
       Charm::Coin.set(&mut charms);
     }
 
-    match self.rarity() {
+    match self.rarity(network) {
       Rarity::Common => {}
       Rarity::Epic => Charm::Epic.set(&mut charms),
       Rarity::Mythic => Charm::Mythic.set(&mut charms),
@@ -97,6 +94,28 @@ impl Sat {
     charms
   }
 
+  /// Parses a sat in any notation. Decimal notation depends on the network's
+  /// subsidy schedule, so it is interpreted on `network`.
+  pub fn parse(s: &str, network: Network) -> Result<Self, Error> {
+    if s.chars().any(|c| c.is_ascii_lowercase()) {
+      Self::from_name(s)
+    } else if s.contains('%') {
+      Self::from_percentile(s)
+    } else if s.contains('.') {
+      Self::from_decimal(s, network)
+    } else {
+      let sat = Self(
+        s.parse()
+          .map_err(|source| ErrorKind::ParseInt { source }.error(s))?,
+      );
+      if sat > Self::LAST {
+        Err(ErrorKind::IntegerRange.error(s))
+      } else {
+        Ok(sat)
+      }
+    }
+  }
+
   fn from_name(s: &str) -> Result<Self, Error> {
     let mut x = 0;
     for c in s.chars() {
@@ -132,7 +151,7 @@ impl Sat {
       return Err(ErrorKind::BlockOffset.error(decimal));
     }
 
-    Ok(height.starting_sat() + offset)
+    Ok(height.starting_sat(network) + offset)
   }
 
   fn from_percentile(percentile: &str) -> Result<Self, Error> {
@@ -255,24 +274,10 @@ impl AddAssign<u64> for Sat {
 impl FromStr for Sat {
   type Err = Error;
 
+  /// Parses a sat, interpreting decimal notation on mainnet. Use `Sat::parse`
+  /// where the network is known.
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    if s.chars().any(|c| c.is_ascii_lowercase()) {
-      Self::from_name(s)
-    } else if s.contains('%') {
-      Self::from_percentile(s)
-    } else if s.contains('.') {
-      Self::from_decimal(s, Network::Bellscoin)
-    } else {
-      let sat = Self(
-        s.parse()
-          .map_err(|source| ErrorKind::ParseInt { source }.error(s))?,
-      );
-      if sat > Self::LAST {
-        Err(ErrorKind::IntegerRange.error(s))
-      } else {
-        Ok(sat)
-      }
-    }
+    Self::parse(s, Network::Bellscoin)
   }
 }
 
@@ -288,11 +293,11 @@ mod tests {
 
   #[test]
   fn height() {
-    assert_eq!(Sat(0).height(), 0);
-    assert_eq!(Sat(1).height(), 0);
-    assert_eq!(Sat(50 * COIN_VALUE).height(), 1);
-    assert_eq!(Sat(2099999997689999).height(), 6929999);
-    assert_eq!(Sat(2099999997689998).height(), 6929998);
+    assert_eq!(Sat(0).height(Network::Bellscoin), 0);
+    assert_eq!(Sat(1).height(Network::Bellscoin), 0);
+    assert_eq!(Sat(50 * COIN_VALUE).height(Network::Bellscoin), 1);
+    assert_eq!(Sat(2099999997689999).height(Network::Bellscoin), 6929999);
+    assert_eq!(Sat(2099999997689998).height(Network::Bellscoin), 6929998);
   }
 
   #[test]
@@ -409,6 +414,18 @@ mod tests {
     assert!(parse("6930000.0").is_err());
   }
 
+  #[test]
+  fn parse_decimal_on_network() {
+    assert_eq!(
+      Sat::parse("144002.0", Network::Regtest).unwrap(),
+      Height(144_002).starting_sat(Network::Regtest),
+    );
+    assert_eq!(
+      Sat::parse("144002.0", Network::Bellscoin).unwrap(),
+      Height(144_002).starting_sat(Network::Bellscoin),
+    );
+  }
+
   #[test]
   fn from_str_number() {
     assert_eq!(parse("0").unwrap(), 0);
@@ -473,7 +490,7 @@ mod tests {
     fn case(n: u64) {
       assert_eq!(
         Sat(n).common(Network::Bellscoin),
-        Sat(n).rarity() == Rarity::Common
+        Sat(n).rarity(Network::Bellscoin) == Rarity::Common
       );
     }
 
@@ -493,7 +510,10 @@ mod tests {
     // uncommon sat.
     for height in 0..Epoch::FIRST_POST_SUBSIDY.starting_height().0 {
       let height = Height(height);
-      assert!(!Sat::common(height.starting_sat(), Network::Bellscoin));
+      assert!(!Sat::common(
+        height.starting_sat(Network::Bellscoin),
+        Network::Bellscoin
+      ));
     }
   }
 
@@ -511,10 +531,10 @@ mod tests {
       let sat = Sat(height * 50 * COIN_VALUE);
       assert_eq!(
         sat.nineball(),
-        sat.height() == 9,
+        sat.height(Network::Bellscoin) == 9,
         "nineball: {} height: {}",
         sat.nineball(),
-        sat.height()
+        sat.height(Network::Bellscoin)
       );
     }
   }
@@ -1,16 +1,13 @@
-use io::Read;
+use {super::*, io::Read, std::sync::RwLock, subsidy::TAIL_EMISSION_HEIGHT};
 
-use super::*;
+/// Number of heights computed at a time when a table is extended.
+const EXTENSION: u32 = 10_000;
 
 lazy_static! {
-  static ref FILE_CONTENT: HashMap<u32, u64> = {
-    String::from_utf8(decompress_brotli().unwrap())
-      .unwrap()
-      .lines()
-      .enumerate()
-      .filter_map(|(idx, line)| line.parse::<u64>().ok().map(|x| (idx as u32, x)))
-      .collect()
-  };
+  /// Cumulative coin supply at the start of each height, per network. Tables
+  /// only cover heights up to `TAIL_EMISSION_HEIGHT`, after which the subsidy
+  /// is constant, and are extended on demand.
+  static ref SUPPLY: RwLock<HashMap<Network, Vec<u64>>> = RwLock::new(HashMap::new());
 }
 
 fn decompress_brotli() -> io::Result<Vec<u8>> {
@@ -23,38 +20,92 @@ fn decompress_brotli() -> io::Result<Vec<u8>> {
   Ok(decompressed_data)
 }
 
+/// The embedded mainnet supply table. Heights it covers are used as-is rather
+/// than recomputed, so that sat numbers in existing indexes don't change.
+fn embedded() -> Vec<u64> {
+  String::from_utf8(decompress_brotli().unwrap())
+    .unwrap()
+    .lines()
+    .map_while(|line| line.parse::<u64>().ok())
+    .take(TAIL_EMISSION_HEIGHT as usize + 1)
+    .collect()
+}
+
 #[repr(transparent)]
 pub struct SatsSubsidy;
 
 impl SatsSubsidy {
-  pub fn height_from_sat(value: Sat) -> Height {
-    let mut low = 0;
-    let mut high = 800_000;
-
-    while low <= high {
-      let mid = (low + high) / 2;
-      let mid_height = Height(mid as u32);
-      let mid_value = Self::sat_from_height(mid_height).0;
-
-      if mid_value == value.0 {
-        return mid_height;
-      } else if mid_value < value.0 {
-        low = mid + 1;
-      } else {
-        high = mid - 1;
+  /// Calls `f` with the supply table for `network`, after extending it until
+  /// `covered` returns true or it reaches `TAIL_EMISSION_HEIGHT`.
+  fn with_table<T>(
+    network: Network,
+    covered: impl Fn(&[u64]) -> bool,
+    f: impl FnOnce(&[u64]) -> T,
+  ) -> T {
+    let complete = |table: &[u64]| table.len() > TAIL_EMISSION_HEIGHT as usize;
+
+    {
+      let tables = SUPPLY.read().unwrap();
+      if let Some(table) = tables.get(&network) {
+        if complete(table) || covered(table) {
+          return f(table);
+        }
       }
     }
 
-    // Return the highest height that does not exceed the value
-    if high < 0 {
-      Height(0)
-    } else {
-      Height(high as u32)
+    let mut tables = SUPPLY.write().unwrap();
+
+    let table = tables.entry(network).or_insert_with(|| {
+      if network == Network::Bellscoin {
+        embedded()
+      } else {
+        vec![0]
+      }
+    });
+
+    while !(complete(table) || covered(table)) {
+      let start = u32::try_from(table.len()).unwrap();
+      let end = (start + EXTENSION).min(TAIL_EMISSION_HEIGHT + 1);
+
+      let mut supply = *table.last().unwrap();
+      for height in start..end {
+        supply += get_block_subsidy(height - 1, network);
+        table.push(supply);
+      }
     }
+
+    f(table)
   }
 
-  pub fn sat_from_height(value: Height) -> Sat {
-    Sat(*FILE_CONTENT.get(&value.0).unwrap() * COIN_VALUE)
+  pub fn height_from_sat(sat: Sat, network: Network) -> Height {
+    Self::with_table(
+      network,
+      |table| table.last().unwrap() * COIN_VALUE > sat.0,
+      |table| match table.partition_point(|supply| supply * COIN_VALUE <= sat.0) {
+        after if after < table.len() => Height(u32::try_from(after - 1).unwrap()),
+        _ => {
+          let tail_start = table[TAIL_EMISSION_HEIGHT as usize] * COIN_VALUE;
+          let subsidy = get_block_subsidy(TAIL_EMISSION_HEIGHT, network) * COIN_VALUE;
+          Height(TAIL_EMISSION_HEIGHT + u32::try_from((sat.0 - tail_start) / subsidy).unwrap())
+        }
+      },
+    )
+  }
+
+  pub fn sat_from_height(height: Height, network: Network) -> Sat {
+    let tail = height.0.saturating_sub(TAIL_EMISSION_HEIGHT);
+
+    Self::with_table(
+      network,
+      |table| table.len() > height.0 as usize,
+      |table| {
+        Sat(
+          (table[(height.0 - tail) as usize]
+            + u64::from(tail) * get_block_subsidy(TAIL_EMISSION_HEIGHT, network))
+            * COIN_VALUE,
+        )
+      },
+    )
   }
 }
 
@@ -64,20 +115,87 @@ mod tests {
 
   #[test]
   fn test_sat_from_height() {
-    assert_eq!(SatsSubsidy::sat_from_height(Height(0)), 0);
-    assert_eq!(SatsSubsidy::sat_from_height(Height(1)), 88 * COIN_VALUE);
-    assert_eq!(SatsSubsidy::sat_from_height(Height(124)), 3588 * COIN_VALUE);
-    assert_eq!(SatsSubsidy::sat_from_height(Height(125)), 3638 * COIN_VALUE);
+    assert_eq!(
+      SatsSubsidy::sat_from_height(Height(0), Network::Bellscoin),
+      0
+    );
+    assert_eq!(
+      SatsSubsidy::sat_from_height(Height(1), Network::Bellscoin),
+      88 * COIN_VALUE
+    );
+    assert_eq!(
+      SatsSubsidy::sat_from_height(Height(124), Network::Bellscoin),
+      3588 * COIN_VALUE
+    );
+    assert_eq!(
+      SatsSubsidy::sat_from_height(Height(125), Network::Bellscoin),
+      3638 * COIN_VALUE
+    );
   }
 
   #[test]
   fn test_height_from_sat() {
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(363799999999)).0, 124);
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(363800000000)).0, 125);
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(363800000001)).0, 125);
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(0)).0, 0);
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(8800000000)).0, 1);
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(8799999999)).0, 0);
-    assert_eq!(SatsSubsidy::height_from_sat(Sat(8799999998)).0, 0);
+    let height = |sat| SatsSubsidy::height_from_sat(Sat(sat), Network::Bellscoin).0;
+    assert_eq!(height(363799999999), 124);
+    assert_eq!(height(363800000000), 125);
+    assert_eq!(height(363800000001), 125);
+    assert_eq!(height(0), 0);
+    assert_eq!(height(8800000000), 1);
+    assert_eq!(height(8799999999), 0);
+    assert_eq!(height(8799999998), 0);
+  }
+
+  #[test]
+  fn non_mainnet_tables_are_computed_from_subsidy_schedule() {
+    for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+      assert_eq!(SatsSubsidy::sat_from_height(Height(0), network), 0);
+      assert_eq!(
+        SatsSubsidy::sat_from_height(Height(1), network),
+        88 * COIN_VALUE
+      );
+      assert_eq!(
+        SatsSubsidy::sat_from_height(Height(101), network),
+        (88 + 100 * 2) * COIN_VALUE
+      );
+      assert_eq!(
+        SatsSubsidy::sat_from_height(Height(102), network),
+        (88 + 100 * 2 + get_block_subsidy(101, network)) * COIN_VALUE
+      );
+      assert_eq!(
+        SatsSubsidy::height_from_sat(Sat((88 + 100 * 2) * COIN_VALUE - 1), network),
+        100
+      );
+      assert_eq!(
+        SatsSubsidy::height_from_sat(Sat((88 + 100 * 2) * COIN_VALUE), network),
+        101
+      );
+    }
+  }
+
+  #[test]
+  fn tables_extend_past_tail_emission() {
+    for network in [Network::Bellscoin, Network::Regtest] {
+      let tail = SatsSubsidy::sat_from_height(Height(TAIL_EMISSION_HEIGHT), network);
+
+      assert_eq!(
+        SatsSubsidy::sat_from_height(Height(TAIL_EMISSION_HEIGHT + 1_000_000), network),
+        tail.0 + 1_000_000 * get_block_subsidy(TAIL_EMISSION_HEIGHT, network) * COIN_VALUE,
+      );
+
+      for height in [
+        TAIL_EMISSION_HEIGHT - 1,
+        TAIL_EMISSION_HEIGHT,
+        TAIL_EMISSION_HEIGHT + 1,
+        2_000_000,
+      ] {
+        let start = SatsSubsidy::sat_from_height(Height(height), network);
+        assert_eq!(SatsSubsidy::height_from_sat(start, network), height);
+        assert_eq!(SatsSubsidy::height_from_sat(start + 1, network), height);
+        assert_eq!(
+          SatsSubsidy::height_from_sat(Sat(start.0 - 1), network),
+          height - 1
+        );
+      }
+    }
   }
 }
@@ -3,6 +3,9 @@ use bellscoin::Network;
 const AUXPOW_START_HEIGHT: u32 = 144_000;
 const AUXPOW_THRESHOLD: u32 = 1000;
 
+/// Height from which the subsidy no longer varies.
+pub(crate) const TAIL_EMISSION_HEIGHT: u32 = 518_400;
+
 struct MT19937 {
   mt: [u32; 624],
   index: usize,
@@ -132,7 +135,7 @@ pub fn get_block_subsidy(n_height: u32, network: Network) -> u64 {
       500..=699 => 50,
       _ => 25,
     };
-  } else if n_height < 518400 {
+  } else if n_height < TAIL_EMISSION_HEIGHT {
     n_subsidy = match rand {
       990..=1000 => 500,
       940..=989 => 50,
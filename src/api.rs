@@ -1,24 +1,57 @@
 use {
   super::*,
+  schemars::JsonSchema,
   serde_hex::{SerHex, Strict},
 };
 
 pub use crate::{
   subcommand::decode::RawOutput as Decode,
   templates::{
-    BlocksHtml as Blocks, RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status,
+    BlocksHtml as Blocks, RuneHoldersHtml as RuneHolders, RunesHtml as Runes, StatusHtml as Status,
     TransactionHtml as Transaction,
   },
 };
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AuxPow {
+  pub chain_index: i32,
+  #[schemars(with = "Vec<String>")]
+  pub chain_merkle_branch: Vec<TxMerkleNode>,
+  pub coinbase_index: i32,
+  #[schemars(with = "Vec<String>")]
+  pub coinbase_merkle_branch: Vec<TxMerkleNode>,
+  #[schemars(with = "String")]
+  pub parent_block_hash: BlockHash,
+  #[schemars(with = "serde_json::Value")]
+  pub parent_coinbase: bellscoin::blockdata::transaction::Transaction,
+}
+
+impl AuxPow {
+  pub(crate) fn new(auxpow: bellscoin::block::AuxPow) -> Self {
+    Self {
+      chain_index: auxpow.chain_index,
+      chain_merkle_branch: auxpow.blockchain_branch,
+      coinbase_index: auxpow.n_index,
+      coinbase_merkle_branch: auxpow.coinbase_branch,
+      parent_block_hash: BlockHash::hash(&consensus::encode::serialize(&auxpow.parent_block_hash)),
+      parent_coinbase: auxpow.coinbase_tx,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Block {
+  pub auxpow: Option<AuxPow>,
   pub best_height: u32,
+  #[schemars(with = "String")]
   pub hash: BlockHash,
   pub height: u32,
   pub inscriptions: Vec<InscriptionId>,
+  #[schemars(with = "Vec<String>")]
   pub runes: Vec<SpacedRune>,
+  #[schemars(with = "String")]
   pub target: BlockHash,
+  #[schemars(with = "Vec<serde_json::Value>")]
   pub transactions: Vec<bellscoin::blockdata::transaction::Transaction>,
 }
 
@@ -31,6 +64,7 @@ impl Block {
     runes: Vec<SpacedRune>,
   ) -> Self {
     Self {
+      auxpow: block.header.auxpow.clone().map(AuxPow::new),
       hash: block.header.block_hash(),
       target: target_as_block_hash(block.header.target()),
       height: height.0,
@@ -42,15 +76,18 @@ impl Block {
   }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BlockInfo {
   pub average_fee: u64,
   pub average_fee_rate: u64,
+  pub auxpow: Option<AuxPow>,
   pub bits: u32,
+  #[schemars(with = "String")]
   #[serde(with = "SerHex::<Strict>")]
   pub chainwork: [u8; 32],
   pub confirmations: i32,
   pub difficulty: f64,
+  #[schemars(with = "String")]
   pub hash: BlockHash,
   pub feerate_percentiles: [u64; 5],
   pub height: u32,
@@ -59,13 +96,17 @@ pub struct BlockInfo {
   pub max_tx_size: u32,
   pub median_fee: u64,
   pub median_time: Option<u64>,
+  #[schemars(with = "String")]
   pub merkle_root: TxMerkleNode,
   pub min_fee: u64,
   pub min_fee_rate: u64,
+  #[schemars(with = "Option<String>")]
   pub next_block: Option<BlockHash>,
   pub nonce: u32,
+  #[schemars(with = "Option<String>")]
   pub previous_block: Option<BlockHash>,
   pub subsidy: u64,
+  #[schemars(with = "String")]
   pub target: BlockHash,
   pub timestamp: u64,
   pub total_fee: u64,
@@ -75,27 +116,29 @@ pub struct BlockInfo {
   pub version: u32,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Children {
   pub ids: Vec<InscriptionId>,
   pub more: bool,
   pub page: usize,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ChildInscriptions {
   pub children: Vec<ChildInscriptionRecursive>,
   pub more: bool,
   pub page: usize,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Inscription {
   pub address: Option<String>,
+  #[schemars(with = "Vec<String>")]
   pub charms: Vec<Charm>,
   pub children: Vec<InscriptionId>,
   pub content_length: Option<usize>,
   pub content_type: Option<String>,
+  pub duplicate_of: Option<InscriptionId>,
   pub effective_content_type: Option<String>,
   pub fee: u64,
   pub height: u32,
@@ -104,15 +147,39 @@ pub struct Inscription {
   pub number: i32,
   pub parents: Vec<InscriptionId>,
   pub previous: Option<InscriptionId>,
+  #[schemars(with = "Option<String>")]
   pub rune: Option<SpacedRune>,
+  #[schemars(with = "Option<u64>")]
   pub sat: Option<ordinals::Sat>,
+  #[schemars(with = "String")]
   pub satpoint: SatPoint,
   pub timestamp: i64,
   pub value: Option<u64>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct InscriptionHistory {
+  pub id: InscriptionId,
+  pub more: bool,
+  pub page: usize,
+  pub transfers: Vec<InscriptionTransfer>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct InscriptionTransfer {
+  pub address: Option<String>,
+  pub height: u32,
+  #[schemars(with = "String")]
+  pub new_satpoint: SatPoint,
+  #[schemars(with = "String")]
+  pub old_satpoint: SatPoint,
+  #[schemars(with = "String")]
+  pub txid: Txid,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct InscriptionRecursive {
+  #[schemars(with = "Vec<String>")]
   pub charms: Vec<Charm>,
   pub content_type: Option<String>,
   pub content_length: Option<usize>,
@@ -121,43 +188,67 @@ pub struct InscriptionRecursive {
   pub height: u32,
   pub id: InscriptionId,
   pub number: i32,
+  #[schemars(with = "String")]
   pub output: OutPoint,
+  #[schemars(with = "Option<u64>")]
   pub sat: Option<ordinals::Sat>,
+  #[schemars(with = "String")]
   pub satpoint: SatPoint,
   pub timestamp: i64,
   pub value: Option<u64>,
   pub address: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ChildInscriptionRecursive {
+  #[schemars(with = "Vec<String>")]
   pub charms: Vec<Charm>,
   pub fee: u64,
   pub height: u32,
   pub id: InscriptionId,
   pub number: i32,
+  #[schemars(with = "String")]
   pub output: OutPoint,
+  #[schemars(with = "Option<u64>")]
   pub sat: Option<ordinals::Sat>,
+  #[schemars(with = "String")]
   pub satpoint: SatPoint,
   pub timestamp: i64,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EventBatch {
+  pub block_height: u32,
+  pub events: Vec<crate::index::event::Event>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Events {
+  pub events: Vec<crate::index::event::Event>,
+  pub more: bool,
+  pub next_height: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Inscriptions {
   pub ids: Vec<InscriptionId>,
   pub more: bool,
   pub page_index: u32,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Output {
+  #[schemars(with = "Option<String>")]
   pub address: Option<Address<NetworkUnchecked>>,
   pub indexed: bool,
   pub inscriptions: Vec<InscriptionId>,
+  #[schemars(with = "BTreeMap<String, schema::Pile>")]
   pub runes: BTreeMap<SpacedRune, Pile>,
   pub sat_ranges: Option<Vec<(u64, u64)>>,
+  #[schemars(with = "String")]
   pub script_pubkey: ScriptBuf,
   pub spent: bool,
+  #[schemars(with = "String")]
   pub transaction: Txid,
   pub value: u64,
 }
@@ -190,9 +281,10 @@ impl Output {
   }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Sat {
   pub block: u32,
+  #[schemars(with = "Vec<String>")]
   pub charms: Vec<Charm>,
   pub cycle: u32,
   pub decimal: String,
@@ -202,31 +294,123 @@ pub struct Sat {
   pub number: u64,
   pub offset: u64,
   pub percentile: String,
+  #[schemars(with = "String")]
   pub rarity: Rarity,
+  #[schemars(with = "Option<String>")]
   pub satpoint: Option<SatPoint>,
   pub timestamp: i64,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SatInscription {
   pub id: Option<InscriptionId>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SatInscriptions {
   pub ids: Vec<InscriptionId>,
   pub more: bool,
   pub page: u64,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AddressHistory {
+  pub more: bool,
+  pub page: usize,
+  pub transactions: Vec<AddressTransaction>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct AddressTransaction {
+  pub height: u32,
+  pub inscriptions: Vec<InscriptionId>,
+  pub received: u64,
+  #[schemars(with = "BTreeMap<String, schema::Pile>")]
+  pub runes: BTreeMap<SpacedRune, Pile>,
+  pub sent: u64,
+  #[schemars(with = "String")]
+  pub txid: Txid,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AddressInfo {
+  #[schemars(with = "Vec<String>")]
   pub outputs: Vec<OutPoint>,
   pub inscriptions: Vec<InscriptionId>,
   pub sat_balance: u64,
+  #[schemars(with = "Vec<(String, String, Option<char>)>")]
   pub runes_balances: Vec<(SpacedRune, Decimal, Option<char>)>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Rune {
+  pub entry: RuneEntry,
+  #[schemars(with = "String")]
+  pub id: RuneId,
+  pub mintable: bool,
+  pub parent: Option<InscriptionId>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RuneActivity {
+  pub activity: Vec<RuneBlockActivity>,
+  #[schemars(with = "String")]
+  pub id: RuneId,
+  pub more: bool,
+  pub next_height: Option<u32>,
+  #[schemars(with = "String")]
+  pub rune: SpacedRune,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy, JsonSchema)]
+pub struct RuneBlockActivity {
+  pub burned: u128,
+  pub height: u32,
+  pub minted: u128,
+  pub mints: u64,
+  pub transferred: u128,
+}
+
+/// Holders are addresses if the server has an address index, and outputs
+/// otherwise. Script pubkeys which are not addresses are shown as is.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuneHolder {
+  Address(String),
+  Output(#[schemars(with = "String")] OutPoint),
+  ScriptPubkey(#[schemars(with = "String")] ScriptBuf),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[repr(transparent)]
-pub struct AddressRunes(pub Vec<(SpacedRune, Decimal, Option<char>)>);
+pub struct AddressRunes(
+  #[schemars(with = "Vec<(String, String, Option<char>)>")]
+  pub  Vec<(SpacedRune, Decimal, Option<char>)>,
+);
+
+/// Schemas for foreign types which are not serialized as strings.
+#[allow(dead_code)]
+pub(crate) mod schema {
+  use super::*;
+
+  #[derive(JsonSchema)]
+  pub(crate) struct Duration {
+    pub(crate) secs: u64,
+    pub(crate) nanos: u32,
+  }
+
+  #[derive(JsonSchema)]
+  pub(crate) struct Pile {
+    pub(crate) amount: u128,
+    pub(crate) divisibility: u8,
+    pub(crate) symbol: Option<char>,
+  }
+
+  #[derive(JsonSchema)]
+  pub(crate) struct Terms {
+    pub(crate) amount: Option<u128>,
+    pub(crate) cap: Option<u128>,
+    pub(crate) height: (Option<u64>, Option<u64>),
+    pub(crate) offset: (Option<u64>, Option<u64>),
+  }
+}
@@ -2,11 +2,13 @@ use {
   self::{
     entry::{
       Entry, HeaderValue, InscriptionEntry, InscriptionEntryValue, InscriptionIdValue,
-      OutPointValue, RuneEntryValue, RuneIdValue, SatPointValue, SatRange, TxidValue,
+      OutPointValue, PartialContentValue, RuneEntryValue, RuneIdValue, SatPointValue, SatRange,
+      TxidValue,
     },
     event::Event,
     lot::Lot,
     reorg::Reorg,
+    undo::{UndoLog, UndoMultimapTable, UndoTable},
     updater::Updater,
     utxo_entry::{ParsedUtxoEntry, UtxoEntry, UtxoEntryBuf},
   },
@@ -16,13 +18,17 @@ use {
     subcommand::{find::FindRangeOutput, server::query},
     templates::StatusHtml,
   },
-  bellscoin::block::Header,
+  bellscoin::block::{AuxPow, Header, HeaderWithoutAuxPow},
   bellscoincore_rpc::{
     json::{GetBlockHeaderResult, GetBlockStatsResult},
     Client,
   },
   chrono::SubsecRound,
-  entry::{OutPointsValue, PartialValue},
+  entry::{
+    AddressHistoryEntry, AddressHistoryEntryValue, InscriptionTransferEntry,
+    InscriptionTransferEntryValue, OutPointsValue, PartialValue, RuneActivityEntry,
+    RuneActivityEntryValue,
+  },
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
   partials::{InscriptionParser, ParsedInscription},
@@ -32,50 +38,76 @@ use {
     StorageError, Table, TableDefinition, TableHandle, TableStats, WriteTransaction,
   },
   std::{
-    collections::HashMap,
+    collections::{BinaryHeap, HashMap},
     io::{BufWriter, Write},
+    ops::Bound,
     sync::Once,
   },
 };
 
-pub use self::entry::RuneEntry;
+pub use self::{
+  entry::RuneEntry,
+  export::{ChildRecord, InscriptionRecord, RuneBalanceRecord, RuneRecord, Tabular},
+  snapshot::Snapshot,
+};
 
 pub(crate) mod entry;
 pub mod event;
+mod export;
 mod fetcher;
+mod fulltext;
 mod lot;
 mod reorg;
 mod rtx;
+mod snapshot;
+mod undo;
 mod updater;
 mod utxo_entry;
 
 #[cfg(test)]
 pub(crate) mod testing;
 
-const SCHEMA_VERSION: u64 = 28;
+const SCHEMA_VERSION: u64 = 41;
 
+define_multimap_table! { RUNE_ID_TO_BALANCE_AND_OUTPOINT, RuneIdValue, (u128, OutPointValue) }
+define_multimap_table! { RUNE_ID_TO_BALANCE_AND_SCRIPT_PUBKEY, RuneIdValue, (u128, &[u8]) }
 define_multimap_table! { SAT_TO_SEQUENCE_NUMBER, u64, u32 }
 define_multimap_table! { SEQUENCE_NUMBER_TO_CHILDREN, u32, u32 }
+define_multimap_table! { SHA256_TO_SEQUENCE_NUMBER, &[u8; 32], u32 }
 define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], OutPointValue }
+define_table! { SCRIPT_PUBKEY_HEIGHT_AND_INDEX_TO_HISTORY, (&[u8], u32, u32), AddressHistoryEntryValue }
+define_table! { CHARM_AND_SEQUENCE_NUMBER, (u8, u32), () }
+define_table! { CONTENT_TYPE_AND_SEQUENCE_NUMBER, (&[u8], u32), () }
+define_table! { HEIGHT_AND_INDEX_TO_EVENT, (u32, u32), &[u8] }
+define_table! { HEIGHT_AND_INDEX_TO_UNDO_RECORD, (u32, u32), &[u8] }
 define_table! { HEIGHT_TO_BLOCK_HEADER, u32, HeaderValue }
 define_table! { HEIGHT_TO_LAST_SEQUENCE_NUMBER, u32, u32 }
 define_table! { HOME_INSCRIPTIONS, u32, InscriptionIdValue }
 define_table! { INSCRIPTION_ID_TO_SEQUENCE_NUMBER, InscriptionIdValue, u32 }
 define_table! { INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER, i32, u32 }
+define_table! { METAPROTOCOL_AND_SEQUENCE_NUMBER, (&[u8], u32), () }
 define_table! { OUTPOINT_TO_RUNE_BALANCES, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_UTXO_ENTRY, &OutPointValue, &UtxoEntry }
+define_table! { RARITY_AND_SEQUENCE_NUMBER, (u8, u32), () }
+define_table! { RUNE_ID_AND_HEIGHT_TO_ACTIVITY, (RuneIdValue, u32), RuneActivityEntryValue }
+define_table! { RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE, (RuneIdValue, &[u8]), u128 }
 define_table! { RUNE_ID_TO_RUNE_ENTRY, RuneIdValue, RuneEntryValue }
 define_table! { RUNE_TO_RUNE_ID, u128, RuneIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_table! { SAVEPOINT_ID_TO_BLOCK_COUNT, u64, u32 }
+define_table! { SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER, (u32, u32), InscriptionTransferEntryValue }
 define_table! { SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY, u32, InscriptionEntryValue }
 define_table! { SEQUENCE_NUMBER_TO_RUNE_ID, u32, RuneIdValue }
 define_table! { SEQUENCE_NUMBER_TO_SATPOINT, u32, &SatPointValue }
+define_table! { SEQUENCE_NUMBER_TO_TERM_COUNT, u32, u32 }
 define_table! { STATISTIC_TO_COUNT, u64, u64 }
+define_table! { TERM_AND_SEQUENCE_NUMBER_TO_FREQUENCY, (&str, u32), u32 }
 define_table! { TRANSACTION_ID_TO_RUNE, &TxidValue, u128 }
 define_table! { TRANSACTION_ID_TO_TRANSACTION, &TxidValue, &[u8] }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u32, u128 }
 define_table! { PARTIAL_OUTPOINT_TO_PARTIALS, &OutPointValue, PartialValue }
 define_table! { INSCRIPTION_ID_TO_OUTPOINTS, InscriptionIdValue, OutPointsValue }
+define_table! { INSCRIPTION_ID_TO_PARTIAL_CONTENT, InscriptionIdValue, PartialContentValue }
 
 #[derive(Copy, Clone)]
 pub(crate) enum Statistic {
@@ -95,6 +127,12 @@ pub(crate) enum Statistic {
   Runes = 13,
   SatRanges = 14,
   UnboundInscriptions = 16,
+  IndexPartials = 17,
+  IndexEvents = 18,
+  IndexAuxpow = 19,
+  IndexAddressHistory = 20,
+  IndexText = 21,
+  TextTerms = 22,
 }
 
 impl Statistic {
@@ -117,15 +155,22 @@ pub struct Info {
   index_file_size: u64,
   index_path: PathBuf,
   leaf_pages: u64,
+  max_savepoints: u32,
   metadata_bytes: u64,
   outputs_traversed: u64,
   page_size: usize,
   sat_ranges: u64,
+  savepoint_bytes_upper_bound: u64,
+  savepoint_interval: u32,
+  savepoints: u64,
   stored_bytes: u64,
   tables: BTreeMap<String, TableInfo>,
   total_bytes: u64,
   pub transactions: Vec<TransactionInfo>,
   tree_height: u32,
+  undo_log_bytes: u64,
+  undo_log_depth: u32,
+  undo_log_records: u64,
   utxos_indexed: u64,
 }
 
@@ -162,6 +207,78 @@ pub struct TransactionInfo {
   pub starting_timestamp: u128,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rollback {
+  pub blocks_indexed: u32,
+  pub blocks_removed: u32,
+  pub tables: BTreeMap<String, TableChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableChange {
+  pub entries_before: u64,
+  pub entries_after: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct InscriptionFilter {
+  pub charm: Option<Charm>,
+  pub content_type: Option<String>,
+  pub from_height: Option<u32>,
+  pub metaprotocol: Option<String>,
+  pub rarity: Option<Rarity>,
+  pub to_height: Option<u32>,
+}
+
+impl InscriptionFilter {
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Query string that reproduces this filter, with leading `?`, or the
+  /// empty string if nothing is filtered.
+  pub fn query_string(&self) -> String {
+    let mut parameters = Vec::new();
+
+    if let Some(charm) = self.charm {
+      parameters.push(format!("charm={charm}"));
+    }
+
+    if let Some(content_type) = &self.content_type {
+      parameters.push(format!(
+        "content_type={}",
+        urlencoding::encode(content_type)
+      ));
+    }
+
+    if let Some(from_height) = self.from_height {
+      parameters.push(format!("from_height={from_height}"));
+    }
+
+    if let Some(metaprotocol) = &self.metaprotocol {
+      parameters.push(format!(
+        "metaprotocol={}",
+        urlencoding::encode(metaprotocol)
+      ));
+    }
+
+    if let Some(rarity) = self.rarity {
+      parameters.push(format!("rarity={rarity}"));
+    }
+
+    if let Some(to_height) = self.to_height {
+      parameters.push(format!("to_height={to_height}"));
+    }
+
+    if parameters.is_empty() {
+      String::new()
+    } else {
+      format!("?{}", parameters.join("&"))
+    }
+  }
+}
+
 pub(crate) trait BitcoinCoreRpcResultExt<T> {
   fn into_option(self) -> Result<Option<T>>;
 }
@@ -202,10 +319,15 @@ pub struct Index {
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u32>,
+  index_address_history: bool,
   index_addresses: bool,
+  index_auxpow: bool,
+  index_events: bool,
   index_inscriptions: bool,
+  index_partials: bool,
   index_runes: bool,
   index_sats: bool,
+  index_text: bool,
   index_transactions: bool,
   path: PathBuf,
   settings: Settings,
@@ -308,22 +430,39 @@ impl Index {
 
         tx.set_durability(durability);
 
+        tx.open_multimap_table(RUNE_ID_TO_BALANCE_AND_OUTPOINT)?;
+        tx.open_multimap_table(RUNE_ID_TO_BALANCE_AND_SCRIPT_PUBKEY)?;
         tx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
         tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
         tx.open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?;
+        tx.open_multimap_table(SHA256_TO_SEQUENCE_NUMBER)?;
+        tx.open_table(CHARM_AND_SEQUENCE_NUMBER)?;
+        tx.open_table(CONTENT_TYPE_AND_SEQUENCE_NUMBER)?;
+        tx.open_table(HEIGHT_AND_INDEX_TO_EVENT)?;
+        tx.open_table(HEIGHT_AND_INDEX_TO_UNDO_RECORD)?;
         tx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
         tx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
         tx.open_table(HOME_INSCRIPTIONS)?;
+        tx.open_table(INSCRIPTION_ID_TO_PARTIAL_CONTENT)?;
         tx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
+        tx.open_table(METAPROTOCOL_AND_SEQUENCE_NUMBER)?;
         tx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
         tx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+        tx.open_table(RARITY_AND_SEQUENCE_NUMBER)?;
+        tx.open_table(RUNE_ID_AND_HEIGHT_TO_ACTIVITY)?;
+        tx.open_table(RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE)?;
         tx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
         tx.open_table(RUNE_TO_RUNE_ID)?;
         tx.open_table(SAT_TO_SATPOINT)?;
+        tx.open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?;
+        tx.open_table(SCRIPT_PUBKEY_HEIGHT_AND_INDEX_TO_HISTORY)?;
+        tx.open_table(SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?;
         tx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
         tx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+        tx.open_table(SEQUENCE_NUMBER_TO_TERM_COUNT)?;
+        tx.open_table(TERM_AND_SEQUENCE_NUMBER_TO_FREQUENCY)?;
         tx.open_table(TRANSACTION_ID_TO_RUNE)?;
         tx.open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?;
 
@@ -333,7 +472,13 @@ impl Index {
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexAddresses,
-            u64::from(settings.index_addresses_raw()),
+            u64::from(settings.index_addresses_raw() || settings.index_address_history_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexAddressHistory,
+            u64::from(settings.index_address_history_raw()),
           )?;
 
           Self::set_statistic(
@@ -342,6 +487,24 @@ impl Index {
             u64::from(settings.index_inscriptions_raw()),
           )?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexAuxpow,
+            u64::from(settings.index_auxpow_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexEvents,
+            u64::from(settings.index_events_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexPartials,
+            u64::from(settings.index_partials_raw()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexRunes,
@@ -354,6 +517,12 @@ impl Index {
             u64::from(settings.index_sats_raw()),
           )?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexText,
+            u64::from(settings.index_text_raw()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexTransactions,
@@ -411,19 +580,29 @@ impl Index {
       Err(error) => bail!("failed to open index: {error}"),
     };
 
+    let index_address_history;
     let index_addresses;
+    let index_auxpow;
+    let index_events;
+    let index_partials;
     let index_runes;
     let index_sats;
+    let index_text;
     let index_transactions;
     let index_inscriptions;
 
     {
       let tx = database.begin_read()?;
       let statistics = tx.open_table(STATISTIC_TO_COUNT)?;
+      index_address_history = Self::is_statistic_set(&statistics, Statistic::IndexAddressHistory)?;
       index_addresses = Self::is_statistic_set(&statistics, Statistic::IndexAddresses)?;
+      index_auxpow = Self::is_statistic_set(&statistics, Statistic::IndexAuxpow)?;
+      index_events = Self::is_statistic_set(&statistics, Statistic::IndexEvents)?;
       index_inscriptions = Self::is_statistic_set(&statistics, Statistic::IndexInscriptions)?;
+      index_partials = Self::is_statistic_set(&statistics, Statistic::IndexPartials)?;
       index_runes = Self::is_statistic_set(&statistics, Statistic::IndexRunes)?;
       index_sats = Self::is_statistic_set(&statistics, Statistic::IndexSats)?;
+      index_text = Self::is_statistic_set(&statistics, Statistic::IndexText)?;
       index_transactions = Self::is_statistic_set(&statistics, Statistic::IndexTransactions)?;
     }
 
@@ -449,9 +628,14 @@ impl Index {
       first_index_height,
       genesis_block_coinbase_transaction,
       height_limit: settings.height_limit(),
+      index_address_history,
       index_addresses,
+      index_auxpow,
+      index_events,
+      index_partials,
       index_runes,
       index_sats,
+      index_text,
       index_transactions,
       index_inscriptions,
       settings: settings.clone(),
@@ -475,7 +659,7 @@ impl Index {
   }
 
   #[cfg(test)]
-  fn set_durability(&mut self, durability: redb::Durability) {
+  pub(crate) fn set_durability(&mut self, durability: redb::Durability) {
     self.durability = durability;
   }
 
@@ -490,6 +674,29 @@ impl Index {
     )
   }
 
+  /// The script pubkey stored in the UTXO entry of `outpoint`, if addresses
+  /// are indexed and `outpoint` is unspent.
+  pub fn get_script_pubkey_for_output(&self, outpoint: OutPoint) -> Result<Option<ScriptBuf>> {
+    if !self.index_addresses {
+      return Ok(None);
+    }
+
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(OUTPOINT_TO_UTXO_ENTRY)?
+        .get(&outpoint.store())?
+        .map(|utxo_entry| {
+          ScriptBuf::from_bytes(utxo_entry.value().parse(self).script_pubkey().to_vec())
+        }),
+    )
+  }
+
+  pub fn has_address_history_index(&self) -> bool {
+    self.index_address_history
+  }
+
   pub fn has_address_index(&self) -> bool {
     self.index_addresses
   }
@@ -498,10 +705,26 @@ impl Index {
     self.index_inscriptions
   }
 
+  pub fn has_auxpow_index(&self) -> bool {
+    self.index_auxpow
+  }
+
+  pub fn has_event_index(&self) -> bool {
+    self.index_events
+  }
+
+  pub fn has_partial_index(&self) -> bool {
+    self.index_partials
+  }
+
   pub fn has_rune_index(&self) -> bool {
     self.index_runes
   }
 
+  pub fn has_text_index(&self) -> bool {
+    self.index_text
+  }
+
   pub fn has_sat_index(&self) -> bool {
     self.index_sats
   }
@@ -558,7 +781,11 @@ impl Index {
   }
 
   pub fn info(&self) -> Result<Info> {
-    let stats = self.database.begin_write()?.stats()?;
+    let (stats, savepoints) = {
+      let wtx = self.database.begin_write()?;
+      let savepoints = wtx.list_persistent_savepoints()?.count();
+      (wtx.stats()?, u64::try_from(savepoints)?)
+    };
 
     let rtx = self.database.begin_read()?;
 
@@ -593,6 +820,23 @@ impl Index {
       table_info.proportion = table_info.total_bytes as f64 / total_bytes as f64
     });
 
+    let index_file_size = fs::metadata(&self.path)?.len();
+
+    // pages which are not reachable from the current tree are either free or
+    // kept alive by a persistent savepoint, and redb does not report which, so
+    // this is only an upper bound on what savepoints cost
+    let savepoint_bytes_upper_bound = if savepoints > 0 {
+      index_file_size
+        .saturating_sub(stats.stored_bytes() + stats.metadata_bytes() + stats.fragmented_bytes())
+    } else {
+      0
+    };
+
+    let undo_log_bytes = tables
+      .get(HEIGHT_AND_INDEX_TO_UNDO_RECORD.name())
+      .map(|table_info| table_info.total_bytes)
+      .unwrap_or_default();
+
     let info = {
       let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
       let sat_ranges = statistic_to_count
@@ -614,12 +858,16 @@ impl Index {
           .unwrap_or(0),
         branch_pages: stats.branch_pages(),
         fragmented_bytes: stats.fragmented_bytes(),
-        index_file_size: fs::metadata(&self.path)?.len(),
+        index_file_size,
         leaf_pages: stats.leaf_pages(),
+        max_savepoints: self.settings.max_savepoints(),
         metadata_bytes: stats.metadata_bytes(),
         sat_ranges,
         outputs_traversed,
         page_size: stats.page_size(),
+        savepoint_bytes_upper_bound,
+        savepoint_interval: self.settings.savepoint_interval(),
+        savepoints,
         stored_bytes: stats.stored_bytes(),
         total_bytes,
         tables,
@@ -636,6 +884,13 @@ impl Index {
           })
           .collect(),
         tree_height: stats.tree_height(),
+        undo_log_bytes,
+        undo_log_depth: self.settings.undo_log_depth(),
+        undo_log_records: match rtx.open_table(HEIGHT_AND_INDEX_TO_UNDO_RECORD) {
+          Ok(undo_records) => undo_records.len()?,
+          Err(redb::TableError::TableDoesNotExist(_)) => 0,
+          Err(err) => return Err(err.into()),
+        },
         utxos_indexed: rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?.len()?,
       }
     };
@@ -643,6 +898,82 @@ impl Index {
     Ok(info)
   }
 
+  /// Depth of the deepest reorg that savepoints or the undo log can undo.
+  pub(crate) fn max_recoverable_reorg_depth(&self) -> u32 {
+    self
+      .settings
+      .max_savepoints()
+      .saturating_mul(self.settings.savepoint_interval())
+      .max(self.settings.undo_log_depth())
+  }
+
+  /// Rolls the index back so that it contains no blocks at or above `height`,
+  /// after which the next update re-syncs forward from wherever the rollback
+  /// landed. Tables whose contents changed are reported.
+  pub fn rollback(&self, height: u32) -> Result<Rollback> {
+    let block_count = self.block_count()?;
+
+    ensure!(
+      height < block_count,
+      "cannot roll back to height {height}, index only contains {block_count} blocks"
+    );
+
+    let before = self.table_summaries()?;
+
+    let blocks_indexed = Reorg::rollback(self, height)?;
+
+    let after = self.table_summaries()?;
+
+    let tables = before
+      .keys()
+      .chain(after.keys())
+      .filter(|name| before.get(*name) != after.get(*name))
+      .map(|name| {
+        let entries = |summaries: &BTreeMap<String, (u64, u64)>| {
+          summaries
+            .get(name)
+            .map(|(entries, _stored_bytes)| *entries)
+            .unwrap_or_default()
+        };
+
+        (
+          name.clone(),
+          TableChange {
+            entries_before: entries(&before),
+            entries_after: entries(&after),
+          },
+        )
+      })
+      .collect();
+
+    Ok(Rollback {
+      blocks_indexed,
+      blocks_removed: block_count - blocks_indexed,
+      tables,
+    })
+  }
+
+  /// Returns the number of entries and stored bytes of each table.
+  fn table_summaries(&self) -> Result<BTreeMap<String, (u64, u64)>> {
+    let rtx = self.database.begin_read()?;
+
+    let mut summaries = BTreeMap::new();
+
+    for handle in rtx.list_tables()? {
+      let name = handle.name().into();
+      let table = rtx.open_untyped_table(handle)?;
+      summaries.insert(name, (table.len()?, table.stats()?.stored_bytes()));
+    }
+
+    for handle in rtx.list_multimap_tables()? {
+      let name = handle.name().into();
+      let table = rtx.open_untyped_multimap_table(handle)?;
+      summaries.insert(name, (table.len()?, table.stats()?.stored_bytes()));
+    }
+
+    Ok(summaries)
+  }
+
   pub fn update(&self) -> Result {
     loop {
       let wtx = self.begin_write()?;
@@ -723,37 +1054,7 @@ impl Index {
       )?;
 
       if include_addresses {
-        let address = if satpoint.outpoint == unbound_outpoint() {
-          "unbound".to_string()
-        } else {
-          let script_pubkey = if self.index_addresses {
-            ScriptBuf::from_bytes(
-              outpoint_to_utxo_entry
-                .get(&satpoint.outpoint.store())?
-                .unwrap()
-                .value()
-                .parse(self)
-                .script_pubkey()
-                .to_vec(),
-            )
-          } else {
-            self
-              .get_transaction(satpoint.outpoint.txid)?
-              .unwrap()
-              .output
-              .into_iter()
-              .nth(satpoint.outpoint.vout.try_into().unwrap())
-              .unwrap()
-              .script_pubkey
-          };
-
-          self
-            .settings
-            .chain()
-            .address_from_script(&script_pubkey)
-            .map(|address| address.to_string())
-            .unwrap_or_else(|e| e.to_string())
-        };
+        let address = self.export_address(&outpoint_to_utxo_entry, satpoint)?;
         write!(writer, "\t{}", address)?;
       }
       writeln!(writer)?;
@@ -865,6 +1166,38 @@ impl Index {
     Ok(blocks)
   }
 
+  /// Returns logged events starting at `from_height`, whether more events
+  /// remain, and the height to resume from. Blocks are never split across
+  /// pages, so a page may hold more than `limit` events.
+  pub fn get_events(&self, from_height: u32, limit: usize) -> Result<(Vec<Event>, bool, u32)> {
+    let rtx = self.begin_read()?;
+
+    let block_count = rtx.block_count()?;
+
+    let mut events = Vec::new();
+    let mut last_height = None;
+
+    for entry in rtx
+      .0
+      .open_table(HEIGHT_AND_INDEX_TO_EVENT)?
+      .range((from_height, 0)..)?
+    {
+      let (key, event) = entry?;
+      let (height, _index) = key.value();
+
+      if let Some(last_height) = last_height {
+        if events.len() >= limit && height != last_height {
+          return Ok((events, true, last_height + 1));
+        }
+      }
+
+      events.push(serde_json::from_slice(event.value())?);
+      last_height = Some(height);
+    }
+
+    Ok((events, false, block_count.max(from_height)))
+  }
+
   pub fn rare_sat_satpoints(&self) -> Result<Vec<(Sat, SatPoint)>> {
     let rtx = self.database.begin_read()?;
 
@@ -1487,6 +1820,20 @@ impl Index {
   ) -> Result<Option<Inscription>> {
     let rtx = self.database.begin_read()?;
 
+    if self.index_partials {
+      if let Some((content_type, body)) = rtx
+        .open_table(INSCRIPTION_ID_TO_PARTIAL_CONTENT)?
+        .get(&inscription_id.store())?
+        .map(|content| content.value())
+      {
+        return Ok(Some(Inscription {
+          content_type: Some(content_type),
+          body: Some(body),
+          ..default()
+        }));
+      }
+    }
+
     let Some(partial_txs) = rtx
       .open_table(INSCRIPTION_ID_TO_OUTPOINTS)?
       .get(&inscription_id.store())?
@@ -1613,6 +1960,15 @@ impl Index {
   }
 
   pub fn get_transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    if let Some(transaction) = self.get_stored_transaction(txid)? {
+      return Ok(Some(transaction));
+    }
+
+    self.client.get_raw_transaction(&txid, None).into_option()
+  }
+
+  /// Returns the transaction if it is available without asking the node.
+  fn get_stored_transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
     if txid == self.genesis_block_coinbase_txid {
       return Ok(Some(self.genesis_block_coinbase_transaction.clone()));
     }
@@ -1628,7 +1984,7 @@ impl Index {
       }
     }
 
-    self.client.get_raw_transaction(&txid, None).into_option()
+    Ok(None)
   }
 
   pub fn get_transactions(
@@ -1647,7 +2003,7 @@ impl Index {
     let sat = sat.0;
     let rtx = self.begin_read()?;
 
-    if rtx.block_count()? <= Sat(sat).height().n() {
+    if rtx.block_count()? <= Sat(sat).height(self.settings.chain().network()).n() {
       return Ok(None);
     }
 
@@ -1682,7 +2038,12 @@ impl Index {
     let range_end = range_end.0;
     let rtx = self.begin_read()?;
 
-    if rtx.block_count()? < Sat(range_end - 1).height().n() + 1 {
+    if rtx.block_count()?
+      < Sat(range_end - 1)
+        .height(self.settings.chain().network())
+        .n()
+        + 1
+    {
       return Ok(None);
     }
 
@@ -1798,6 +2159,19 @@ impl Index {
     Ok(true)
   }
 
+  /// Returns the AuxPoW of the block at `height`, if the index was created
+  /// with `--index-auxpow` and the block was merge-mined.
+  pub fn block_auxpow(&self, height: u32) -> Result<Option<AuxPow>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .get(height)?
+        .and_then(|header| Header::load(header.value()).auxpow),
+    )
+  }
+
   pub fn block_time(&self, height: Height) -> Result<Blocktime> {
     let height = height.n();
 
@@ -1834,9 +2208,14 @@ impl Index {
 
   pub fn get_inscriptions_paginated(
     &self,
+    filter: &InscriptionFilter,
     page_size: u32,
     page_index: u32,
   ) -> Result<(Vec<InscriptionId>, bool)> {
+    if !filter.is_empty() {
+      return self.get_filtered_inscriptions_paginated(filter, page_size, page_index);
+    }
+
     let rtx = self.database.begin_read()?;
 
     let sequence_number_to_inscription_entry =
@@ -1868,102 +2247,457 @@ impl Index {
     Ok((inscriptions, more))
   }
 
-  pub fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
+  fn get_filtered_inscriptions_paginated(
+    &self,
+    filter: &InscriptionFilter,
+    page_size: u32,
+    page_index: u32,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
     let rtx = self.database.begin_read()?;
 
+    let charm_and_sequence_number = rtx.open_table(CHARM_AND_SEQUENCE_NUMBER)?;
+    let content_type_and_sequence_number = rtx.open_table(CONTENT_TYPE_AND_SEQUENCE_NUMBER)?;
     let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+    let metaprotocol_and_sequence_number = rtx.open_table(METAPROTOCOL_AND_SEQUENCE_NUMBER)?;
+    let rarity_and_sequence_number = rtx.open_table(RARITY_AND_SEQUENCE_NUMBER)?;
     let sequence_number_to_inscription_entry =
       rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
 
-    let Some(newest_sequence_number) = height_to_last_sequence_number
-      .get(&block_height)?
-      .map(|ag| ag.value())
-    else {
-      return Ok(Vec::new());
+    // `HEIGHT_TO_LAST_SEQUENCE_NUMBER` holds the next sequence number after
+    // each block, so the block range maps to a half-open sequence number range
+    let start = match filter.from_height {
+      Some(from_height) => height_to_last_sequence_number
+        .range(..from_height)?
+        .next_back()
+        .transpose()?
+        .map(|(_height, sequence_number)| sequence_number.value())
+        .unwrap_or_default(),
+      None => 0,
     };
 
-    let oldest_sequence_number = height_to_last_sequence_number
-      .get(block_height.saturating_sub(1))?
-      .map(|ag| ag.value())
-      .unwrap_or(0);
+    let end = match filter.to_height {
+      Some(to_height) => height_to_last_sequence_number
+        .range(..=to_height)?
+        .next_back()
+        .transpose()?
+        .map(|(_height, sequence_number)| sequence_number.value())
+        .unwrap_or_default(),
+      None => sequence_number_to_inscription_entry
+        .last()?
+        .map(|(sequence_number, _entry)| sequence_number.value() + 1)
+        .unwrap_or_default(),
+    };
 
-    (oldest_sequence_number..newest_sequence_number)
-      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
-        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
-        Ok(None) => Err(anyhow!(
-          "could not find inscription for inscription number {num}"
-        )),
-        Err(err) => Err(anyhow!(err)),
-      })
-      .collect::<Result<Vec<InscriptionId>>>()
-  }
+    if start >= end {
+      return Ok((Vec::new(), false));
+    }
 
-  pub fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
-    let rtx = self.database.begin_read()?;
+    let mut content_types = Vec::<Vec<u8>>::new();
 
-    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    if let Some(prefix) = &filter.content_type {
+      let prefix = prefix.as_bytes();
 
-    let min_id = RuneId {
-      block: block_height,
-      tx: 0,
-    };
+      loop {
+        let lower = match content_types.last() {
+          Some(content_type) => Bound::Excluded((content_type.as_slice(), u32::MAX)),
+          None => Bound::Included((prefix, 0)),
+        };
 
-    let max_id = RuneId {
-      block: block_height,
-      tx: u32::MAX,
-    };
+        let Some(content_type) = content_type_and_sequence_number
+          .range::<(&[u8], u32)>((lower, Bound::Unbounded))?
+          .next()
+          .transpose()?
+          .map(|(key, _value)| key.value().0.to_vec())
+        else {
+          break;
+        };
 
-    let runes = rune_id_to_rune_entry
-      .range(min_id.store()..=max_id.store())?
-      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
-      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+        if !content_type.starts_with(prefix) {
+          break;
+        }
 
-    Ok(runes)
-  }
+        content_types.push(content_type);
+      }
+    }
 
-  pub fn get_highest_paying_inscriptions_in_block(
-    &self,
-    block_height: u32,
-    n: usize,
-  ) -> Result<(Vec<InscriptionId>, usize)> {
-    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+    // each indexed filter returns its newest sequence number at or below a
+    // bound, and at or above `start`
+    type Seek<'a> = Box<dyn Fn(u32) -> Result<Option<u32>, StorageError> + 'a>;
 
-    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
-    for id in &inscription_ids {
-      inscription_to_fee.push((
-        *id,
-        self
-          .get_inscription_entry(*id)?
-          .ok_or_else(|| anyhow!("could not get entry for inscription {id}"))?
-          .fee,
-      ));
-    }
+    let mut seeks = Vec::<Seek>::new();
 
-    inscription_to_fee.sort_by_key(|(_, fee)| *fee);
+    if filter.content_type.is_some() {
+      seeks.push(Box::new(|at| {
+        let mut newest = None;
 
-    Ok((
-      inscription_to_fee
-        .iter()
-        .map(|(id, _)| *id)
-        .rev()
-        .take(n)
-        .collect(),
-      inscription_ids.len(),
-    ))
-  }
+        for content_type in &content_types {
+          let sequence_number = content_type_and_sequence_number
+            .range((content_type.as_slice(), start)..=(content_type.as_slice(), at))?
+            .next_back()
+            .transpose()?
+            .map(|(key, _value)| key.value().1);
 
-  pub fn get_home_inscriptions(&self) -> Result<Vec<InscriptionId>> {
-    Ok(
-      self
-        .database
-        .begin_read()?
-        .open_table(HOME_INSCRIPTIONS)?
-        .iter()?
-        .rev()
-        .flat_map(|result| result.map(|(_number, id)| InscriptionId::load(id.value())))
-        .collect(),
-    )
-  }
+          newest = newest.max(sequence_number);
+        }
+
+        Ok(newest)
+      }));
+    }
+
+    if let Some(metaprotocol) = &filter.metaprotocol {
+      let metaprotocol = metaprotocol.as_bytes();
+
+      seeks.push(Box::new(move |at| {
+        Ok(
+          metaprotocol_and_sequence_number
+            .range((metaprotocol, start)..=(metaprotocol, at))?
+            .next_back()
+            .transpose()?
+            .map(|(key, _value)| key.value().1),
+        )
+      }));
+    }
+
+    if let Some(charm) = filter.charm {
+      let charm = charm as u8;
+
+      seeks.push(Box::new(move |at| {
+        Ok(
+          charm_and_sequence_number
+            .range((charm, start)..=(charm, at))?
+            .next_back()
+            .transpose()?
+            .map(|(key, _value)| key.value().1),
+        )
+      }));
+    }
+
+    if let Some(rarity) = filter.rarity {
+      let rarity = u8::from(rarity);
+
+      seeks.push(Box::new(move |at| {
+        Ok(
+          rarity_and_sequence_number
+            .range((rarity, start)..=(rarity, at))?
+            .next_back()
+            .transpose()?
+            .map(|(key, _value)| key.value().1),
+        )
+      }));
+    }
+
+    // newest sequence number at or below `at` which every filter matches,
+    // found by lowering `at` to each filter's match in turn until all agree
+    let matching = |mut at: u32| -> Result<Option<u32>, StorageError> {
+      let mut agreed = 0;
+
+      for seek in seeks.iter().cycle() {
+        let Some(sequence_number) = seek(at)? else {
+          return Ok(None);
+        };
+
+        if sequence_number == at {
+          agreed += 1;
+        } else {
+          at = sequence_number;
+          agreed = 1;
+        }
+
+        if agreed == seeks.len() {
+          return Ok(Some(at));
+        }
+      }
+
+      // height ranges are contiguous, so without other filters every
+      // sequence number matches
+      Ok(Some(at))
+    };
+
+    let mut skip = u64::from(page_size) * u64::from(page_index);
+
+    // sequence numbers are contiguous, so skipped pages of a height range
+    // need not be read
+    let mut next = if seeks.is_empty() {
+      let skipped = u32::try_from(skip).unwrap_or(u32::MAX);
+      skip = 0;
+      end.saturating_sub(skipped).checked_sub(1)
+    } else {
+      Some(end - 1)
+    };
+
+    let mut inscriptions = Vec::new();
+
+    while let Some(at) = next.filter(|at| *at >= start) {
+      let Some(sequence_number) = matching(at)? else {
+        break;
+      };
+
+      if skip > 0 {
+        skip -= 1;
+      } else {
+        inscriptions.push(
+          InscriptionEntry::load(
+            sequence_number_to_inscription_entry
+              .get(sequence_number)?
+              .unwrap()
+              .value(),
+          )
+          .id,
+        );
+
+        if inscriptions.len() > page_size.into_usize() {
+          break;
+        }
+      }
+
+      next = sequence_number.checked_sub(1);
+    }
+
+    let more = inscriptions.len() > page_size.into_usize();
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+
+  pub fn get_inscriptions_by_sha256(
+    &self,
+    sha256: sha256::Hash,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut inscriptions = rtx
+      .open_multimap_table(SHA256_TO_SEQUENCE_NUMBER)?
+      .get(&sha256.to_byte_array())?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result
+          .and_then(|sequence_number| {
+            sequence_number_to_inscription_entry
+              .get(sequence_number.value())
+              .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = inscriptions.len() > page_size;
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+
+  /// Returns a page of inscriptions whose text contains the words of
+  /// `query`, most relevant first, and whether there are more.
+  pub fn search_inscriptions(
+    &self,
+    query: &str,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+    let sequence_number_to_term_count = rtx.open_table(SEQUENCE_NUMBER_TO_TERM_COUNT)?;
+    let term_and_sequence_number_to_frequency =
+      rtx.open_table(TERM_AND_SEQUENCE_NUMBER_TO_FREQUENCY)?;
+
+    let documents = sequence_number_to_term_count.len()?;
+
+    if documents == 0 {
+      return Ok((Vec::new(), false));
+    }
+
+    let text_terms = rtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::TextTerms.key())?
+      .map(|text_terms| text_terms.value())
+      .unwrap_or_default();
+
+    let average_term_count = text_terms as f64 / documents as f64;
+
+    let mut scores = HashMap::<u32, f64>::new();
+
+    for term in fulltext::terms(query).collect::<BTreeSet<String>>() {
+      let postings = term_and_sequence_number_to_frequency
+        .range((term.as_str(), 0)..=(term.as_str(), u32::MAX))?
+        .rev()
+        .take(fulltext::MAX_POSTINGS)
+        .map(|result| result.map(|(key, frequency)| (key.value().1, frequency.value())))
+        .collect::<Result<Vec<(u32, u32)>, StorageError>>()?;
+
+      let matches = u64::try_from(postings.len()).unwrap();
+
+      for (sequence_number, frequency) in postings {
+        let term_count = sequence_number_to_term_count
+          .get(sequence_number)?
+          .map(|term_count| term_count.value())
+          .unwrap_or_default();
+
+        *scores.entry(sequence_number).or_default() += fulltext::score(
+          frequency,
+          term_count,
+          average_term_count,
+          documents,
+          matches,
+        );
+      }
+    }
+
+    // keep only the hits up to the end of the requested page, plus one to
+    // tell whether there are more, evicting the least relevant
+    let limit = page_index
+      .saturating_add(1)
+      .saturating_mul(page_size)
+      .saturating_add(1);
+
+    let mut hits = BinaryHeap::new();
+
+    for (sequence_number, score) in scores {
+      hits.push(fulltext::Hit {
+        score,
+        sequence_number,
+      });
+
+      if hits.len() > limit {
+        hits.pop();
+      }
+    }
+
+    let mut inscriptions = hits
+      .into_sorted_vec()
+      .into_iter()
+      .skip(page_index.saturating_mul(page_size))
+      .map(|hit| {
+        Ok(
+          InscriptionEntry::load(
+            sequence_number_to_inscription_entry
+              .get(hit.sequence_number)?
+              .unwrap()
+              .value(),
+          )
+          .id,
+        )
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = inscriptions.len() > page_size;
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+
+  pub fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let Some(newest_sequence_number) = height_to_last_sequence_number
+      .get(&block_height)?
+      .map(|ag| ag.value())
+    else {
+      return Ok(Vec::new());
+    };
+
+    let oldest_sequence_number = height_to_last_sequence_number
+      .get(block_height.saturating_sub(1))?
+      .map(|ag| ag.value())
+      .unwrap_or(0);
+
+    (oldest_sequence_number..newest_sequence_number)
+      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
+        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
+        Ok(None) => Err(anyhow!(
+          "could not find inscription for inscription number {num}"
+        )),
+        Err(err) => Err(anyhow!(err)),
+      })
+      .collect::<Result<Vec<InscriptionId>>>()
+  }
+
+  pub fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let min_id = RuneId {
+      block: block_height,
+      tx: 0,
+    };
+
+    let max_id = RuneId {
+      block: block_height,
+      tx: u32::MAX,
+    };
+
+    let runes = rune_id_to_rune_entry
+      .range(min_id.store()..=max_id.store())?
+      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
+      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+
+    Ok(runes)
+  }
+
+  pub fn get_highest_paying_inscriptions_in_block(
+    &self,
+    block_height: u32,
+    n: usize,
+  ) -> Result<(Vec<InscriptionId>, usize)> {
+    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+
+    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
+    for id in &inscription_ids {
+      inscription_to_fee.push((
+        *id,
+        self
+          .get_inscription_entry(*id)?
+          .ok_or_else(|| anyhow!("could not get entry for inscription {id}"))?
+          .fee,
+      ));
+    }
+
+    inscription_to_fee.sort_by_key(|(_, fee)| *fee);
+
+    Ok((
+      inscription_to_fee
+        .iter()
+        .map(|(id, _)| *id)
+        .rev()
+        .take(n)
+        .collect(),
+      inscription_ids.len(),
+    ))
+  }
+
+  pub fn get_home_inscriptions(&self) -> Result<Vec<InscriptionId>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(HOME_INSCRIPTIONS)?
+        .iter()?
+        .rev()
+        .flat_map(|result| result.map(|(_number, id)| InscriptionId::load(id.value())))
+        .collect(),
+    )
+  }
 
   pub fn get_feed_inscriptions(&self, n: usize) -> Result<Vec<(u32, InscriptionId)>> {
     Ok(
@@ -2156,6 +2890,29 @@ impl Index {
       inscription.content_type().map(str::to_string)
     };
 
+    let duplicate_of = match &inscription.body {
+      Some(body) => rtx
+        .open_multimap_table(SHA256_TO_SEQUENCE_NUMBER)?
+        .get(&sha256::Hash::hash(body).to_byte_array())?
+        .next()
+        .transpose()?
+        .map(|first| first.value())
+        .filter(|&first| first != sequence_number)
+        .map(|first| -> Result<InscriptionId> {
+          Ok(
+            InscriptionEntry::load(
+              sequence_number_to_inscription_entry
+                .get(first)?
+                .unwrap()
+                .value(),
+            )
+            .id,
+          )
+        })
+        .transpose()?,
+      None => None,
+    };
+
     Ok(Some((
       api::Inscription {
         address: output
@@ -2172,6 +2929,7 @@ impl Index {
         children,
         content_length: inscription.content_length(),
         content_type: inscription.content_type().map(|s| s.to_string()),
+        duplicate_of,
         effective_content_type: effective_mime_type,
         fee: entry.fee,
         height: entry.height,
@@ -2339,19 +3097,249 @@ impl Index {
       .collect()
   }
 
-  pub fn get_address_runes(
+  /// Returns a page of transactions that funded or spent outputs of
+  /// `address`, most recent first, and whether there are more.
+  pub fn get_address_history(
     &self,
     address: &Address,
-  ) -> Result<Vec<(SpacedRune, Decimal, Option<char>)>> {
-    let v = self
-      .database
-      .begin_read()?
-      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?
-      .get(address.script_pubkey().as_bytes())?
-      .map(|result| {
-        result
-          .map_err(|err| anyhow!(err))
-          .map(|value| OutPoint::load(value.value()))
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<api::AddressTransaction>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let history = rtx.open_table(SCRIPT_PUBKEY_HEIGHT_AND_INDEX_TO_HISTORY)?;
+    let id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let script_pubkey = address.script_pubkey();
+    let script_pubkey = script_pubkey.as_bytes();
+
+    let mut entries = history
+      .range((script_pubkey, 0, 0)..=(script_pubkey, u32::MAX, u32::MAX))?
+      .rev()
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result.map(|(key, entry)| (key.value().1, AddressHistoryEntry::load(entry.value())))
+      })
+      .collect::<Result<Vec<(u32, AddressHistoryEntry)>, StorageError>>()?;
+
+    let more = entries.len() > page_size;
+
+    entries.truncate(page_size);
+
+    let mut transactions = Vec::with_capacity(entries.len());
+
+    for (height, entry) in entries {
+      let mut inscriptions = Vec::with_capacity(entry.inscriptions.len());
+      for sequence_number in entry.inscriptions {
+        inscriptions.push(
+          InscriptionEntry::load(
+            sequence_number_to_inscription_entry
+              .get(sequence_number)?
+              .unwrap()
+              .value(),
+          )
+          .id,
+        );
+      }
+
+      let mut runes = BTreeMap::new();
+      for (id, amount) in entry.runes {
+        let rune_entry = RuneEntry::load(id_to_rune_entry.get(id.store())?.unwrap().value());
+        runes.insert(
+          rune_entry.spaced_rune,
+          Pile {
+            amount,
+            divisibility: rune_entry.divisibility,
+            symbol: rune_entry.symbol,
+          },
+        );
+      }
+
+      transactions.push(api::AddressTransaction {
+        height,
+        inscriptions,
+        received: entry.received,
+        runes,
+        sent: entry.sent,
+        txid: entry.txid,
+      });
+    }
+
+    Ok((transactions, more))
+  }
+
+  pub fn get_inscription_history(
+    &self,
+    inscription_id: InscriptionId,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<Option<(Vec<api::InscriptionTransfer>, bool)>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&inscription_id.store())?
+      .map(|guard| guard.value())
+    else {
+      return Ok(None);
+    };
+
+    let mut transfers = rtx
+      .open_table(SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?
+      .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result.map(|(_, entry)| {
+          let entry = InscriptionTransferEntry::load(entry.value());
+          api::InscriptionTransfer {
+            address: self
+              .settings
+              .chain()
+              .address_from_script(&entry.script_pubkey)
+              .ok()
+              .map(|address| address.to_string()),
+            height: entry.height,
+            new_satpoint: entry.new_satpoint,
+            old_satpoint: entry.old_satpoint,
+            txid: entry.txid,
+          }
+        })
+      })
+      .collect::<Result<Vec<api::InscriptionTransfer>, StorageError>>()?;
+
+    let more = transfers.len() > page_size;
+
+    transfers.truncate(page_size);
+
+    Ok(Some((transfers, more)))
+  }
+
+  /// Returns the activity of rune `id` in blocks with heights from `from` to
+  /// `to` inclusive, for at most `limit` blocks, and whether there is more
+  /// activity in the range.
+  pub fn get_rune_activity(
+    &self,
+    id: RuneId,
+    from: u32,
+    to: u32,
+    limit: usize,
+  ) -> Result<(Vec<api::RuneBlockActivity>, bool)> {
+    if from > to {
+      return Ok((Vec::new(), false));
+    }
+
+    let mut activity = self
+      .database
+      .begin_read()?
+      .open_table(RUNE_ID_AND_HEIGHT_TO_ACTIVITY)?
+      .range((id.store(), from)..=(id.store(), to))?
+      .take(limit.saturating_add(1))
+      .map(|result| {
+        result.map(|(key, entry)| {
+          let entry = RuneActivityEntry::load(entry.value());
+          api::RuneBlockActivity {
+            burned: entry.burned,
+            height: key.value().1,
+            minted: entry.minted,
+            mints: entry.mints,
+            transferred: entry.transferred,
+          }
+        })
+      })
+      .collect::<Result<Vec<api::RuneBlockActivity>, StorageError>>()?;
+
+    let more = activity.len() > limit;
+
+    activity.truncate(limit);
+
+    Ok((activity, more))
+  }
+
+  /// Returns a page of the holders of rune `id`, largest balance first, the
+  /// number of outputs holding it, the number of script pubkeys holding it if
+  /// addresses are indexed, and whether there are more holders.
+  pub fn get_rune_holders(
+    &self,
+    id: RuneId,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<(api::RuneHolder, u128)>, u64, Option<u64>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let outputs = rtx
+      .open_multimap_table(RUNE_ID_TO_BALANCE_AND_OUTPOINT)?
+      .get(id.store())?;
+
+    let output_count = outputs.len();
+
+    let (mut holders, address_count) = if self.index_addresses {
+      let script_pubkeys = rtx
+        .open_multimap_table(RUNE_ID_TO_BALANCE_AND_SCRIPT_PUBKEY)?
+        .get(id.store())?;
+
+      let address_count = script_pubkeys.len();
+
+      let holders = script_pubkeys
+        .rev()
+        .skip(page_index.saturating_mul(page_size))
+        .take(page_size.saturating_add(1))
+        .map(|result| {
+          result.map(|guard| {
+            let (balance, script_pubkey) = guard.value();
+
+            let script_pubkey = ScriptBuf::from_bytes(script_pubkey.to_vec());
+
+            let holder = match self.settings.chain().address_from_script(&script_pubkey) {
+              Ok(address) => api::RuneHolder::Address(address.to_string()),
+              Err(_) => api::RuneHolder::ScriptPubkey(script_pubkey),
+            };
+
+            (holder, balance)
+          })
+        })
+        .collect::<Result<Vec<(api::RuneHolder, u128)>, StorageError>>()?;
+
+      (holders, Some(address_count))
+    } else {
+      let holders = outputs
+        .rev()
+        .skip(page_index.saturating_mul(page_size))
+        .take(page_size.saturating_add(1))
+        .map(|result| {
+          result.map(|guard| {
+            let (balance, outpoint) = guard.value();
+            (api::RuneHolder::Output(OutPoint::load(outpoint)), balance)
+          })
+        })
+        .collect::<Result<Vec<(api::RuneHolder, u128)>, StorageError>>()?;
+
+      (holders, None)
+    };
+
+    let more = holders.len() > page_size;
+
+    holders.truncate(page_size);
+
+    Ok((holders, output_count, address_count, more))
+  }
+
+  pub fn get_address_runes(
+    &self,
+    address: &Address,
+  ) -> Result<Vec<(SpacedRune, Decimal, Option<char>)>> {
+    let v = self
+      .database
+      .begin_read()?
+      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?
+      .get(address.script_pubkey().as_bytes())?
+      .map(|result| {
+        result
+          .map_err(|err| anyhow!(err))
+          .map(|value| OutPoint::load(value.value()))
           .and_then(|outpoint| {
             self
               .get_rune_balances_for_output(outpoint)
@@ -3717,7 +4705,10 @@ mod tests {
 
       context.mine_blocks(1);
 
-      let (inscriptions, more) = context.index.get_inscriptions_paginated(100, 0).unwrap();
+      let (inscriptions, more) = context
+        .index
+        .get_inscriptions_paginated(&InscriptionFilter::default(), 100, 0)
+        .unwrap();
       assert_eq!(inscriptions, &[inscription_id]);
       assert!(!more);
     }
@@ -3744,117 +4735,477 @@ mod tests {
 
       assert_eq!(ids.len(), 100);
 
-      let (inscriptions, more) = context.index.get_inscriptions_paginated(100, 0).unwrap();
+      let (inscriptions, more) = context
+        .index
+        .get_inscriptions_paginated(&InscriptionFilter::default(), 100, 0)
+        .unwrap();
       assert_eq!(inscriptions, ids);
       assert!(more);
     }
   }
 
   #[test]
-  fn unrecognized_even_field_inscriptions_are_cursed_and_unbound() {
-    for context in Context::configurations() {
-      context.mine_blocks(1);
+  fn get_inscriptions_paginated_with_filter() {
+    let context = Context::builder().build();
 
-      let witness = envelope(&[
-        b"ord",
-        &[1],
-        b"text/plain;charset=utf-8",
-        &[2],
-        b"bar",
-        &[4],
-        b"ord",
-      ]);
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let mut ids = Vec::new();
 
+    for (i, inscription) in [
+      inscription("text/plain", "hello"),
+      inscription("image/png", [1; 100]),
+      Inscription {
+        content_type: Some("text/html".into()),
+        metaprotocol: Some("brc-20".into()),
+        body: Some("{}".into()),
+        ..default()
+      },
+    ]
+    .into_iter()
+    .enumerate()
+    {
       let txid = context.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0, witness)],
+        inputs: &[(i + 1, 0, 0, inscription.to_witness())],
         ..default()
       });
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+      ids.push(InscriptionId { txid, index: 0 });
+    }
 
-      let inscription_id = InscriptionId { txid, index: 0 };
+    let inscriptions = |filter: InscriptionFilter, page_size: u32, page_index: u32| {
+      context
+        .index
+        .get_inscriptions_paginated(&filter, page_size, page_index)
+        .unwrap()
+    };
 
-      context.mine_blocks(1);
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          content_type: Some("text/".into()),
+          ..default()
+        },
+        100,
+        0
+      ),
+      (vec![ids[2], ids[0]], false)
+    );
 
-      context.index.assert_inscription_location(
-        inscription_id,
-        SatPoint {
-          outpoint: unbound_outpoint(),
-          offset: 0,
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          metaprotocol: Some("brc-20".into()),
+          ..default()
         },
-        None,
-      );
+        100,
+        0
+      ),
+      (vec![ids[2]], false)
+    );
 
-      assert_eq!(context.index.inscription_number(inscription_id), -1);
-    }
-  }
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          content_type: Some("text/plain".into()),
+          metaprotocol: Some("brc-20".into()),
+          ..default()
+        },
+        100,
+        0
+      ),
+      (Vec::new(), false)
+    );
 
-  #[test]
-  fn unrecognized_even_field_inscriptions_are_unbound_after_jubilee() {
-    for context in Context::configurations() {
-      context.mine_blocks(109);
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          content_type: Some("text/".into()),
+          metaprotocol: Some("brc-20".into()),
+          ..default()
+        },
+        100,
+        0
+      ),
+      (vec![ids[2]], false)
+    );
 
-      let witness = envelope(&[
-        b"ord",
-        &[1],
-        b"text/plain;charset=utf-8",
-        &[2],
-        b"bar",
-        &[4],
-        b"ord",
-      ]);
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          from_height: Some(3),
+          ..default()
+        },
+        100,
+        0
+      ),
+      (vec![ids[2], ids[1]], false)
+    );
 
-      let txid = context.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0, witness)],
-        ..default()
-      });
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          from_height: Some(3),
+          to_height: Some(3),
+          ..default()
+        },
+        100,
+        0
+      ),
+      (vec![ids[1]], false)
+    );
 
-      let inscription_id = InscriptionId { txid, index: 0 };
+    assert_eq!(
+      inscriptions(
+        InscriptionFilter {
+          charm: Some(Charm::Cursed),
+          ..default()
+        },
+        100,
+        0
+      ),
+      (Vec::new(), false)
+    );
 
-      context.mine_blocks(1);
+    let filter = InscriptionFilter {
+      content_type: Some("text/".into()),
+      ..default()
+    };
 
-      context.index.assert_inscription_location(
-        inscription_id,
-        SatPoint {
-          outpoint: unbound_outpoint(),
-          offset: 0,
-        },
-        None,
-      );
+    assert_eq!(inscriptions(filter.clone(), 1, 0), (vec![ids[2]], true));
+    assert_eq!(inscriptions(filter, 1, 1), (vec![ids[0]], false));
 
-      assert_eq!(context.index.inscription_number(inscription_id), 0);
-    }
+    let filter = InscriptionFilter {
+      from_height: Some(2),
+      ..default()
+    };
+
+    assert_eq!(inscriptions(filter.clone(), 1, 1), (vec![ids[1]], true));
+    assert_eq!(inscriptions(filter.clone(), 1, 2), (vec![ids[0]], false));
+    assert_eq!(inscriptions(filter, 1, 3), (Vec::new(), false));
   }
 
   #[test]
-  fn inscriptions_are_uncursed_after_jubilee() {
-    for context in Context::configurations() {
-      context.mine_blocks(108);
+  fn get_inscriptions_paginated_with_charm_and_rarity_filters() {
+    let context = Context::builder().arg("--index-sats").build();
 
-      let witness = envelope(&[
-        b"ord",
-        &[1],
-        b"text/plain;charset=utf-8",
-        &[1],
-        b"text/plain;charset=utf-8",
-      ]);
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-      let txid = context.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0, witness.clone())],
-        ..default()
-      });
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
 
-      let inscription_id = InscriptionId { txid, index: 0 };
+    let uncommon = InscriptionId { txid, index: 0 };
 
-      context.mine_blocks(1);
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-      assert_eq!(context.core.height(), 109);
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, Witness::new())],
+      outputs: 2,
+      ..default()
+    });
 
-      assert_eq!(context.index.inscription_number(inscription_id), -1);
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-      let txid = context.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(2, 0, 0, witness)],
+    let common = InscriptionId {
+      txid: context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(3, 1, 1, inscription("text/plain", "world").to_witness())],
         ..default()
-      });
-
+      }),
+      index: 0,
+    };
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscriptions = |filter: InscriptionFilter| {
+      context
+        .index
+        .get_inscriptions_paginated(&filter, 100, 0)
+        .unwrap()
+        .0
+    };
+
+    assert_eq!(
+      inscriptions(InscriptionFilter {
+        rarity: Some(Rarity::Uncommon),
+        ..default()
+      }),
+      [uncommon]
+    );
+
+    assert_eq!(
+      inscriptions(InscriptionFilter {
+        rarity: Some(Rarity::Common),
+        ..default()
+      }),
+      [common]
+    );
+
+    assert_eq!(
+      inscriptions(InscriptionFilter {
+        charm: Some(Charm::Uncommon),
+        ..default()
+      }),
+      [uncommon]
+    );
+
+    assert_eq!(
+      inscriptions(InscriptionFilter {
+        charm: Some(Charm::Uncommon),
+        rarity: Some(Rarity::Common),
+        ..default()
+      }),
+      []
+    );
+
+    assert_eq!(
+      inscriptions(InscriptionFilter {
+        charm: Some(Charm::Burned),
+        ..default()
+      }),
+      []
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      outputs: 0,
+      op_return_index: Some(0),
+      op_return_value: Some(2 * COIN_VALUE),
+      op_return: Some(
+        script::Builder::new()
+          .push_opcode(opcodes::all::OP_RETURN)
+          .into_script(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    assert_eq!(
+      inscriptions(InscriptionFilter {
+        charm: Some(Charm::Burned),
+        content_type: Some("text/plain".into()),
+        from_height: Some(2),
+        to_height: Some(2),
+        ..default()
+      }),
+      [uncommon]
+    );
+  }
+
+  #[test]
+  fn inscriptions_are_indexed_by_content_hash() {
+    let context = Context::builder().build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let mut ids = Vec::new();
+
+    for (i, body) in ["foo", "bar", "foo"].into_iter().enumerate() {
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, inscription("text/plain", body).to_witness())],
+        ..default()
+      });
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+      ids.push(InscriptionId { txid, index: 0 });
+    }
+
+    assert_eq!(
+      context
+        .index
+        .get_inscriptions_by_sha256(sha256::Hash::hash(b"foo"), 100, 0)
+        .unwrap(),
+      (vec![ids[0], ids[2]], false)
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_inscriptions_by_sha256(sha256::Hash::hash(b"foo"), 1, 0)
+        .unwrap(),
+      (vec![ids[0]], true)
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_inscriptions_by_sha256(sha256::Hash::hash(b"baz"), 100, 0)
+        .unwrap(),
+      (Vec::new(), false)
+    );
+  }
+
+  #[test]
+  fn text_inscriptions_are_searchable() {
+    let context = Context::builder().arg("--index-text").build();
+
+    assert!(context.index.has_text_index());
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let mut ids = Vec::new();
+
+    for (i, inscription) in [
+      inscription("text/plain", "The quick brown fox"),
+      inscription("text/plain;charset=utf-8", "lazy dog"),
+      inscription("text/markdown", "# fox fox fox"),
+      inscription("image/png", "fox"),
+      inscription("application/json", "{\"fox\":1}"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, inscription.to_witness())],
+        ..default()
+      });
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+      ids.push(InscriptionId { txid, index: 0 });
+    }
+
+    assert_eq!(
+      context.index.search_inscriptions("FOX", 100, 0).unwrap(),
+      (vec![ids[2], ids[4], ids[0]], false),
+    );
+
+    assert_eq!(
+      context.index.search_inscriptions("lazy fox", 1, 0).unwrap(),
+      (vec![ids[1]], true),
+    );
+
+    assert_eq!(
+      context.index.search_inscriptions("cat", 100, 0).unwrap(),
+      (Vec::new(), false),
+    );
+  }
+
+  #[test]
+  fn text_is_not_indexed_without_flag() {
+    let context = Context::builder().build();
+
+    assert!(!context.index.has_text_index());
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "fox").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    assert_eq!(
+      context.index.search_inscriptions("fox", 100, 0).unwrap(),
+      (Vec::new(), false),
+    );
+  }
+
+  #[test]
+  fn unrecognized_even_field_inscriptions_are_cursed_and_unbound() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let witness = envelope(&[
+        b"ord",
+        &[1],
+        b"text/plain;charset=utf-8",
+        &[2],
+        b"bar",
+        &[4],
+        b"ord",
+      ]);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, witness)],
+        ..default()
+      });
+
+      let inscription_id = InscriptionId { txid, index: 0 };
+
+      context.mine_blocks(1);
+
+      context.index.assert_inscription_location(
+        inscription_id,
+        SatPoint {
+          outpoint: unbound_outpoint(),
+          offset: 0,
+        },
+        None,
+      );
+
+      assert_eq!(context.index.inscription_number(inscription_id), -1);
+    }
+  }
+
+  #[test]
+  fn unrecognized_even_field_inscriptions_are_unbound_after_jubilee() {
+    for context in Context::configurations() {
+      context.mine_blocks(109);
+
+      let witness = envelope(&[
+        b"ord",
+        &[1],
+        b"text/plain;charset=utf-8",
+        &[2],
+        b"bar",
+        &[4],
+        b"ord",
+      ]);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, witness)],
+        ..default()
+      });
+
+      let inscription_id = InscriptionId { txid, index: 0 };
+
+      context.mine_blocks(1);
+
+      context.index.assert_inscription_location(
+        inscription_id,
+        SatPoint {
+          outpoint: unbound_outpoint(),
+          offset: 0,
+        },
+        None,
+      );
+
+      assert_eq!(context.index.inscription_number(inscription_id), 0);
+    }
+  }
+
+  #[test]
+  fn inscriptions_are_uncursed_after_jubilee() {
+    for context in Context::configurations() {
+      context.mine_blocks(108);
+
+      let witness = envelope(&[
+        b"ord",
+        &[1],
+        b"text/plain;charset=utf-8",
+        &[1],
+        b"text/plain;charset=utf-8",
+      ]);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, witness.clone())],
+        ..default()
+      });
+
+      let inscription_id = InscriptionId { txid, index: 0 };
+
+      context.mine_blocks(1);
+
+      assert_eq!(context.core.height(), 109);
+
+      assert_eq!(context.index.inscription_number(inscription_id), -1);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0, witness)],
+        ..default()
+      });
+
       let inscription_id = InscriptionId { txid, index: 0 };
 
       context.mine_blocks(1);
@@ -6417,167 +7768,252 @@ mod tests {
   }
 
   #[test]
-  fn fee_spent_inscriptions_are_numbered_last_in_block() {
-    for context in Context::configurations() {
-      context.mine_blocks(2);
-
-      let txid = context.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
-        fee: 50 * COIN_VALUE,
-        ..default()
-      });
-
-      let a = InscriptionId { txid, index: 0 };
-
-      let txid = context.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(2, 0, 0, inscription("text/plain", "hello").to_witness())],
-        ..default()
-      });
+  fn inscription_history_is_recorded() {
+    let context = Context::builder().build();
 
-      let b = InscriptionId { txid, index: 0 };
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-      context.mine_blocks(1);
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
 
-      assert_eq!(context.index.inscription_number(a), 1);
-      assert_eq!(context.index.inscription_number(b), 0);
-    }
-  }
+    let inscription_id = InscriptionId { txid, index: 0 };
 
-  #[test]
-  fn inscription_event_sender_channel() {
-    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
-    let context = Context::builder().event_sender(event_sender).build();
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-    context.mine_blocks(1);
+    assert_eq!(
+      context
+        .index
+        .get_inscription_history(inscription_id, 100, 0)
+        .unwrap(),
+      Some((Vec::new(), false)),
+    );
 
-    let inscription = Inscription::default();
-    let create_txid = context.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription.to_witness())],
-      fee: 0,
-      outputs: 1,
+    let send = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
       ..default()
     });
 
-    context.mine_blocks(1);
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-    let inscription_id = InscriptionId {
-      txid: create_txid,
-      index: 0,
+    let output = context.index.get_transaction(send).unwrap().unwrap().output[0].clone();
+
+    let first = api::InscriptionTransfer {
+      address: context
+        .index
+        .settings
+        .chain()
+        .address_from_script(&output.script_pubkey)
+        .ok()
+        .map(|address| address.to_string()),
+      height: 3,
+      new_satpoint: SatPoint {
+        outpoint: OutPoint {
+          txid: send,
+          vout: 0,
+        },
+        offset: 0,
+      },
+      old_satpoint: SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      txid: send,
     };
-    let create_event = event_receiver.blocking_recv().unwrap();
-    let expected_charms = if context.index.index_sats { 513 } else { 0 };
-    assert_eq!(
-      create_event,
-      Event::InscriptionCreated {
-        inscription_id,
-        location: Some(SatPoint {
-          outpoint: OutPoint {
-            txid: create_txid,
-            vout: 0
-          },
-          offset: 0
-        }),
-        sequence_number: 0,
-        block_height: 2,
-        charms: expected_charms,
-        parent_inscription_ids: Vec::new(),
-      }
-    );
 
-    // Transfer inscription
-    let transfer_txid = context.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(2, 1, 0, Default::default())],
-      fee: 0,
-      outputs: 1,
+    let fee_spend = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 0, Default::default())],
+      fee: 2 * COIN_VALUE,
       ..default()
     });
 
-    context.mine_blocks(1);
+    let coinbase = context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE)[0].txdata[0].clone();
+
+    let (transfers, more) = context
+      .index
+      .get_inscription_history(inscription_id, 100, 0)
+      .unwrap()
+      .unwrap();
+
+    assert!(!more);
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(transfers[0], first);
+    assert_eq!(transfers[1].height, 4);
+    assert_eq!(transfers[1].old_satpoint, first.new_satpoint);
+    assert_eq!(transfers[1].new_satpoint.outpoint.txid, coinbase.txid());
+    assert_eq!(transfers[1].txid, fee_spend);
 
-    let transfer_event = event_receiver.blocking_recv().unwrap();
     assert_eq!(
-      transfer_event,
-      Event::InscriptionTransferred {
-        block_height: 3,
-        inscription_id,
-        new_location: SatPoint {
-          outpoint: OutPoint {
-            txid: transfer_txid,
-            vout: 0
-          },
-          offset: 0
-        },
-        old_location: SatPoint {
-          outpoint: OutPoint {
-            txid: create_txid,
-            vout: 0
+      context
+        .index
+        .get_inscription_history(inscription_id, 1, 1)
+        .unwrap()
+        .unwrap(),
+      (vec![transfers[1].clone()], false),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_inscription_history(
+          InscriptionId {
+            txid: send,
+            index: 0
           },
-          offset: 0
-        },
-        sequence_number: 0,
-      }
+          100,
+          0
+        )
+        .unwrap(),
+      None,
     );
   }
 
   #[test]
-  fn rune_event_sender_channel() {
+  fn rune_holders_are_aggregated_by_address() {
     const RUNE: u128 = 99246114928149462;
 
-    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
     let context = Context::builder()
       .arg("--index-runes")
-      .event_sender(event_sender)
+      .arg("--index-addresses")
       .build();
 
-    let (txid0, id) = context.etch(
+    let (txid, id) = context.etch(
       Runestone {
+        edicts: vec![
+          Edict {
+            id: RuneId::default(),
+            amount: 1000,
+            output: 0,
+          },
+          Edict {
+            id: RuneId::default(),
+            amount: 0,
+            output: 1,
+          },
+        ],
         etching: Some(Etching {
           rune: Some(Rune(RUNE)),
-          terms: Some(Terms {
-            amount: Some(1000),
-            cap: Some(100),
-            ..default()
-          }),
+          premine: Some(1500),
           ..default()
         }),
         ..default()
       },
-      1,
+      2,
+    );
+
+    let chain = context.index.settings.chain();
+
+    let etcher = chain
+      .address_from_script(
+        &context.index.get_transaction(txid).unwrap().unwrap().output[0].script_pubkey,
+      )
+      .unwrap();
+
+    assert_eq!(
+      context.index.get_rune_holders(id, 100, 0).unwrap(),
+      (
+        vec![(api::RuneHolder::Address(etcher.to_string()), 1500)],
+        2,
+        Some(1),
+        false,
+      ),
+    );
+
+    let send = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 1, Witness::new())],
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let recipient = chain
+      .address_from_script(
+        &context.index.get_transaction(send).unwrap().unwrap().output[0].script_pubkey,
+      )
+      .unwrap();
+
+    assert_eq!(
+      context.index.get_rune_holders(id, 100, 0).unwrap(),
+      (
+        vec![
+          (api::RuneHolder::Address(etcher.to_string()), 1000),
+          (api::RuneHolder::Address(recipient.to_string()), 500),
+        ],
+        2,
+        Some(2),
+        false,
+      ),
+    );
+
+    assert_eq!(
+      context.index.get_rune_holders(id, 1, 0).unwrap(),
+      (
+        vec![(api::RuneHolder::Address(etcher.to_string()), 1000)],
+        2,
+        Some(2),
+        true,
+      ),
+    );
+
+    assert_eq!(
+      context.index.get_rune_holders(id, 1, 1).unwrap(),
+      (
+        vec![(api::RuneHolder::Address(recipient.to_string()), 500)],
+        2,
+        Some(2),
+        false,
+      ),
     );
 
     context.assert_runes(
-      [(
-        id,
-        RuneEntry {
-          block: id.block,
-          etching: txid0,
-          spaced_rune: SpacedRune {
-            rune: Rune(RUNE),
-            spacers: 0,
+      context.index.runes().unwrap(),
+      [
+        (OutPoint { txid, vout: 0 }, vec![(id, 1000)]),
+        (
+          OutPoint {
+            txid: send,
+            vout: 0,
           },
-          timestamp: id.block,
-          mints: 0,
+          vec![(id, 500)],
+        ),
+      ],
+    );
+  }
+
+  #[test]
+  fn rune_activity_is_recorded() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (_txid, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
           terms: Some(Terms {
-            amount: Some(1000),
-            cap: Some(100),
+            amount: Some(100),
+            cap: Some(10),
             ..default()
           }),
           ..default()
-        },
-      )],
-      [],
+        }),
+        ..default()
+      },
+      1,
     );
 
     assert_eq!(
-      event_receiver.blocking_recv().unwrap(),
-      Event::RuneEtched {
-        block_height: 8,
-        txid: txid0,
-        rune_id: id,
-      }
+      context
+        .index
+        .get_rune_activity(id, 0, u32::MAX, usize::MAX)
+        .unwrap(),
+      (Vec::new(), false),
     );
 
-    let txid1 = context.core.broadcast_tx(TransactionTemplate {
+    context.core.broadcast_tx(TransactionTemplate {
       inputs: &[(2, 0, 0, Witness::new())],
       op_return: Some(
         Runestone {
@@ -6589,126 +8025,321 @@ mod tests {
       ..default()
     });
 
-    context.mine_blocks(1);
-
-    context.assert_runes(
-      [(
-        id,
-        RuneEntry {
-          block: id.block,
-          etching: txid0,
-          terms: Some(Terms {
-            amount: Some(1000),
-            cap: Some(100),
-            ..default()
-          }),
-          mints: 1,
-          spaced_rune: SpacedRune {
-            rune: Rune(RUNE),
-            spacers: 0,
-          },
-          premine: 0,
-          timestamp: id.block,
-          ..default()
-        },
-      )],
-      [(
-        OutPoint {
-          txid: txid1,
-          vout: 0,
-        },
-        vec![(id, 1000)],
-      )],
-    );
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-    assert_eq!(
-      event_receiver.blocking_recv().unwrap(),
-      Event::RuneMinted {
-        block_height: 9,
-        txid: txid1,
-        rune_id: id,
-        amount: 1000,
-      }
-    );
+    let mint = api::RuneBlockActivity {
+      burned: 0,
+      height: context.index.block_height().unwrap().unwrap().n(),
+      minted: 100,
+      mints: 1,
+      transferred: 0,
+    };
 
-    let txid2 = context.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(9, 1, 0, Witness::new())],
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
       op_return: Some(
         Runestone {
           edicts: vec![Edict {
             id,
-            amount: 1000,
-            output: 0,
+            amount: 400,
+            output: 1,
           }],
-          ..Default::default()
+          ..default()
         }
         .encipher(),
       ),
-      ..Default::default()
+      ..default()
     });
 
-    context.mine_blocks(1);
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-    context.assert_runes(
-      [(
-        id,
-        RuneEntry {
-          block: 8,
-          etching: txid0,
-          spaced_rune: SpacedRune {
-            rune: Rune(RUNE),
-            ..default()
-          },
-          terms: Some(Terms {
-            amount: Some(1000),
-            cap: Some(100),
-            ..Default::default()
-          }),
-          timestamp: 8,
-          mints: 1,
-          ..Default::default()
-        },
-      )],
-      [(
-        OutPoint {
-          txid: txid2,
-          vout: 0,
-        },
-        vec![(id, 1000)],
-      )],
+    let transfer = api::RuneBlockActivity {
+      burned: 400,
+      height: mint.height + 1,
+      minted: 0,
+      mints: 0,
+      transferred: 1000,
+    };
+
+    assert_eq!(
+      context
+        .index
+        .get_rune_activity(id, 0, u32::MAX, usize::MAX)
+        .unwrap(),
+      (vec![mint, transfer], false),
     );
 
-    event_receiver.blocking_recv().unwrap();
+    assert_eq!(
+      context.index.get_rune_activity(id, 0, u32::MAX, 1).unwrap(),
+      (vec![mint], true),
+    );
 
-    pretty_assert_eq!(
-      event_receiver.blocking_recv().unwrap(),
-      Event::RuneTransferred {
-        block_height: 10,
-        txid: txid2,
-        rune_id: id,
-        amount: 1000,
-        outpoint: OutPoint {
-          txid: txid2,
-          vout: 0,
+    assert_eq!(
+      context
+        .index
+        .get_rune_activity(id, transfer.height, transfer.height, 1)
+        .unwrap(),
+      (vec![transfer], false),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_rune_activity(id, transfer.height, mint.height, 1)
+        .unwrap(),
+      (Vec::new(), false),
+    );
+  }
+
+  #[test]
+  fn address_history_is_recorded() {
+    let context = Context::builder().arg("--index-address-history").build();
+
+    assert!(context.index.has_address_index());
+    assert!(context.index.has_address_history_index());
+
+    let coinbase = context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE)[0].txdata[0].clone();
+
+    let chain = context.index.settings.chain();
+
+    let miner = chain
+      .address_from_script(&coinbase.output[0].script_pubkey)
+      .unwrap();
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      outputs: 2,
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let transaction = context.index.get_transaction(txid).unwrap().unwrap();
+
+    let recipient = chain
+      .address_from_script(&transaction.output[0].script_pubkey)
+      .unwrap();
+
+    let spend = api::AddressTransaction {
+      height: 2,
+      inscriptions: vec![InscriptionId { txid, index: 0 }],
+      received: 0,
+      runes: BTreeMap::new(),
+      sent: 2 * COIN_VALUE,
+      txid,
+    };
+
+    let coinbase = api::AddressTransaction {
+      height: 1,
+      inscriptions: Vec::new(),
+      received: 2 * COIN_VALUE,
+      runes: BTreeMap::new(),
+      sent: 0,
+      txid: coinbase.txid(),
+    };
+
+    assert_eq!(
+      context.index.get_address_history(&miner, 100, 0).unwrap(),
+      (vec![spend.clone(), coinbase.clone()], false),
+    );
+
+    assert_eq!(
+      context.index.get_address_history(&miner, 1, 0).unwrap(),
+      (vec![spend.clone()], true),
+    );
+
+    assert_eq!(
+      context.index.get_address_history(&miner, 1, 1).unwrap(),
+      (vec![coinbase], false),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_address_history(&recipient, 100, 0)
+        .unwrap(),
+      (
+        vec![api::AddressTransaction {
+          received: 2 * COIN_VALUE,
+          sent: 0,
+          ..spend
+        }],
+        false
+      ),
+    );
+  }
+
+  #[test]
+  fn fee_spent_inscriptions_are_numbered_last_in_block() {
+    for context in Context::configurations() {
+      context.mine_blocks(2);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        fee: 50 * COIN_VALUE,
+        ..default()
+      });
+
+      let a = InscriptionId { txid, index: 0 };
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      });
+
+      let b = InscriptionId { txid, index: 0 };
+
+      context.mine_blocks(1);
+
+      assert_eq!(context.index.inscription_number(a), 1);
+      assert_eq!(context.index.inscription_number(b), 0);
+    }
+  }
+
+  #[test]
+  fn inscription_event_sender_channel() {
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let context = Context::builder().event_sender(event_sender).build();
+
+    context.mine_blocks(1);
+
+    let inscription = Inscription::default();
+    let create_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription.to_witness())],
+      fee: 0,
+      outputs: 1,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let inscription_id = InscriptionId {
+      txid: create_txid,
+      index: 0,
+    };
+    let create_event = event_receiver.blocking_recv().unwrap();
+    let expected_charms = if context.index.index_sats { 513 } else { 0 };
+    assert_eq!(
+      create_event,
+      Event::InscriptionCreated {
+        inscription_id,
+        location: Some(SatPoint {
+          outpoint: OutPoint {
+            txid: create_txid,
+            vout: 0
+          },
+          offset: 0
+        }),
+        sequence_number: 0,
+        block_height: 2,
+        charms: expected_charms,
+        parent_inscription_ids: Vec::new(),
+      }
+    );
+
+    // Transfer inscription
+    let transfer_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      fee: 0,
+      outputs: 1,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let transfer_event = event_receiver.blocking_recv().unwrap();
+    assert_eq!(
+      transfer_event,
+      Event::InscriptionTransferred {
+        block_height: 3,
+        inscription_id,
+        new_location: SatPoint {
+          outpoint: OutPoint {
+            txid: transfer_txid,
+            vout: 0
+          },
+          offset: 0
+        },
+        old_location: SatPoint {
+          outpoint: OutPoint {
+            txid: create_txid,
+            vout: 0
+          },
+          offset: 0
         },
+        sequence_number: 0,
       }
     );
+  }
 
-    let txid3 = context.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(10, 1, 0, Witness::new())],
+  #[test]
+  fn rune_event_sender_channel() {
+    const RUNE: u128 = 99246114928149462;
+
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let context = Context::builder()
+      .arg("--index-runes")
+      .event_sender(event_sender)
+      .build();
+
+    let (txid0, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    context.assert_runes(
+      [(
+        id,
+        RuneEntry {
+          block: id.block,
+          etching: txid0,
+          spaced_rune: SpacedRune {
+            rune: Rune(RUNE),
+            spacers: 0,
+          },
+          timestamp: id.block,
+          mints: 0,
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..default()
+          }),
+          ..default()
+        },
+      )],
+      [],
+    );
+
+    assert_eq!(
+      event_receiver.blocking_recv().unwrap(),
+      Event::RuneEtched {
+        block_height: 8,
+        txid: txid0,
+        rune_id: id,
+      }
+    );
+
+    let txid1 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, Witness::new())],
       op_return: Some(
         Runestone {
-          edicts: vec![Edict {
-            id,
-            amount: 111,
-            output: 0,
-          }],
-          ..Default::default()
+          mint: Some(id),
+          ..default()
         }
         .encipher(),
       ),
-      op_return_index: Some(0),
-      ..Default::default()
+      ..default()
     });
 
     context.mine_blocks(1);
@@ -6717,35 +8348,157 @@ mod tests {
       [(
         id,
         RuneEntry {
-          block: 8,
+          block: id.block,
           etching: txid0,
-          spaced_rune: SpacedRune {
-            rune: Rune(RUNE),
-            ..default()
-          },
           terms: Some(Terms {
             amount: Some(1000),
             cap: Some(100),
-            ..Default::default()
+            ..default()
           }),
-          timestamp: 8,
           mints: 1,
-          burned: 111,
-          ..Default::default()
+          spaced_rune: SpacedRune {
+            rune: Rune(RUNE),
+            spacers: 0,
+          },
+          premine: 0,
+          timestamp: id.block,
+          ..default()
         },
       )],
       [(
         OutPoint {
-          txid: txid3,
-          vout: 1,
+          txid: txid1,
+          vout: 0,
         },
-        vec![(id, 889)],
+        vec![(id, 1000)],
       )],
     );
 
-    event_receiver.blocking_recv().unwrap();
-
-    pretty_assert_eq!(
+    assert_eq!(
+      event_receiver.blocking_recv().unwrap(),
+      Event::RuneMinted {
+        block_height: 9,
+        txid: txid1,
+        rune_id: id,
+        amount: 1000,
+      }
+    );
+
+    let txid2 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(9, 1, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 1000,
+            output: 0,
+          }],
+          ..Default::default()
+        }
+        .encipher(),
+      ),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    context.assert_runes(
+      [(
+        id,
+        RuneEntry {
+          block: 8,
+          etching: txid0,
+          spaced_rune: SpacedRune {
+            rune: Rune(RUNE),
+            ..default()
+          },
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..Default::default()
+          }),
+          timestamp: 8,
+          mints: 1,
+          ..Default::default()
+        },
+      )],
+      [(
+        OutPoint {
+          txid: txid2,
+          vout: 0,
+        },
+        vec![(id, 1000)],
+      )],
+    );
+
+    event_receiver.blocking_recv().unwrap();
+
+    pretty_assert_eq!(
+      event_receiver.blocking_recv().unwrap(),
+      Event::RuneTransferred {
+        block_height: 10,
+        txid: txid2,
+        rune_id: id,
+        amount: 1000,
+        outpoint: OutPoint {
+          txid: txid2,
+          vout: 0,
+        },
+      }
+    );
+
+    let txid3 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(10, 1, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 111,
+            output: 0,
+          }],
+          ..Default::default()
+        }
+        .encipher(),
+      ),
+      op_return_index: Some(0),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    context.assert_runes(
+      [(
+        id,
+        RuneEntry {
+          block: 8,
+          etching: txid0,
+          spaced_rune: SpacedRune {
+            rune: Rune(RUNE),
+            ..default()
+          },
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..Default::default()
+          }),
+          timestamp: 8,
+          mints: 1,
+          burned: 111,
+          ..Default::default()
+        },
+      )],
+      [(
+        OutPoint {
+          txid: txid3,
+          vout: 1,
+        },
+        vec![(id, 889)],
+      )],
+    );
+
+    event_receiver.blocking_recv().unwrap();
+
+    pretty_assert_eq!(
       event_receiver.blocking_recv().unwrap(),
       Event::RuneBurned {
         block_height: 11,
@@ -6756,6 +8509,532 @@ mod tests {
     );
   }
 
+  #[test]
+  fn events_are_logged_and_rolled_back_with_index_events() {
+    let mut context = Context::builder().arg("--index-events").build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let created = Event::InscriptionCreated {
+      block_height: 2,
+      charms: 0,
+      inscription_id: InscriptionId { txid, index: 0 },
+      location: Some(SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      }),
+      parent_inscription_ids: Vec::new(),
+      sequence_number: 0,
+    };
+
+    assert_eq!(
+      context.index.get_events(0, 1000).unwrap(),
+      (vec![created.clone()], false, 3)
+    );
+
+    assert_eq!(
+      context.index.get_events(3, 1000).unwrap(),
+      (Vec::new(), false, 3)
+    );
+
+    context.core.invalidate_tip();
+    context.mine_blocks_with_subsidy(2, 2 * COIN_VALUE);
+
+    assert_eq!(
+      context.index.get_events(0, 1000).unwrap(),
+      (
+        vec![Event::RolledBack {
+          block_height: 2,
+          depth: 1,
+        }],
+        false,
+        4
+      )
+    );
+  }
+
+  #[test]
+  fn events_are_not_logged_without_index_events() {
+    let context = Context::builder().build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    assert!(!context.index.has_event_index());
+
+    assert_eq!(
+      context.index.get_events(0, 1000).unwrap(),
+      (Vec::new(), false, 3)
+    );
+  }
+
+  #[test]
+  fn undo_log_recovers_from_reorg_deeper_than_savepoints() {
+    for args in [
+      vec!["--max-savepoints=1", "--undo-log-depth=20"],
+      vec![
+        "--max-savepoints=1",
+        "--undo-log-depth=20",
+        "--index-events",
+      ],
+    ] {
+      let context = Context::builder().args(args).build();
+
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      });
+      let inscription_id = InscriptionId { txid, index: 0 };
+
+      context.mine_blocks_with_subsidy(15, 2 * COIN_VALUE);
+
+      assert!(context.index.inscription_exists(inscription_id).unwrap());
+      assert!(context.index.info().unwrap().undo_log_records > 0);
+
+      for _ in 0..15 {
+        context.core.invalidate_tip();
+      }
+
+      context.mine_blocks_with_subsidy(16, 2 * COIN_VALUE);
+
+      assert!(!context.index.inscription_exists(inscription_id).unwrap());
+      assert_eq!(context.index.block_count().unwrap(), 18);
+
+      for height in 0..18 {
+        assert_eq!(
+          context.index.block_hash(Some(height)).unwrap().unwrap(),
+          context.core.state().hashes[usize::try_from(height).unwrap()],
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn reorg_deeper_than_savepoints_is_unrecoverable_without_undo_log() {
+    let mut context = Context::builder().arg("--max-savepoints=1").build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks_with_subsidy(16, 2 * COIN_VALUE);
+
+    for _ in 0..15 {
+      context.core.invalidate_tip();
+    }
+
+    context.core.mine_blocks_with_subsidy(16, 2 * COIN_VALUE);
+
+    assert_eq!(
+      context
+        .index
+        .update()
+        .unwrap_err()
+        .downcast_ref::<reorg::Error>(),
+      Some(&reorg::Error::Unrecoverable)
+    );
+  }
+
+  #[test]
+  fn rollback_restores_savepoint() {
+    let mut context = Context::builder().build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    for _ in 0..6 {
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+    }
+
+    assert_eq!(context.index.block_count().unwrap(), 7);
+
+    assert_eq!(
+      context.index.rollback(5).unwrap_err().to_string(),
+      "no savepoint or undo log to roll back to height 5"
+    );
+
+    let savepoints = || {
+      context
+        .index
+        .database
+        .begin_write()
+        .unwrap()
+        .list_persistent_savepoints()
+        .unwrap()
+        .count()
+    };
+
+    assert_eq!(savepoints(), 2);
+
+    let rollback = context.index.rollback(6).unwrap();
+
+    assert_eq!(savepoints(), 1);
+
+    assert_eq!(rollback.blocks_indexed, 6);
+    assert_eq!(rollback.blocks_removed, 1);
+    assert_eq!(
+      rollback.tables[HEIGHT_TO_BLOCK_HEADER.name()],
+      TableChange {
+        entries_before: 7,
+        entries_after: 6,
+      }
+    );
+    assert!(!rollback
+      .tables
+      .contains_key(INSCRIPTION_ID_TO_SEQUENCE_NUMBER.name()));
+
+    assert_eq!(context.index.block_count().unwrap(), 6);
+
+    context.index.update().unwrap();
+
+    assert_eq!(context.index.block_count().unwrap(), 7);
+  }
+
+  #[test]
+  fn rollback_uses_undo_log() {
+    let context = Context::builder().arg("--undo-log-depth=10").build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    for _ in 0..5 {
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+    }
+
+    assert!(context.index.inscription_exists(inscription_id).unwrap());
+
+    let rollback = context.index.rollback(2).unwrap();
+
+    assert_eq!(rollback.blocks_indexed, 2);
+    assert_eq!(rollback.blocks_removed, 5);
+    assert_eq!(
+      rollback.tables[INSCRIPTION_ID_TO_SEQUENCE_NUMBER.name()],
+      TableChange {
+        entries_before: 1,
+        entries_after: 0,
+      }
+    );
+
+    assert!(!context.index.inscription_exists(inscription_id).unwrap());
+
+    context.index.update().unwrap();
+
+    assert_eq!(context.index.block_count().unwrap(), 7);
+    assert!(context.index.inscription_exists(inscription_id).unwrap());
+  }
+
+  #[test]
+  fn rollback_height_must_be_below_block_count() {
+    let context = Context::builder().arg("--undo-log-depth=10").build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    assert_eq!(
+      context.index.rollback(2).unwrap_err().to_string(),
+      "cannot roll back to height 2, index only contains 2 blocks"
+    );
+  }
+
+  #[test]
+  fn snapshot_round_trip() {
+    let mut context = Context::builder().arg("--savepoint-interval=2").build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let tip = context
+      .mine_blocks_with_subsidy(4, 2 * COIN_VALUE)
+      .last()
+      .unwrap()
+      .block_hash();
+
+    let Context {
+      index,
+      core,
+      tempdir,
+    } = context;
+
+    let settings = index.settings.clone();
+    let path = tempdir.path().join("index.snapshot");
+
+    let snapshot = index.create_snapshot(&path).unwrap();
+
+    assert_eq!(snapshot.height, 5);
+    assert_eq!(snapshot.block_hash, tip);
+    assert_eq!(snapshot.chain, Chain::Regtest);
+    assert_eq!(snapshot.schema_version, SCHEMA_VERSION);
+
+    assert_eq!(
+      Index::restore_snapshot(&settings, &path, false)
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "index at `{}` already exists, use --force to replace it",
+        settings.index().display()
+      ),
+    );
+
+    fs::remove_file(settings.index()).unwrap();
+
+    assert_eq!(
+      Index::restore_snapshot(&settings, &path, false).unwrap(),
+      snapshot
+    );
+
+    let index = Index::open(&settings).unwrap();
+    assert_eq!(index.block_count().unwrap(), 6);
+    assert!(index.inscription_exists(inscription_id).unwrap());
+
+    core.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+    index.update().unwrap();
+    assert_eq!(index.block_count().unwrap(), 7);
+  }
+
+  #[test]
+  fn snapshot_restore_is_verified() {
+    let context = Context::builder().build();
+
+    context.mine_blocks_with_subsidy(2, 2 * COIN_VALUE);
+
+    let Context {
+      index,
+      core,
+      tempdir,
+    } = context;
+
+    let settings = index.settings.clone();
+    let path = tempdir.path().join("index.snapshot");
+
+    let snapshot = index.create_snapshot(&path).unwrap();
+
+    let mut corrupt = fs::read(&path).unwrap();
+    *corrupt.last_mut().unwrap() ^= 1;
+    let corrupt_path = tempdir.path().join("corrupt.snapshot");
+    fs::write(&corrupt_path, corrupt).unwrap();
+
+    assert!(Index::restore_snapshot(&settings, &corrupt_path, true)
+      .unwrap_err()
+      .to_string()
+      .starts_with("snapshot checksum mismatch"));
+
+    assert!(!settings.index().with_extension("redb.snapshot").exists());
+
+    core.invalidate_tip();
+    core.mine_blocks_with_subsidy(2, 2 * COIN_VALUE);
+
+    assert_eq!(
+      Index::restore_snapshot(&settings, &path, true)
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "snapshot block {} at height 2 is not in the connected node's best chain",
+        snapshot.block_hash
+      ),
+    );
+  }
+
+  #[test]
+  fn export_inscriptions_and_children_by_height() {
+    let context = Context::builder().build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let parent_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let parent = InscriptionId {
+      txid: parent_txid,
+      index: 0,
+    };
+
+    let child_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        2,
+        1,
+        0,
+        Inscription {
+          content_type: Some("text/html".into()),
+          body: Some("<p>child</p>".into()),
+          parents: vec![parent.value()],
+          ..default()
+        }
+        .to_witness(),
+      )],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let child = InscriptionId {
+      txid: child_txid,
+      index: 0,
+    };
+
+    let inscriptions = |heights| {
+      let mut records = Vec::new();
+      context
+        .index
+        .export_inscriptions(heights, false, |record| {
+          records.push(record);
+          Ok(())
+        })
+        .unwrap();
+      records
+    };
+
+    assert_eq!(
+      inscriptions(0..=u32::MAX)
+        .into_iter()
+        .map(|record| record.id)
+        .collect::<Vec<InscriptionId>>(),
+      [parent, child],
+    );
+
+    assert_eq!(
+      inscriptions(3..=3),
+      [InscriptionRecord {
+        id: child,
+        number: 1,
+        sequence_number: 1,
+        height: 3,
+        timestamp: 3,
+        content_type: Some("text/html".into()),
+        content_length: Some(12),
+        charms: vec![Charm::Reinscription, Charm::Vindicated],
+        fee: 0,
+        sat: None,
+        satpoint: SatPoint {
+          outpoint: OutPoint {
+            txid: child_txid,
+            vout: 0,
+          },
+          offset: 0,
+        },
+        parents: vec![parent],
+        address: None,
+      }],
+    );
+
+    assert_eq!(
+      inscriptions(0..=2)
+        .into_iter()
+        .map(|record| record.id)
+        .collect::<Vec<InscriptionId>>(),
+      [parent],
+    );
+
+    let mut children = Vec::new();
+    context
+      .index
+      .export_children(0..=u32::MAX, |record| {
+        children.push(record);
+        Ok(())
+      })
+      .unwrap();
+
+    assert_eq!(
+      children,
+      [ChildRecord {
+        parent,
+        child,
+        height: 3,
+      }],
+    );
+  }
+
+  #[test]
+  fn partial_inscription_content_is_stored_with_index_partials() {
+    for index_partials in [false, true] {
+      let context = if index_partials {
+        Context::builder().arg("--index-partials").build()
+      } else {
+        Context::builder().build()
+      };
+
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+      let first = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, Default::default())],
+        script_sigs: &[script::Builder::new()
+          .push_slice(PROTOCOL_ID)
+          .push_int(2)
+          .push_slice(b"text/plain")
+          .push_int(1)
+          .push_slice(b"foo")
+          .into_script()],
+        ..default()
+      });
+
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+      let inscription_id = InscriptionId {
+        txid: first,
+        index: 0,
+      };
+
+      assert_eq!(
+        context.index.get_inscription_by_id(inscription_id).unwrap(),
+        None
+      );
+
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 1, 0, Default::default())],
+        script_sigs: &[script::Builder::new()
+          .push_int(0)
+          .push_slice(b"bar")
+          .into_script()],
+        ..default()
+      });
+
+      context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+      assert_eq!(
+        context.index.get_inscription_by_id(inscription_id).unwrap(),
+        Some(inscription("text/plain", "foobar"))
+      );
+
+      assert_eq!(
+        context
+          .index
+          .database
+          .begin_read()
+          .unwrap()
+          .open_table(INSCRIPTION_ID_TO_PARTIAL_CONTENT)
+          .unwrap()
+          .get(&inscription_id.store())
+          .unwrap()
+          .map(|content| content.value()),
+        index_partials.then(|| (b"text/plain".to_vec(), b"foobar".to_vec())),
+      );
+    }
+  }
+
   #[test]
   fn assert_schema_statistic_key_is_zero() {
     // other schema statistic keys may chenge when the schema changes, but for
@@ -6763,16 +9042,4 @@ mod tests {
     // zero
     assert_eq!(Statistic::Schema.key(), 0);
   }
-
-  #[test]
-  fn reminder_to_update_utxo_entry_type_name() {
-    // This test will break when the schema version is updated, and is a
-    // reminder to fix the type name in `impl redb::Value for &UtxoEntry`.
-    //
-    // The type name should be changed from `ord::index::utxo_entry::UtxoValue`
-    // to `ord::UtxoEntry`. I think it's probably best if we just name types
-    // `ord::NAME`, instead of including the full path, since the full path
-    // will change if we reorganize the code.
-    assert_eq!(SCHEMA_VERSION, 28);
-  }
 }
@@ -12,24 +12,144 @@ pub(crate) trait Entry: Sized {
 
 pub(super) type HeaderValue = Vec<u8>;
 
+/// Headers are stored as their 80 byte encoding, followed by their AuxPoW if
+/// it was indexed. The AuxPoW version bit is not enough to tell whether it is
+/// present, since AuxPoW is only stored with `--index-auxpow`.
 impl Entry for Header {
   type Value = HeaderValue;
 
   fn load(value: Self::Value) -> Self {
-    consensus::encode::deserialize(&value).unwrap()
+    let (header, len) =
+      consensus::encode::deserialize_partial::<HeaderWithoutAuxPow>(&value).unwrap();
+
+    Self {
+      version: header.version,
+      prev_blockhash: header.prev_blockhash,
+      merkle_root: header.merkle_root,
+      time: header.time,
+      bits: header.bits,
+      nonce: header.nonce,
+      auxpow: (len < value.len()).then(|| consensus::encode::deserialize(&value[len..]).unwrap()),
+    }
   }
 
   fn store(self) -> Self::Value {
     let mut buffer = Cursor::new(vec![]);
-    let len = self
-      .consensus_encode(&mut buffer)
-      .expect("in-memory writers don't error");
+    let mut len = HeaderWithoutAuxPow {
+      version: self.version,
+      prev_blockhash: self.prev_blockhash,
+      merkle_root: self.merkle_root,
+      time: self.time,
+      bits: self.bits,
+      nonce: self.nonce,
+    }
+    .consensus_encode(&mut buffer)
+    .expect("in-memory writers don't error");
+    if let Some(auxpow) = self.auxpow {
+      len += auxpow
+        .consensus_encode(&mut buffer)
+        .expect("in-memory writers don't error");
+    }
     let buffer = buffer.into_inner();
     debug_assert_eq!(len, buffer.len());
     buffer
   }
 }
 
+pub(super) type AddressHistoryEntryValue = (
+  TxidValue,                // txid
+  u64,                      // sent
+  u64,                      // received
+  Vec<u32>,                 // inscriptions
+  Vec<(RuneIdValue, u128)>, // runes
+);
+
+/// A transaction that funded or spent outputs of a script pubkey, along with
+/// the inscriptions and runes that the transaction moved.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct AddressHistoryEntry {
+  pub(crate) inscriptions: Vec<u32>,
+  pub(crate) received: u64,
+  pub(crate) runes: Vec<(RuneId, u128)>,
+  pub(crate) sent: u64,
+  pub(crate) txid: Txid,
+}
+
+impl Entry for AddressHistoryEntry {
+  type Value = AddressHistoryEntryValue;
+
+  fn load((txid, sent, received, inscriptions, runes): Self::Value) -> Self {
+    Self {
+      inscriptions,
+      received,
+      runes: runes
+        .into_iter()
+        .map(|(id, amount)| (RuneId::load(id), amount))
+        .collect(),
+      sent,
+      txid: Txid::load(txid),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.txid.store(),
+      self.sent,
+      self.received,
+      self.inscriptions,
+      self
+        .runes
+        .into_iter()
+        .map(|(id, amount)| (id.store(), amount))
+        .collect(),
+    )
+  }
+}
+
+pub(super) type InscriptionTransferEntryValue = (
+  u32,           // height
+  TxidValue,     // txid
+  SatPointValue, // old satpoint
+  SatPointValue, // new satpoint
+  Vec<u8>,       // script pubkey
+);
+
+/// A transfer of an inscription from one satpoint to another. The txid is
+/// that of the transaction which spent the inscription, which differs from
+/// the new satpoint's txid if the inscription was spent as fee.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct InscriptionTransferEntry {
+  pub(crate) height: u32,
+  pub(crate) new_satpoint: SatPoint,
+  pub(crate) old_satpoint: SatPoint,
+  pub(crate) script_pubkey: ScriptBuf,
+  pub(crate) txid: Txid,
+}
+
+impl Entry for InscriptionTransferEntry {
+  type Value = InscriptionTransferEntryValue;
+
+  fn load((height, txid, old_satpoint, new_satpoint, script_pubkey): Self::Value) -> Self {
+    Self {
+      height,
+      new_satpoint: SatPoint::load(new_satpoint),
+      old_satpoint: SatPoint::load(old_satpoint),
+      script_pubkey: ScriptBuf::from_bytes(script_pubkey),
+      txid: Txid::load(txid),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.height,
+      self.txid.store(),
+      self.old_satpoint.store(),
+      self.new_satpoint.store(),
+      self.script_pubkey.into_bytes(),
+    )
+  }
+}
+
 impl Entry for Rune {
   type Value = u128;
 
@@ -42,17 +162,20 @@ impl Entry for Rune {
   }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RuneEntry {
   pub block: u64,
   pub burned: u128,
   pub divisibility: u8,
+  #[schemars(with = "String")]
   pub etching: Txid,
   pub mints: u128,
   pub number: u64,
   pub premine: u128,
+  #[schemars(with = "String")]
   pub spaced_rune: SpacedRune,
   pub symbol: Option<char>,
+  #[schemars(with = "Option<crate::api::schema::Terms>")]
   pub terms: Option<Terms>,
   pub timestamp: u64,
   pub turbo: bool,
@@ -290,6 +413,40 @@ impl Entry for RuneId {
   }
 }
 
+pub(super) type RuneActivityEntryValue = (
+  u64,  // mints
+  u128, // minted
+  u128, // burned
+  u128, // transferred
+);
+
+/// Activity of a rune in a single block. Transferred is the total balance of
+/// the outputs holding the rune which were spent in the block.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub(crate) struct RuneActivityEntry {
+  pub(crate) burned: u128,
+  pub(crate) minted: u128,
+  pub(crate) mints: u64,
+  pub(crate) transferred: u128,
+}
+
+impl Entry for RuneActivityEntry {
+  type Value = RuneActivityEntryValue;
+
+  fn load((mints, minted, burned, transferred): Self::Value) -> Self {
+    Self {
+      burned,
+      minted,
+      mints,
+      transferred,
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (self.mints, self.minted, self.burned, self.transferred)
+  }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct InscriptionEntry {
   pub charms: u16,
@@ -474,6 +631,8 @@ impl Entry for SatPoint {
   }
 }
 
+pub(super) type PartialContentValue = (Vec<u8>, Vec<u8>);
+
 pub(super) type PartialValue = Vec<u8>;
 
 impl Entry for Partial {
@@ -660,6 +819,75 @@ mod tests {
     assert_eq!(RuneId { block: 1, tx: 2 }, RuneId::load((1, 2)),);
   }
 
+  #[test]
+  fn address_history_entry() {
+    let entry = AddressHistoryEntry {
+      inscriptions: vec![1, 2],
+      received: 3,
+      runes: vec![(RuneId { block: 4, tx: 5 }, 6)],
+      sent: 7,
+      txid: txid(8),
+    };
+
+    let value = (txid(8).store(), 7, 3, vec![1, 2], vec![((4, 5), 6)]);
+
+    assert_eq!(entry.clone().store(), value);
+    assert_eq!(AddressHistoryEntry::load(value), entry);
+  }
+
+  #[test]
+  fn rune_activity_entry() {
+    let entry = RuneActivityEntry {
+      burned: 1,
+      minted: 2,
+      mints: 3,
+      transferred: 4,
+    };
+
+    let value = (3, 2, 1, 4);
+
+    assert_eq!(entry.store(), value);
+    assert_eq!(RuneActivityEntry::load(value), entry);
+  }
+
+  #[test]
+  fn inscription_transfer_entry() {
+    let old_satpoint = SatPoint {
+      outpoint: OutPoint {
+        txid: txid(1),
+        vout: 2,
+      },
+      offset: 3,
+    };
+
+    let new_satpoint = SatPoint {
+      outpoint: OutPoint {
+        txid: txid(4),
+        vout: 5,
+      },
+      offset: 6,
+    };
+
+    let entry = InscriptionTransferEntry {
+      height: 7,
+      new_satpoint,
+      old_satpoint,
+      script_pubkey: ScriptBuf::from_bytes(vec![8, 9]),
+      txid: txid(10),
+    };
+
+    let value = (
+      7,
+      txid(10).store(),
+      old_satpoint.store(),
+      new_satpoint.store(),
+      vec![8, 9],
+    );
+
+    assert_eq!(entry.clone().store(), value);
+    assert_eq!(InscriptionTransferEntry::load(value), entry);
+  }
+
   #[test]
   fn header() {
     let expected = vec![
@@ -675,6 +903,51 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn header_with_auxpow() {
+    let parent = HeaderWithoutAuxPow {
+      version: bellscoin::block::Version::ONE,
+      prev_blockhash: BlockHash::all_zeros(),
+      merkle_root: TxMerkleNode::all_zeros(),
+      time: 1,
+      bits: bellscoin::CompactTarget::from_consensus(2),
+      nonce: 3,
+    };
+
+    let header = Header {
+      version: bellscoin::block::Version::from_consensus(1 << 8),
+      prev_blockhash: BlockHash::all_zeros(),
+      merkle_root: TxMerkleNode::all_zeros(),
+      time: 4,
+      bits: bellscoin::CompactTarget::from_consensus(5),
+      nonce: 6,
+      auxpow: Some(AuxPow {
+        coinbase_tx: Chain::Mainnet.genesis_block().txdata[0].clone(),
+        block_hash: BlockHash::all_zeros(),
+        coinbase_branch: vec![TxMerkleNode::all_zeros()],
+        n_index: 0,
+        blockchain_branch: Vec::new(),
+        chain_index: 7,
+        parent_block_hash: parent,
+      }),
+    };
+
+    let value = header.clone().store();
+
+    assert_eq!(value, consensus::encode::serialize(&header));
+    assert_eq!(Header::load(value), header);
+
+    let stripped = Header {
+      auxpow: None,
+      ..header
+    };
+
+    let value = stripped.clone().store();
+
+    assert_eq!(value.len(), 80);
+    assert_eq!(Header::load(value), stripped);
+  }
+
   #[test]
   fn mintable_default() {
     assert_eq!(RuneEntry::default().mintable(0), Err(MintError::Unmintable));
@@ -1,11 +1,13 @@
-use super::*;
+use {super::*, schemars::JsonSchema};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum Event {
   InscriptionCreated {
     block_height: u32,
     charms: u16,
     inscription_id: InscriptionId,
+    #[schemars(with = "Option<String>")]
     location: Option<SatPoint>,
     parent_inscription_ids: Vec<InscriptionId>,
     sequence_number: u32,
@@ -13,32 +15,127 @@ pub enum Event {
   InscriptionTransferred {
     block_height: u32,
     inscription_id: InscriptionId,
+    #[schemars(with = "String")]
     new_location: SatPoint,
+    #[schemars(with = "String")]
     old_location: SatPoint,
     sequence_number: u32,
   },
+  /// Emitted after a reorg. Events previously emitted for `block_height` and
+  /// above are no longer valid and will be emitted again as blocks are
+  /// reindexed.
+  RolledBack { block_height: u32, depth: u32 },
   RuneBurned {
     amount: u128,
     block_height: u32,
+    #[schemars(with = "String")]
     rune_id: RuneId,
+    #[schemars(with = "String")]
     txid: Txid,
   },
   RuneEtched {
     block_height: u32,
+    #[schemars(with = "String")]
     rune_id: RuneId,
+    #[schemars(with = "String")]
     txid: Txid,
   },
   RuneMinted {
     amount: u128,
     block_height: u32,
+    #[schemars(with = "String")]
     rune_id: RuneId,
+    #[schemars(with = "String")]
     txid: Txid,
   },
   RuneTransferred {
     amount: u128,
     block_height: u32,
+    #[schemars(with = "String")]
     outpoint: OutPoint,
+    #[schemars(with = "String")]
     rune_id: RuneId,
+    #[schemars(with = "String")]
     txid: Txid,
   },
 }
+
+impl Event {
+  pub const KINDS: [&'static str; 7] = [
+    "inscription_created",
+    "inscription_transferred",
+    "rolled_back",
+    "rune_burned",
+    "rune_etched",
+    "rune_minted",
+    "rune_transferred",
+  ];
+
+  pub fn block_height(&self) -> u32 {
+    match self {
+      Self::InscriptionCreated { block_height, .. }
+      | Self::InscriptionTransferred { block_height, .. }
+      | Self::RolledBack { block_height, .. }
+      | Self::RuneBurned { block_height, .. }
+      | Self::RuneEtched { block_height, .. }
+      | Self::RuneMinted { block_height, .. }
+      | Self::RuneTransferred { block_height, .. } => *block_height,
+    }
+  }
+
+  pub fn inscription_id(&self) -> Option<InscriptionId> {
+    match self {
+      Self::InscriptionCreated { inscription_id, .. }
+      | Self::InscriptionTransferred { inscription_id, .. } => Some(*inscription_id),
+      Self::RolledBack { .. }
+      | Self::RuneBurned { .. }
+      | Self::RuneEtched { .. }
+      | Self::RuneMinted { .. }
+      | Self::RuneTransferred { .. } => None,
+    }
+  }
+
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Self::InscriptionCreated { .. } => "inscription_created",
+      Self::InscriptionTransferred { .. } => "inscription_transferred",
+      Self::RolledBack { .. } => "rolled_back",
+      Self::RuneBurned { .. } => "rune_burned",
+      Self::RuneEtched { .. } => "rune_etched",
+      Self::RuneMinted { .. } => "rune_minted",
+      Self::RuneTransferred { .. } => "rune_transferred",
+    }
+  }
+
+  /// Outputs whose owner is affected by this event. Transfers include the
+  /// spent output, so that an address sees both incoming and outgoing moves.
+  pub fn outpoints(&self) -> Vec<OutPoint> {
+    match self {
+      Self::InscriptionCreated { location, .. } => {
+        location.iter().map(|satpoint| satpoint.outpoint).collect()
+      }
+      Self::InscriptionTransferred {
+        new_location,
+        old_location,
+        ..
+      } => vec![old_location.outpoint, new_location.outpoint],
+      Self::RuneTransferred { outpoint, .. } => vec![*outpoint],
+      Self::RolledBack { .. }
+      | Self::RuneBurned { .. }
+      | Self::RuneEtched { .. }
+      | Self::RuneMinted { .. } => Vec::new(),
+    }
+  }
+
+  pub fn rune_id(&self) -> Option<RuneId> {
+    match self {
+      Self::RuneBurned { rune_id, .. }
+      | Self::RuneEtched { rune_id, .. }
+      | Self::RuneMinted { rune_id, .. }
+      | Self::RuneTransferred { rune_id, .. } => Some(*rune_id),
+      Self::InscriptionCreated { .. }
+      | Self::InscriptionTransferred { .. }
+      | Self::RolledBack { .. } => None,
+    }
+  }
+}
@@ -0,0 +1,489 @@
+use {
+  super::{fetcher::Fetcher, *},
+  redb::ReadTransaction,
+  std::ops::RangeInclusive,
+};
+
+/// A record that can be written as a row of a delimited file.
+pub trait Tabular {
+  const HEADER: &'static [&'static str];
+
+  fn fields(&self) -> Vec<String>;
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+  value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn list<T: ToString>(values: &[T]) -> String {
+  values
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<String>>()
+    .join(";")
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InscriptionRecord {
+  pub id: InscriptionId,
+  pub number: i32,
+  pub sequence_number: u32,
+  pub height: u32,
+  pub timestamp: u32,
+  pub content_type: Option<String>,
+  pub content_length: Option<usize>,
+  pub charms: Vec<Charm>,
+  pub fee: u64,
+  pub sat: Option<Sat>,
+  pub satpoint: SatPoint,
+  pub parents: Vec<InscriptionId>,
+  pub address: Option<String>,
+}
+
+impl Tabular for InscriptionRecord {
+  const HEADER: &'static [&'static str] = &[
+    "id",
+    "number",
+    "sequence_number",
+    "height",
+    "timestamp",
+    "content_type",
+    "content_length",
+    "charms",
+    "fee",
+    "sat",
+    "satpoint",
+    "parents",
+    "address",
+  ];
+
+  fn fields(&self) -> Vec<String> {
+    vec![
+      self.id.to_string(),
+      self.number.to_string(),
+      self.sequence_number.to_string(),
+      self.height.to_string(),
+      self.timestamp.to_string(),
+      optional(&self.content_type),
+      optional(&self.content_length),
+      list(&self.charms),
+      self.fee.to_string(),
+      optional(&self.sat.map(|sat| sat.n())),
+      self.satpoint.to_string(),
+      list(&self.parents),
+      optional(&self.address),
+    ]
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChildRecord {
+  pub parent: InscriptionId,
+  pub child: InscriptionId,
+  pub height: u32,
+}
+
+impl Tabular for ChildRecord {
+  const HEADER: &'static [&'static str] = &["parent", "child", "height"];
+
+  fn fields(&self) -> Vec<String> {
+    vec![
+      self.parent.to_string(),
+      self.child.to_string(),
+      self.height.to_string(),
+    ]
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneRecord {
+  pub id: RuneId,
+  pub rune: SpacedRune,
+  pub number: u64,
+  pub block: u64,
+  pub etching: Txid,
+  pub timestamp: u64,
+  pub divisibility: u8,
+  pub symbol: Option<char>,
+  pub premine: u128,
+  pub mints: u128,
+  pub burned: u128,
+  pub supply: u128,
+  pub turbo: bool,
+}
+
+impl Tabular for RuneRecord {
+  const HEADER: &'static [&'static str] = &[
+    "id",
+    "rune",
+    "number",
+    "block",
+    "etching",
+    "timestamp",
+    "divisibility",
+    "symbol",
+    "premine",
+    "mints",
+    "burned",
+    "supply",
+    "turbo",
+  ];
+
+  fn fields(&self) -> Vec<String> {
+    vec![
+      self.id.to_string(),
+      self.rune.to_string(),
+      self.number.to_string(),
+      self.block.to_string(),
+      self.etching.to_string(),
+      self.timestamp.to_string(),
+      self.divisibility.to_string(),
+      optional(&self.symbol),
+      self.premine.to_string(),
+      self.mints.to_string(),
+      self.burned.to_string(),
+      self.supply.to_string(),
+      self.turbo.to_string(),
+    ]
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneBalanceRecord {
+  pub outpoint: OutPoint,
+  pub id: RuneId,
+  pub rune: SpacedRune,
+  pub amount: u128,
+}
+
+impl Tabular for RuneBalanceRecord {
+  const HEADER: &'static [&'static str] = &["outpoint", "id", "rune", "amount"];
+
+  fn fields(&self) -> Vec<String> {
+    vec![
+      self.outpoint.to_string(),
+      self.id.to_string(),
+      self.rune.to_string(),
+      self.amount.to_string(),
+    ]
+  }
+}
+
+impl Index {
+  pub(super) fn export_address(
+    &self,
+    outpoint_to_utxo_entry: &impl ReadableTable<&'static OutPointValue, &'static UtxoEntry>,
+    satpoint: SatPoint,
+  ) -> Result<String> {
+    if satpoint.outpoint == unbound_outpoint() {
+      return Ok("unbound".into());
+    }
+
+    let script_pubkey = if self.index_addresses {
+      ScriptBuf::from_bytes(
+        outpoint_to_utxo_entry
+          .get(&satpoint.outpoint.store())?
+          .unwrap()
+          .value()
+          .parse(self)
+          .script_pubkey()
+          .to_vec(),
+      )
+    } else {
+      self
+        .get_transaction(satpoint.outpoint.txid)?
+        .unwrap()
+        .output
+        .into_iter()
+        .nth(satpoint.outpoint.vout.try_into().unwrap())
+        .unwrap()
+        .script_pubkey
+    };
+
+    Ok(
+      self
+        .settings
+        .chain()
+        .address_from_script(&script_pubkey)
+        .map(|address| address.to_string())
+        .unwrap_or_else(|e| e.to_string()),
+    )
+  }
+
+  /// Calls `f` with every inscription entry revealed in `heights`, in
+  /// sequence number order.
+  fn for_each_inscription_entry(
+    &self,
+    rtx: &ReadTransaction,
+    heights: &RangeInclusive<u32>,
+    mut f: impl FnMut(InscriptionEntry) -> Result,
+  ) -> Result {
+    let start = match heights.start().checked_sub(1) {
+      Some(height) => rtx
+        .open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?
+        .range(..=height)?
+        .next_back()
+        .transpose()?
+        .map(|(_height, sequence_number)| sequence_number.value())
+        .unwrap_or_default(),
+      None => 0,
+    };
+
+    for result in rtx
+      .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+      .range(start..)?
+    {
+      let entry = InscriptionEntry::load(result?.1.value());
+
+      if entry.height > *heights.end() {
+        break;
+      }
+
+      if heights.contains(&entry.height) {
+        f(entry)?;
+      }
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Inscriptions whose content is looked up together, so that transactions
+  /// the index does not store are fetched with one batched RPC request.
+  const EXPORT_BATCH_SIZE: usize = 1000;
+
+  pub fn export_inscriptions(
+    &self,
+    heights: RangeInclusive<u32>,
+    include_addresses: bool,
+    mut f: impl FnMut(InscriptionRecord) -> Result,
+  ) -> Result {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_entry = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+    let sequence_number_to_satpoint = rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+    let outpoint_to_utxo_entry = rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    let fetcher = Fetcher::new(&self.settings)?;
+    let runtime = Runtime::new()?;
+
+    let mut write = |entries: &mut Vec<InscriptionEntry>| -> Result {
+      let inscriptions = self.export_inscription_batch(&fetcher, &runtime, entries)?;
+
+      for (entry, inscription) in entries.drain(..).zip(inscriptions) {
+        let satpoint = SatPoint::load(
+          *sequence_number_to_satpoint
+            .get(entry.sequence_number)?
+            .unwrap()
+            .value(),
+        );
+
+        let parents = entry
+          .parents
+          .iter()
+          .map(|parent| {
+            Ok(InscriptionEntry::load(sequence_number_to_entry.get(parent)?.unwrap().value()).id)
+          })
+          .collect::<Result<Vec<InscriptionId>>>()?;
+
+        let address = if include_addresses {
+          Some(self.export_address(&outpoint_to_utxo_entry, satpoint)?)
+        } else {
+          None
+        };
+
+        f(InscriptionRecord {
+          id: entry.id,
+          number: entry.inscription_number,
+          sequence_number: entry.sequence_number,
+          height: entry.height,
+          timestamp: entry.timestamp,
+          content_type: inscription
+            .as_ref()
+            .and_then(|inscription| inscription.content_type())
+            .map(str::to_string),
+          content_length: inscription.and_then(|inscription| inscription.content_length()),
+          charms: Charm::charms(entry.charms),
+          fee: entry.fee,
+          sat: entry.sat,
+          satpoint,
+          parents,
+          address,
+        })?;
+      }
+
+      Ok(())
+    };
+
+    let mut entries = Vec::with_capacity(Self::EXPORT_BATCH_SIZE);
+
+    self.for_each_inscription_entry(&rtx, &heights, |entry| {
+      entries.push(entry);
+
+      if entries.len() == Self::EXPORT_BATCH_SIZE {
+        write(&mut entries)?;
+      }
+
+      Ok(())
+    })?;
+
+    write(&mut entries)
+  }
+
+  /// Returns the inscription of each entry, reading partial content and
+  /// stored transactions from the index and fetching the remaining
+  /// transactions from the node in a single batch.
+  fn export_inscription_batch(
+    &self,
+    fetcher: &Fetcher,
+    runtime: &Runtime,
+    entries: &[InscriptionEntry],
+  ) -> Result<Vec<Option<Inscription>>> {
+    let mut inscriptions = Vec::with_capacity(entries.len());
+    let mut transactions = HashMap::new();
+    let mut missing = Vec::new();
+
+    for entry in entries {
+      let inscription = self.get_partial_inscription(entry.id)?;
+
+      if inscription.is_none() && !transactions.contains_key(&entry.id.txid) {
+        match self.get_stored_transaction(entry.id.txid)? {
+          Some(transaction) => {
+            transactions.insert(entry.id.txid, transaction);
+          }
+          None if !missing.contains(&entry.id.txid) => missing.push(entry.id.txid),
+          None => {}
+        }
+      }
+
+      inscriptions.push(inscription);
+    }
+
+    if !missing.is_empty() {
+      let fetched = runtime.block_on(fetcher.get_transactions(missing.clone()))?;
+      transactions.extend(missing.into_iter().zip(fetched));
+    }
+
+    Ok(
+      entries
+        .iter()
+        .zip(inscriptions)
+        .map(|(entry, inscription)| {
+          inscription.or_else(|| {
+            ParsedEnvelope::from_transaction(transactions.get(&entry.id.txid)?)
+              .into_iter()
+              .nth(entry.id.index as usize)
+              .map(|envelope| envelope.payload)
+          })
+        })
+        .collect(),
+    )
+  }
+
+  pub fn export_children(
+    &self,
+    heights: RangeInclusive<u32>,
+    mut f: impl FnMut(ChildRecord) -> Result,
+  ) -> Result {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_entry = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    self.for_each_inscription_entry(&rtx, &heights, |entry| {
+      for parent in &entry.parents {
+        f(ChildRecord {
+          parent: InscriptionEntry::load(sequence_number_to_entry.get(parent)?.unwrap().value()).id,
+          child: entry.id,
+          height: entry.height,
+        })?;
+      }
+
+      Ok(())
+    })
+  }
+
+  pub fn export_runes(
+    &self,
+    heights: RangeInclusive<u32>,
+    mut f: impl FnMut(RuneRecord) -> Result,
+  ) -> Result {
+    let rtx = self.database.begin_read()?;
+
+    for result in rtx
+      .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+      .range((u64::from(*heights.start()), 0)..=(u64::from(*heights.end()), u32::MAX))?
+    {
+      let (id, entry) = result?;
+      let id = RuneId::load(id.value());
+      let entry = RuneEntry::load(entry.value());
+
+      f(RuneRecord {
+        id,
+        rune: entry.spaced_rune,
+        number: entry.number,
+        block: entry.block,
+        etching: entry.etching,
+        timestamp: entry.timestamp,
+        divisibility: entry.divisibility,
+        symbol: entry.symbol,
+        premine: entry.premine,
+        mints: entry.mints,
+        burned: entry.burned,
+        supply: entry.supply(),
+        turbo: entry.turbo,
+      })?;
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn export_rune_balances(&self, mut f: impl FnMut(RuneBalanceRecord) -> Result) -> Result {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let mut runes = HashMap::new();
+
+    for result in rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?.iter()? {
+      let (outpoint, balances) = result?;
+      let outpoint = OutPoint::load(*outpoint.value());
+      let balances = balances.value();
+
+      let mut i = 0;
+      while i < balances.len() {
+        let ((id, amount), length) = Index::decode_rune_balance(&balances[i..])?;
+        i += length;
+
+        let rune = match runes.get(&id) {
+          Some(rune) => *rune,
+          None => {
+            let rune =
+              RuneEntry::load(rune_id_to_rune_entry.get(id.store())?.unwrap().value()).spaced_rune;
+            runes.insert(id, rune);
+            rune
+          }
+        };
+
+        f(RuneBalanceRecord {
+          outpoint,
+          id,
+          rune,
+          amount,
+        })?;
+      }
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+
+    Ok(())
+  }
+}
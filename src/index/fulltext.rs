@@ -0,0 +1,154 @@
+use {super::*, std::str};
+
+const MAX_TERM_LENGTH: usize = 64;
+
+/// Queries only score the most recent postings of each term, so that common
+/// terms don't load the whole index.
+pub(crate) const MAX_POSTINGS: usize = 10_000;
+
+// Okapi BM25 parameters
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// Body of `inscription` as text, if it has a text, JSON or markdown content
+/// type and an uncompressed UTF-8 body.
+pub(crate) fn text(inscription: &Inscription) -> Option<&str> {
+  if inscription.content_encoding().is_some() {
+    return None;
+  }
+
+  let essence = inscription
+    .content_type()?
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_ascii_lowercase();
+
+  if !(essence.starts_with("text/") || essence == "application/json") {
+    return None;
+  }
+
+  str::from_utf8(inscription.body()?).ok()
+}
+
+/// Lowercased alphanumeric words of `text`, skipping overly long ones.
+pub(crate) fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+  text
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|term| !term.is_empty() && term.len() <= MAX_TERM_LENGTH)
+    .map(str::to_lowercase)
+}
+
+pub(crate) fn term_frequencies(text: &str) -> BTreeMap<String, u32> {
+  let mut frequencies = BTreeMap::new();
+
+  for term in terms(text) {
+    *frequencies.entry(term).or_default() += 1;
+  }
+
+  frequencies
+}
+
+/// Relevance of a single query term to an inscription, where `documents` is
+/// the number of indexed inscriptions and `matches` the number containing the
+/// term.
+pub(crate) fn score(
+  frequency: u32,
+  term_count: u32,
+  average_term_count: f64,
+  documents: u64,
+  matches: u64,
+) -> f64 {
+  let documents = documents as f64;
+  let matches = matches as f64;
+  let frequency = f64::from(frequency);
+
+  let idf = (1.0 + (documents - matches + 0.5) / (matches + 0.5)).ln();
+
+  let normalization = 1.0 - B + B * f64::from(term_count) / average_term_count.max(1.0);
+
+  idf * frequency * (K1 + 1.0) / (frequency + K1 * normalization)
+}
+
+/// A scored search result. Hits order from most to least relevant, with older
+/// inscriptions first among equally relevant ones.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Hit {
+  pub(crate) score: f64,
+  pub(crate) sequence_number: u32,
+}
+
+impl Eq for Hit {}
+
+impl PartialOrd for Hit {
+  fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Hit {
+  fn cmp(&self, other: &Self) -> cmp::Ordering {
+    other
+      .score
+      .total_cmp(&self.score)
+      .then(self.sequence_number.cmp(&other.sequence_number))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn text_requires_textual_content_type() {
+    assert_eq!(text(&inscription("text/plain", "foo")), Some("foo"));
+    assert_eq!(
+      text(&inscription("text/markdown;charset=utf-8", "# foo")),
+      Some("# foo")
+    );
+    assert_eq!(text(&inscription("application/json", "{}")), Some("{}"));
+    assert_eq!(text(&inscription("image/png", "foo")), None);
+    assert_eq!(text(&inscription("text/plain", [0xff, 0xfe])), None);
+  }
+
+  #[test]
+  fn terms_are_lowercase_words() {
+    assert_eq!(
+      terms("Hello, WORLD! {\"p\":\"brc-20\"}").collect::<Vec<String>>(),
+      ["hello", "world", "p", "brc", "20"],
+    );
+  }
+
+  #[test]
+  fn term_frequencies_are_counted() {
+    assert_eq!(
+      term_frequencies("foo bar foo"),
+      BTreeMap::from([("bar".to_string(), 1), ("foo".to_string(), 2)]),
+    );
+  }
+
+  #[test]
+  fn rarer_terms_score_higher() {
+    assert!(score(1, 10, 10.0, 100, 1) > score(1, 10, 10.0, 100, 50));
+  }
+
+  #[test]
+  fn shorter_inscriptions_score_higher() {
+    assert!(score(1, 5, 10.0, 100, 1) > score(1, 50, 10.0, 100, 1));
+  }
+
+  #[test]
+  fn hits_order_by_relevance_then_age() {
+    let hit = |score, sequence_number| Hit {
+      score,
+      sequence_number,
+    };
+
+    let mut hits = vec![hit(1.0, 0), hit(2.0, 2), hit(2.0, 1)];
+
+    hits.sort();
+
+    assert_eq!(hits, [hit(2.0, 1), hit(2.0, 2), hit(1.0, 0)]);
+  }
+}
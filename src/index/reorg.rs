@@ -1,4 +1,7 @@
-use {super::*, updater::BlockData};
+use {
+  super::*,
+  updater::{BlockData, EventEmitter},
+};
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum Error {
@@ -19,10 +22,6 @@ impl Display for Error {
 
 impl std::error::Error for Error {}
 
-const MAX_SAVEPOINTS: u32 = 2;
-const SAVEPOINT_INTERVAL: u32 = 10;
-const CHAIN_TIP_DISTANCE: u32 = 21;
-
 pub(crate) struct Reorg {}
 
 impl Reorg {
@@ -32,8 +31,12 @@ impl Reorg {
     match index.block_hash(height.checked_sub(1))? {
       Some(index_prev_blockhash) if index_prev_blockhash == bitcoind_prev_blockhash => Ok(()),
       Some(index_prev_blockhash) if index_prev_blockhash != bitcoind_prev_blockhash => {
-        let max_recoverable_reorg_depth =
-          (MAX_SAVEPOINTS - 1) * SAVEPOINT_INTERVAL + height % SAVEPOINT_INTERVAL;
+        let savepoint_interval = index.settings.savepoint_interval();
+
+        let max_recoverable_reorg_depth = (index.settings.max_savepoints() - 1)
+          .saturating_mul(savepoint_interval)
+          .saturating_add(height % savepoint_interval)
+          .max(index.settings.undo_log_depth());
 
         for depth in 1..max_recoverable_reorg_depth {
           let index_block_hash = index.block_hash(height.checked_sub(depth))?;
@@ -56,26 +59,109 @@ impl Reorg {
   pub(crate) fn handle_reorg(index: &Index, height: u32, depth: u32) -> Result {
     log::info!("rolling back database after reorg of depth {depth} at height {height}");
 
-    if let redb::Durability::None = index.durability {
-      panic!("set index durability to `Durability::Immediate` to test reorg handling");
-    }
+    // the first block which is no longer part of the best chain
+    let fork_height = height.saturating_sub(depth) + 1;
+
+    let block_count = Self::rollback(index, fork_height)?;
 
+    log::info!("successfully rolled back database to height {block_count}");
+
+    Ok(())
+  }
+
+  /// Rolls the index back so that it contains no blocks at or above `height`,
+  /// returning the new block count. The undo log is used if it covers the
+  /// rollback, otherwise the newest persistent savepoint taken before `height`
+  /// is restored.
+  pub(crate) fn rollback(index: &Index, height: u32) -> Result<u32> {
     let mut wtx = index.begin_write()?;
 
-    let oldest_savepoint =
-      wtx.get_persistent_savepoint(wtx.list_persistent_savepoints()?.min().unwrap())?;
+    let old_block_count = Self::block_count(&wtx)?;
+
+    let savepoints = Self::savepoint_block_counts(&wtx)?;
 
-    wtx.restore_savepoint(&oldest_savepoint)?;
+    let block_count = match UndoLog::rollback(&wtx, height)? {
+      Some(block_count) => {
+        log::info!("rolled back database using undo log");
+
+        // savepoints taken after the rollback point may contain blocks which
+        // are no longer part of the best chain
+        let mut savepoint_id_to_block_count = wtx.open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?;
+        for (id, savepoint_block_count) in &savepoints {
+          if *savepoint_block_count > block_count {
+            wtx.delete_persistent_savepoint(*id)?;
+            savepoint_id_to_block_count.remove(id)?;
+          }
+        }
+
+        block_count
+      }
+      None => {
+        if let redb::Durability::None = index.durability {
+          panic!("set index durability to `Durability::Immediate` to test reorg handling");
+        }
+
+        let (id, block_count) = savepoints
+          .iter()
+          .copied()
+          .filter(|(_id, block_count)| *block_count <= height)
+          .max_by_key(|(_id, block_count)| *block_count)
+          .ok_or_else(|| anyhow!("no savepoint or undo log to roll back to height {height}"))?;
+
+        wtx.restore_savepoint(&wtx.get_persistent_savepoint(id)?)?;
+
+        // savepoints taken after the restored one belong to the abandoned chain
+        for (newer, _block_count) in savepoints.iter().filter(|(other, _)| *other > id) {
+          wtx.delete_persistent_savepoint(*newer)?;
+        }
+
+        // the restored state predates the savepoint's own block count entry
+        wtx
+          .open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?
+          .insert(id, block_count)?;
+
+        block_count
+      }
+    };
+
+    EventEmitter::new(index, &wtx, block_count, &UndoLog::disabled())?.emit(Event::RolledBack {
+      block_height: block_count,
+      depth: old_block_count.saturating_sub(block_count),
+    })?;
 
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
-    log::info!(
-      "successfully rolled back database to height {}",
-      index.begin_read()?.block_count()?
-    );
+    Ok(block_count)
+  }
 
-    Ok(())
+  /// Returns the block count at which each persistent savepoint was taken.
+  /// Every savepoint records its block count when it is created, and indexes
+  /// built before that have an older schema version and cannot be opened.
+  fn savepoint_block_counts(wtx: &WriteTransaction) -> Result<Vec<(u64, u32)>> {
+    let savepoint_id_to_block_count = wtx.open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?;
+
+    let mut savepoints = Vec::new();
+
+    for id in wtx.list_persistent_savepoints()? {
+      if let Some(block_count) = savepoint_id_to_block_count.get(id)? {
+        savepoints.push((id, block_count.value()));
+      }
+    }
+
+    Ok(savepoints)
+  }
+
+  fn block_count(wtx: &WriteTransaction) -> Result<u32> {
+    Ok(
+      wtx
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .range(0..)?
+        .next_back()
+        .transpose()?
+        .map(|(height, _header)| height.value() + 1)
+        .unwrap_or(0),
+    )
   }
 
   pub(crate) fn update_savepoints(index: &Index, height: u32) -> Result {
@@ -83,7 +169,10 @@ impl Reorg {
       return Ok(());
     }
 
-    if (height < SAVEPOINT_INTERVAL || height % SAVEPOINT_INTERVAL == 0)
+    let max_savepoints = index.settings.max_savepoints();
+    let savepoint_interval = index.settings.savepoint_interval();
+
+    if (height < savepoint_interval || height % savepoint_interval == 0)
       && u32::try_from(
         index
           .settings
@@ -93,14 +182,25 @@ impl Reorg {
       )
       .unwrap()
       .saturating_sub(height)
-        <= CHAIN_TIP_DISTANCE
+        <= max_savepoints
+          .saturating_mul(savepoint_interval)
+          .saturating_add(1)
     {
       let wtx = index.begin_write()?;
 
-      let savepoints = wtx.list_persistent_savepoints()?.collect::<Vec<u64>>();
+      let mut savepoints = wtx.list_persistent_savepoints()?.collect::<Vec<u64>>();
+      savepoints.sort_unstable();
+
+      // make room for the new savepoint, dropping any excess left over from a
+      // previous run with a higher `--max-savepoints`
+      let excess = (savepoints.len() + 1).saturating_sub(usize::try_from(max_savepoints).unwrap());
 
-      if savepoints.len() >= usize::try_from(MAX_SAVEPOINTS).unwrap() {
-        wtx.delete_persistent_savepoint(savepoints.into_iter().min().unwrap())?;
+      {
+        let mut savepoint_id_to_block_count = wtx.open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?;
+        for savepoint in &savepoints[..excess] {
+          wtx.delete_persistent_savepoint(*savepoint)?;
+          savepoint_id_to_block_count.remove(savepoint)?;
+        }
       }
 
       Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
@@ -109,7 +209,11 @@ impl Reorg {
       let wtx = index.begin_write()?;
 
       log::debug!("creating savepoint at height {}", height);
-      wtx.persistent_savepoint()?;
+      let id = wtx.persistent_savepoint()?;
+
+      wtx
+        .open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?
+        .insert(id, height)?;
 
       Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
       wtx.commit()?;
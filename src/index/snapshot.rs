@@ -0,0 +1,239 @@
+use {
+  super::*,
+  bellscoin::hashes::{sha256, HashEngine},
+  std::io::{BufReader, Read},
+};
+
+const MAGIC: [u8; 8] = *b"ORDSNAP1";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+  pub block_hash: BlockHash,
+  pub chain: Chain,
+  pub database_bytes: u64,
+  pub height: u32,
+  pub schema_version: u64,
+  pub sha256: sha256::Hash,
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+  let mut path = path.as_os_str().to_owned();
+  path.push(suffix);
+  path.into()
+}
+
+/// Copies `len` bytes from `reader` to `writer`, returning their SHA-256.
+fn copy_and_hash(
+  reader: &mut impl Read,
+  writer: &mut impl Write,
+  len: u64,
+) -> Result<sha256::Hash> {
+  let mut engine = sha256::Hash::engine();
+  let mut buffer = vec![0; 1 << 20];
+  let mut remaining = len;
+
+  while remaining > 0 {
+    let n = reader.read(&mut buffer[..usize::try_from(remaining.min(1 << 20)).unwrap()])?;
+    ensure!(
+      n > 0,
+      "snapshot truncated: {remaining} database bytes missing"
+    );
+    engine.input(&buffer[..n]);
+    writer.write_all(&buffer[..n])?;
+    remaining -= u64::try_from(n).unwrap();
+  }
+
+  Ok(sha256::Hash::from_engine(engine))
+}
+
+fn tip(database: &Database) -> Result<Option<(u32, BlockHash)>> {
+  Ok(
+    database
+      .begin_read()?
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, header)| {
+        (
+          height.value(),
+          Header::load(header.value().to_vec()).block_hash(),
+        )
+      }),
+  )
+}
+
+impl Index {
+  /// Writes a compacted copy of the index to `output`, preceded by a
+  /// manifest identifying the chain tip and schema it was built with. The
+  /// index is closed first so that the database file is consistent on disk.
+  pub fn create_snapshot(self, output: &Path) -> Result<Snapshot> {
+    let (height, block_hash) =
+      tip(&self.database)?.ok_or_else(|| anyhow!("cannot snapshot an empty index"))?;
+
+    let chain = self.settings.chain();
+    let path = self.path.clone();
+
+    drop(self);
+
+    let working = with_suffix(output, ".partial");
+
+    fs::copy(&path, &working)?;
+
+    {
+      let mut database = Database::create(&working)?;
+
+      // savepoints can't be restored into a copy of the database and they
+      // prevent compaction, so drop them along with their block counts
+      let wtx = database.begin_write()?;
+      for savepoint in wtx.list_persistent_savepoints()?.collect::<Vec<u64>>() {
+        wtx.delete_persistent_savepoint(savepoint)?;
+      }
+      wtx
+        .open_table(SAVEPOINT_ID_TO_BLOCK_COUNT)?
+        .retain(|_, _| false)?;
+      wtx.commit()?;
+
+      while database.compact()? {}
+    }
+
+    let database_bytes = fs::metadata(&working)?.len();
+
+    let sha256 = copy_and_hash(
+      &mut BufReader::new(fs::File::open(&working)?),
+      &mut io::sink(),
+      database_bytes,
+    )?;
+
+    let snapshot = Snapshot {
+      block_hash,
+      chain,
+      database_bytes,
+      height,
+      schema_version: SCHEMA_VERSION,
+      sha256,
+    };
+
+    let mut writer = BufWriter::new(fs::File::create(output)?);
+
+    let encoded = serde_json::to_vec(&snapshot)?;
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&u32::try_from(encoded.len()).unwrap().to_le_bytes())?;
+    writer.write_all(&encoded)?;
+
+    io::copy(&mut fs::File::open(&working)?, &mut writer)?;
+    writer.flush()?;
+
+    fs::remove_file(&working)?;
+
+    Ok(snapshot)
+  }
+
+  /// Installs the snapshot at `input` as the index for `settings`, after
+  /// checking that it matches this version of ord, the configured chain, and
+  /// the connected node's block at the snapshot height.
+  pub fn restore_snapshot(settings: &Settings, input: &Path, force: bool) -> Result<Snapshot> {
+    let mut reader = BufReader::new(fs::File::open(input)?);
+
+    let mut magic = [0; 8];
+    reader
+      .read_exact(&mut magic)
+      .ok()
+      .filter(|()| magic == MAGIC)
+      .ok_or_else(|| anyhow!("`{}` is not an ord index snapshot", input.display()))?;
+
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let mut encoded = vec![0; usize::try_from(u32::from_le_bytes(len)).unwrap()];
+    reader.read_exact(&mut encoded)?;
+    let snapshot = serde_json::from_slice::<Snapshot>(&encoded)?;
+
+    ensure!(
+      snapshot.schema_version == SCHEMA_VERSION,
+      "snapshot schema {} does not match ord schema {SCHEMA_VERSION}",
+      snapshot.schema_version,
+    );
+
+    ensure!(
+      snapshot.chain == settings.chain(),
+      "snapshot is for {}, not {}",
+      snapshot.chain,
+      settings.chain(),
+    );
+
+    let node_hash = settings
+      .bitcoin_rpc_client(None)?
+      .get_block_hash(snapshot.height.into())
+      .into_option()?;
+
+    ensure!(
+      node_hash == Some(snapshot.block_hash),
+      "snapshot block {} at height {} is not in the connected node's best chain",
+      snapshot.block_hash,
+      snapshot.height,
+    );
+
+    let path = settings.index().to_owned();
+
+    ensure!(
+      force || !path.exists(),
+      "index at `{}` already exists, use --force to replace it",
+      path.display(),
+    );
+
+    if let Some(data_dir) = path.parent() {
+      fs::create_dir_all(data_dir)?;
+    }
+
+    let working = with_suffix(&path, ".snapshot");
+
+    let result = (|| {
+      let mut writer = BufWriter::new(fs::File::create(&working)?);
+      let sha256 = copy_and_hash(&mut reader, &mut writer, snapshot.database_bytes)?;
+      writer.flush()?;
+
+      ensure!(
+        reader.read(&mut [0])? == 0,
+        "snapshot contains trailing data after {} database bytes",
+        snapshot.database_bytes,
+      );
+
+      ensure!(
+        sha256 == snapshot.sha256,
+        "snapshot checksum mismatch: expected {}, got {sha256}",
+        snapshot.sha256,
+      );
+
+      let database = Database::open(&working)?;
+
+      let schema_version = database
+        .begin_read()?
+        .open_table(STATISTIC_TO_COUNT)?
+        .get(&Statistic::Schema.key())?
+        .map(|x| x.value())
+        .unwrap_or(0);
+
+      ensure!(
+        schema_version == snapshot.schema_version,
+        "snapshot database schema {schema_version} does not match manifest schema {}",
+        snapshot.schema_version,
+      );
+
+      ensure!(
+        tip(&database)? == Some((snapshot.height, snapshot.block_hash)),
+        "snapshot database tip does not match manifest",
+      );
+
+      Ok(())
+    })();
+
+    if let Err(err) = result {
+      fs::remove_file(&working).ok();
+      return Err(err);
+    }
+
+    fs::rename(&working, &path)?;
+
+    Ok(snapshot)
+  }
+}
@@ -134,6 +134,80 @@ impl Context {
 
     pretty_assert_eq!(balances, self.index.get_rune_balances().unwrap());
 
+    for (id, _) in runes.iter() {
+      let (holders, outputs, addresses, more) =
+        self.index.get_rune_holders(*id, usize::MAX, 0).unwrap();
+
+      assert!(!more);
+      assert!(holders.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+      let expected = balances
+        .iter()
+        .filter_map(|(outpoint, balances)| {
+          balances
+            .iter()
+            .find(|(rune_id, _)| rune_id == id)
+            .map(|(_, amount)| (*outpoint, *amount))
+        })
+        .collect::<Vec<(OutPoint, u128)>>();
+
+      assert_eq!(outputs, u64::try_from(expected.len()).unwrap());
+
+      if self.index.has_address_index() {
+        let mut expected_by_script_pubkey = BTreeMap::<ScriptBuf, u128>::new();
+
+        for (outpoint, amount) in expected {
+          let script_pubkey = self
+            .index
+            .get_transaction(outpoint.txid)
+            .unwrap()
+            .unwrap()
+            .output[usize::try_from(outpoint.vout).unwrap()]
+          .script_pubkey
+          .clone();
+
+          *expected_by_script_pubkey.entry(script_pubkey).or_default() += amount;
+        }
+
+        assert_eq!(
+          addresses,
+          Some(u64::try_from(expected_by_script_pubkey.len()).unwrap())
+        );
+
+        let holders = holders
+          .into_iter()
+          .map(|(holder, amount)| match holder {
+            api::RuneHolder::Address(address) => (
+              address
+                .parse::<Address<NetworkUnchecked>>()
+                .unwrap()
+                .assume_checked()
+                .script_pubkey(),
+              amount,
+            ),
+            api::RuneHolder::ScriptPubkey(script_pubkey) => (script_pubkey, amount),
+            holder => panic!("unexpected holder {holder:?}"),
+          })
+          .collect::<BTreeMap<ScriptBuf, u128>>();
+
+        pretty_assert_eq!(holders, expected_by_script_pubkey);
+      } else {
+        assert_eq!(addresses, None);
+
+        let mut holders = holders
+          .into_iter()
+          .map(|(holder, amount)| match holder {
+            api::RuneHolder::Output(outpoint) => (outpoint, amount),
+            holder => panic!("unexpected holder {holder:?}"),
+          })
+          .collect::<Vec<(OutPoint, u128)>>();
+
+        holders.sort();
+
+        pretty_assert_eq!(holders, expected);
+      }
+    }
+
     let mut outstanding: HashMap<RuneId, u128> = HashMap::new();
 
     for (_, balances) in balances {
@@ -153,7 +227,7 @@ impl Context {
   pub(crate) fn etch(&self, runestone: Runestone, outputs: usize) -> (Txid, RuneId) {
     let block_count = usize::try_from(self.index.block_count().unwrap()).unwrap();
 
-    self.mine_blocks(1);
+    self.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
     self.core.broadcast_tx(TransactionTemplate {
       inputs: &[(block_count, 0, 0, Witness::new())],
@@ -161,7 +235,7 @@ impl Context {
       ..default()
     });
 
-    self.mine_blocks(Runestone::COMMIT_CONFIRMATIONS.into());
+    self.mine_blocks_with_subsidy(Runestone::COMMIT_CONFIRMATIONS.into(), 2 * COIN_VALUE);
 
     let mut witness = Witness::new();
 
@@ -192,7 +266,7 @@ impl Context {
       ..default()
     });
 
-    self.mine_blocks(1);
+    self.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
     (
       txid,
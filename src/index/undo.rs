@@ -0,0 +1,476 @@
+use {
+  super::*,
+  redb::{AccessGuard, Key, MultimapTableDefinition, TableError},
+  std::{borrow::Borrow, cell::RefCell, ops::Deref, rc::Rc},
+};
+
+/// The write that reverts a change made to an index table while indexing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+  Insert = 0,
+  Remove = 1,
+  MultimapInsert = 2,
+  MultimapRemove = 3,
+}
+
+impl TryFrom<u8> for Action {
+  type Error = Error;
+
+  fn try_from(n: u8) -> Result<Self> {
+    Ok(match n {
+      0 => Self::Insert,
+      1 => Self::Remove,
+      2 => Self::MultimapInsert,
+      3 => Self::MultimapRemove,
+      _ => bail!("invalid undo record action {n}"),
+    })
+  }
+}
+
+#[derive(Debug, PartialEq)]
+struct Record {
+  action: Action,
+  table: String,
+  key: Vec<u8>,
+  value: Vec<u8>,
+}
+
+impl Record {
+  fn encode(action: Action, table: &str, key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(6 + table.len() + key.len() + value.len());
+    buffer.push(action as u8);
+    buffer.push(u8::try_from(table.len()).unwrap());
+    buffer.extend_from_slice(table.as_bytes());
+    buffer.extend_from_slice(&u32::try_from(key.len()).unwrap().to_le_bytes());
+    buffer.extend_from_slice(key);
+    buffer.extend_from_slice(value);
+    buffer
+  }
+
+  fn decode(buffer: &[u8]) -> Result<Self> {
+    let (&action, buffer) = buffer.split_first().context("truncated undo record")?;
+    let (&table_len, buffer) = buffer.split_first().context("truncated undo record")?;
+
+    let table_len = usize::from(table_len);
+    ensure!(buffer.len() >= table_len + 4, "truncated undo record");
+    let (table, buffer) = buffer.split_at(table_len);
+    let (key_len, buffer) = buffer.split_at(4);
+
+    let key_len = usize::try_from(u32::from_le_bytes(key_len.try_into().unwrap())).unwrap();
+    ensure!(buffer.len() >= key_len, "truncated undo record");
+    let (key, value) = buffer.split_at(key_len);
+
+    Ok(Self {
+      action: action.try_into()?,
+      table: String::from_utf8(table.into())?,
+      key: key.into(),
+      value: value.into(),
+    })
+  }
+
+  fn revert<K: Key + 'static, V: redb::Value + 'static>(
+    &self,
+    wtx: &WriteTransaction,
+    definition: TableDefinition<K, V>,
+  ) -> Result {
+    let mut table = wtx.open_table(definition)?;
+
+    match self.action {
+      Action::Insert => {
+        table.insert(K::from_bytes(&self.key), V::from_bytes(&self.value))?;
+      }
+      Action::Remove => {
+        table.remove(K::from_bytes(&self.key))?;
+      }
+      action => bail!("cannot apply {action:?} to table `{}`", self.table),
+    }
+
+    Ok(())
+  }
+
+  fn revert_multimap<K: Key + 'static, V: Key + 'static>(
+    &self,
+    wtx: &WriteTransaction,
+    definition: MultimapTableDefinition<K, V>,
+  ) -> Result {
+    let mut table = wtx.open_multimap_table(definition)?;
+
+    match self.action {
+      Action::MultimapInsert => {
+        table.insert(K::from_bytes(&self.key), V::from_bytes(&self.value))?;
+      }
+      Action::MultimapRemove => {
+        table.remove(K::from_bytes(&self.key), V::from_bytes(&self.value))?;
+      }
+      action => bail!("cannot apply {action:?} to multimap table `{}`", self.table),
+    }
+
+    Ok(())
+  }
+}
+
+macro_rules! revert {
+  {
+    $wtx:expr, $record:expr,
+    tables: [$($table:ident),* $(,)?],
+    multimap_tables: [$($multimap_table:ident),* $(,)?] $(,)?
+  } => {
+    match $record.table.as_str() {
+      $(name if name == $table.name() => $record.revert($wtx, $table),)*
+      $(name if name == $multimap_table.name() => $record.revert_multimap($wtx, $multimap_table),)*
+      name => Err(anyhow!("undo record references unknown table `{name}`")),
+    }
+  }
+}
+
+/// Pre-images of the index writes made while indexing one write transaction's
+/// worth of blocks. Records are kept in memory until the write transaction is
+/// committed, and are stored under the starting block count of that write
+/// transaction, so the index can later be rolled back to that block count by
+/// applying them in reverse.
+#[derive(Clone)]
+pub(crate) struct UndoLog {
+  height: u32,
+  records: Option<Rc<RefCell<Vec<Vec<u8>>>>>,
+}
+
+impl UndoLog {
+  pub(crate) fn new(height: u32, enabled: bool) -> Self {
+    Self {
+      height,
+      records: enabled.then(Default::default),
+    }
+  }
+
+  pub(crate) fn disabled() -> Self {
+    Self::new(0, false)
+  }
+
+  pub(crate) fn open_table<'tx, K: Key + 'static, V: redb::Value + 'static>(
+    &self,
+    wtx: &'tx WriteTransaction,
+    definition: TableDefinition<K, V>,
+  ) -> Result<UndoTable<'tx, K, V>, TableError> {
+    Ok(UndoTable {
+      log: self.clone(),
+      name: definition.name().into(),
+      table: wtx.open_table(definition)?,
+    })
+  }
+
+  pub(crate) fn open_multimap_table<'tx, K: Key + 'static, V: Key + 'static>(
+    &self,
+    wtx: &'tx WriteTransaction,
+    definition: MultimapTableDefinition<K, V>,
+  ) -> Result<UndoMultimapTable<'tx, K, V>, TableError> {
+    Ok(UndoMultimapTable {
+      log: self.clone(),
+      name: definition.name().into(),
+      table: wtx.open_multimap_table(definition)?,
+    })
+  }
+
+  fn recording(&self) -> bool {
+    self.records.is_some()
+  }
+
+  fn record(&self, action: Action, table: &str, key: &[u8], value: &[u8]) {
+    if let Some(records) = &self.records {
+      records
+        .borrow_mut()
+        .push(Record::encode(action, table, key, value));
+    }
+  }
+
+  /// Stores recorded pre-images and drops records that are no longer needed
+  /// to roll back `depth` blocks from `block_count`.
+  pub(crate) fn commit(&self, wtx: &WriteTransaction, block_count: u32, depth: u32) -> Result {
+    let mut undo_records = wtx.open_table(HEIGHT_AND_INDEX_TO_UNDO_RECORD)?;
+
+    if let Some(records) = &self.records {
+      for (i, record) in RefCell::borrow(records).iter().enumerate() {
+        undo_records.insert((self.height, u32::try_from(i).unwrap()), record.as_slice())?;
+      }
+    }
+
+    let oldest = if depth == 0 {
+      u32::MAX
+    } else {
+      wtx
+        .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
+        .range(..=block_count.saturating_sub(depth))?
+        .next_back()
+        .transpose()?
+        .map(|(height, _timestamp)| height.value())
+        .unwrap_or(0)
+    };
+
+    undo_records.retain_in(..(oldest, 0), |_key, _record| false)?;
+
+    Ok(())
+  }
+
+  /// Rolls the index back to the start of the write transaction during which
+  /// the block at `height` was indexed, returning the new block count, or
+  /// `None` if the undo log doesn't cover every write transaction since.
+  pub(crate) fn rollback(wtx: &WriteTransaction, height: u32) -> Result<Option<u32>> {
+    let mut undo_records = wtx.open_table(HEIGHT_AND_INDEX_TO_UNDO_RECORD)?;
+
+    let starting_block_counts = wtx
+      .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
+      .range(0..)?
+      .map(|result| result.map(|(height, _timestamp)| height.value()))
+      .collect::<Result<Vec<u32>, StorageError>>()?;
+
+    let Some(target) = starting_block_counts
+      .iter()
+      .copied()
+      .filter(|starting_block_count| *starting_block_count <= height)
+      .max()
+    else {
+      return Ok(None);
+    };
+
+    for starting_block_count in &starting_block_counts {
+      if *starting_block_count >= target && undo_records.get((*starting_block_count, 0))?.is_none()
+      {
+        return Ok(None);
+      }
+    }
+
+    let records = undo_records
+      .range((target, 0)..)?
+      .map(|result| result.map(|(key, record)| (key.value(), record.value().to_vec())))
+      .collect::<Result<Vec<((u32, u32), Vec<u8>)>, StorageError>>()?;
+
+    for (key, record) in records.into_iter().rev() {
+      let record = Record::decode(&record)?;
+
+      revert! {
+        wtx, record,
+        tables: [
+          CHARM_AND_SEQUENCE_NUMBER,
+          CONTENT_TYPE_AND_SEQUENCE_NUMBER,
+          HEIGHT_AND_INDEX_TO_EVENT,
+          HEIGHT_TO_BLOCK_HEADER,
+          HEIGHT_TO_LAST_SEQUENCE_NUMBER,
+          HOME_INSCRIPTIONS,
+          INSCRIPTION_ID_TO_OUTPOINTS,
+          INSCRIPTION_ID_TO_PARTIAL_CONTENT,
+          INSCRIPTION_ID_TO_SEQUENCE_NUMBER,
+          INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER,
+          METAPROTOCOL_AND_SEQUENCE_NUMBER,
+          OUTPOINT_TO_RUNE_BALANCES,
+          OUTPOINT_TO_UTXO_ENTRY,
+          PARTIAL_OUTPOINT_TO_PARTIALS,
+          RARITY_AND_SEQUENCE_NUMBER,
+          RUNE_ID_AND_HEIGHT_TO_ACTIVITY,
+          RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE,
+          RUNE_ID_TO_RUNE_ENTRY,
+          RUNE_TO_RUNE_ID,
+          SAT_TO_SATPOINT,
+          SCRIPT_PUBKEY_HEIGHT_AND_INDEX_TO_HISTORY,
+          SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER,
+          SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY,
+          SEQUENCE_NUMBER_TO_RUNE_ID,
+          SEQUENCE_NUMBER_TO_SATPOINT,
+          SEQUENCE_NUMBER_TO_TERM_COUNT,
+          STATISTIC_TO_COUNT,
+          TERM_AND_SEQUENCE_NUMBER_TO_FREQUENCY,
+          TRANSACTION_ID_TO_RUNE,
+          TRANSACTION_ID_TO_TRANSACTION,
+          WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP,
+        ],
+        multimap_tables: [
+          RUNE_ID_TO_BALANCE_AND_OUTPOINT,
+          RUNE_ID_TO_BALANCE_AND_SCRIPT_PUBKEY,
+          SAT_TO_SEQUENCE_NUMBER,
+          SCRIPT_PUBKEY_TO_OUTPOINT,
+          SEQUENCE_NUMBER_TO_CHILDREN,
+          SHA256_TO_SEQUENCE_NUMBER,
+        ],
+      }?;
+
+      undo_records.remove(key)?;
+    }
+
+    Ok(Some(
+      wtx
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .range(0..)?
+        .next_back()
+        .transpose()?
+        .map(|(height, _header)| height.value() + 1)
+        .unwrap_or(0),
+    ))
+  }
+}
+
+/// A table whose writes are recorded in an undo log. Reads go through `Deref`,
+/// and since the table is never handed out mutably, every write is recorded.
+pub(crate) struct UndoTable<'tx, K: Key + 'static, V: redb::Value + 'static> {
+  log: UndoLog,
+  name: String,
+  table: Table<'tx, K, V>,
+}
+
+impl<'tx, K: Key + 'static, V: redb::Value + 'static> UndoTable<'tx, K, V> {
+  pub(crate) fn insert<'k, 'v>(
+    &mut self,
+    key: impl Borrow<K::SelfType<'k>>,
+    value: impl Borrow<V::SelfType<'v>>,
+  ) -> Result<Option<AccessGuard<'_, V>>, StorageError> {
+    let old = self.table.insert(key.borrow(), value)?;
+
+    if self.log.recording() {
+      let key = K::as_bytes(key.borrow());
+      match &old {
+        Some(old) => self.log.record(
+          Action::Insert,
+          &self.name,
+          key.as_ref(),
+          V::as_bytes(&old.value()).as_ref(),
+        ),
+        None => self
+          .log
+          .record(Action::Remove, &self.name, key.as_ref(), &[]),
+      }
+    }
+
+    Ok(old)
+  }
+
+  pub(crate) fn remove<'k>(
+    &mut self,
+    key: impl Borrow<K::SelfType<'k>>,
+  ) -> Result<Option<AccessGuard<'_, V>>, StorageError> {
+    let old = self.table.remove(key.borrow())?;
+
+    if let Some(old) = &old {
+      self.log.record(
+        Action::Insert,
+        &self.name,
+        K::as_bytes(key.borrow()).as_ref(),
+        V::as_bytes(&old.value()).as_ref(),
+      );
+    }
+
+    Ok(old)
+  }
+
+  pub(crate) fn pop_first(
+    &mut self,
+  ) -> Result<Option<(AccessGuard<'_, K>, AccessGuard<'_, V>)>, StorageError> {
+    let first = self.table.pop_first()?;
+
+    if let Some((key, value)) = &first {
+      self.log.record(
+        Action::Insert,
+        &self.name,
+        K::as_bytes(&key.value()).as_ref(),
+        V::as_bytes(&value.value()).as_ref(),
+      );
+    }
+
+    Ok(first)
+  }
+}
+
+impl<'tx, K: Key + 'static, V: redb::Value + 'static> Deref for UndoTable<'tx, K, V> {
+  type Target = Table<'tx, K, V>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.table
+  }
+}
+
+/// A multimap table whose writes are recorded in an undo log.
+pub(crate) struct UndoMultimapTable<'tx, K: Key + 'static, V: Key + 'static> {
+  log: UndoLog,
+  name: String,
+  table: MultimapTable<'tx, K, V>,
+}
+
+impl<'tx, K: Key + 'static, V: Key + 'static> UndoMultimapTable<'tx, K, V> {
+  pub(crate) fn insert<'k, 'v>(
+    &mut self,
+    key: impl Borrow<K::SelfType<'k>>,
+    value: impl Borrow<V::SelfType<'v>>,
+  ) -> Result<bool, StorageError> {
+    let existed = self.table.insert(key.borrow(), value.borrow())?;
+
+    if !existed {
+      self.log.record(
+        Action::MultimapRemove,
+        &self.name,
+        K::as_bytes(key.borrow()).as_ref(),
+        V::as_bytes(value.borrow()).as_ref(),
+      );
+    }
+
+    Ok(existed)
+  }
+
+  pub(crate) fn remove<'k, 'v>(
+    &mut self,
+    key: impl Borrow<K::SelfType<'k>>,
+    value: impl Borrow<V::SelfType<'v>>,
+  ) -> Result<bool, StorageError> {
+    let existed = self.table.remove(key.borrow(), value.borrow())?;
+
+    if existed {
+      self.log.record(
+        Action::MultimapInsert,
+        &self.name,
+        K::as_bytes(key.borrow()).as_ref(),
+        V::as_bytes(value.borrow()).as_ref(),
+      );
+    }
+
+    Ok(existed)
+  }
+}
+
+impl<'tx, K: Key + 'static, V: Key + 'static> Deref for UndoMultimapTable<'tx, K, V> {
+  type Target = MultimapTable<'tx, K, V>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.table
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn record_round_trip() {
+    for (action, table, key, value) in [
+      (Action::Insert, "FOO", &b"key"[..], &b"value"[..]),
+      (Action::Remove, "BAR", &[0; 36][..], &[][..]),
+      (Action::MultimapInsert, "", &[][..], &[1, 2, 3][..]),
+      (Action::MultimapRemove, "BAZ", &[1][..], &[][..]),
+    ] {
+      assert_eq!(
+        Record::decode(&Record::encode(action, table, key, value)).unwrap(),
+        Record {
+          action,
+          table: table.into(),
+          key: key.into(),
+          value: value.into(),
+        }
+      );
+    }
+  }
+
+  #[test]
+  fn truncated_records_are_rejected() {
+    let record = Record::encode(Action::Insert, "FOO", b"key", b"");
+
+    for len in 0..record.len() {
+      assert!(Record::decode(&record[..len]).is_err());
+    }
+
+    assert!(Record::decode(&[4, 0, 0, 0, 0, 0]).is_err());
+  }
+}
@@ -8,6 +8,9 @@ use {
   },
 };
 
+pub(crate) use self::event_emitter::EventEmitter;
+
+mod event_emitter;
 mod inscription_updater;
 mod rune_updater;
 
@@ -46,8 +49,10 @@ impl<'index> Updater<'index> {
     let starting_height = u32::try_from(self.index.client.get_block_count()?).unwrap() + 1;
     let starting_index_height = self.height;
 
-    wtx
-      .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
+    let mut undo_log = self.undo_log(starting_height);
+
+    undo_log
+      .open_table(&wtx, WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
       .insert(
         &self.height,
         &SystemTime::now()
@@ -84,6 +89,7 @@ impl<'index> Updater<'index> {
         &mut wtx,
         block,
         &mut utxo_cache,
+        &undo_log,
       )?;
 
       if let Some(progress_bar) = &mut progress_bar {
@@ -101,7 +107,7 @@ impl<'index> Updater<'index> {
       uncommitted += 1;
 
       if uncommitted == self.index.settings.commit_interval() {
-        self.commit(wtx, utxo_cache)?;
+        self.commit(wtx, utxo_cache, &undo_log)?;
         utxo_cache = HashMap::new();
         uncommitted = 0;
         wtx = self.index.begin_write()?;
@@ -117,8 +123,9 @@ impl<'index> Updater<'index> {
           // write transaction
           break;
         }
-        wtx
-          .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
+        undo_log = self.undo_log(starting_height);
+        undo_log
+          .open_table(&wtx, WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
           .insert(
             &self.height,
             &SystemTime::now()
@@ -133,14 +140,14 @@ impl<'index> Updater<'index> {
     }
 
     if starting_index_height == 0 && self.height > 0 {
-      wtx.open_table(STATISTIC_TO_COUNT)?.insert(
+      undo_log.open_table(&wtx, STATISTIC_TO_COUNT)?.insert(
         Statistic::InitialSyncTime.key(),
         &u64::try_from(start.elapsed().as_micros())?,
       )?;
     }
 
     if uncommitted > 0 {
-      self.commit(wtx, utxo_cache)?;
+      self.commit(wtx, utxo_cache, &undo_log)?;
     }
 
     if let Some(progress_bar) = &mut progress_bar {
@@ -150,6 +157,21 @@ impl<'index> Updater<'index> {
     Ok(())
   }
 
+  /// Undo records are only kept for write transactions which may end within
+  /// `--undo-log-depth` blocks of the chain tip.
+  fn undo_log(&self, chain_height: u32) -> UndoLog {
+    let depth = self.index.settings.undo_log_depth();
+
+    UndoLog::new(
+      self.height,
+      depth > 0
+        && chain_height.saturating_sub(self.height)
+          <= depth.saturating_add(
+            u32::try_from(self.index.settings.commit_interval()).unwrap_or(u32::MAX),
+          ),
+    )
+  }
+
   fn fetch_blocks_from(
     index: &Index,
     mut height: u32,
@@ -313,6 +335,7 @@ impl<'index> Updater<'index> {
     wtx: &mut WriteTransaction,
     block: BlockData,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
+    undo_log: &UndoLog,
   ) -> Result<()> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
 
@@ -327,18 +350,25 @@ impl<'index> Updater<'index> {
       block.txdata.len()
     );
 
-    let mut height_to_block_header = wtx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
+    let mut height_to_block_header = undo_log.open_table(wtx, HEIGHT_TO_BLOCK_HEADER)?;
     let mut inscription_id_to_sequence_number =
-      wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
-    let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+      undo_log.open_table(wtx, INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+    let mut statistic_to_count = undo_log.open_table(wtx, STATISTIC_TO_COUNT)?;
+    let mut event_emitter = EventEmitter::new(self.index, wtx, self.height, undo_log)?;
+    let mut address_history = Vec::new();
+    let mut spent_script_pubkeys = HashMap::new();
 
     if self.index.index_inscriptions || self.index.index_addresses || self.index.index_sats {
       self.index_utxo_entries(
         &block,
+        &mut address_history,
+        &mut spent_script_pubkeys,
         txout_receiver,
         output_sender,
         utxo_cache,
         wtx,
+        undo_log,
+        &mut event_emitter,
         &mut inscription_id_to_sequence_number,
         &mut statistic_to_count,
         &mut sat_ranges_written,
@@ -346,12 +376,27 @@ impl<'index> Updater<'index> {
       )?;
     }
 
+    let mut rune_transfers = HashMap::new();
+
     if self.index.index_runes && self.height >= self.index.settings.first_rune_height() {
-      let mut outpoint_to_rune_balances = wtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
-      let mut rune_id_to_rune_entry = wtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
-      let mut rune_to_rune_id = wtx.open_table(RUNE_TO_RUNE_ID)?;
-      let mut sequence_number_to_rune_id = wtx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
-      let mut transaction_id_to_rune = wtx.open_table(TRANSACTION_ID_TO_RUNE)?;
+      let mut outpoint_to_rune_balances = undo_log.open_table(wtx, OUTPOINT_TO_RUNE_BALANCES)?;
+      let mut rune_id_to_rune_entry = undo_log.open_table(wtx, RUNE_ID_TO_RUNE_ENTRY)?;
+      let mut rune_to_rune_id = undo_log.open_table(wtx, RUNE_TO_RUNE_ID)?;
+      let mut sequence_number_to_rune_id = undo_log.open_table(wtx, SEQUENCE_NUMBER_TO_RUNE_ID)?;
+      let mut transaction_id_to_rune = undo_log.open_table(wtx, TRANSACTION_ID_TO_RUNE)?;
+      let mut rune_id_and_height_to_activity =
+        undo_log.open_table(wtx, RUNE_ID_AND_HEIGHT_TO_ACTIVITY)?;
+      let mut rune_id_to_balance_and_outpoint =
+        undo_log.open_multimap_table(wtx, RUNE_ID_TO_BALANCE_AND_OUTPOINT)?;
+
+      let mut script_pubkey_balances = if self.index.index_addresses {
+        Some((
+          undo_log.open_table(wtx, RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE)?,
+          undo_log.open_multimap_table(wtx, RUNE_ID_TO_BALANCE_AND_SCRIPT_PUBKEY)?,
+        ))
+      } else {
+        None
+      };
 
       let runes = statistic_to_count
         .get(&Statistic::Runes.into())?
@@ -359,11 +404,13 @@ impl<'index> Updater<'index> {
         .unwrap_or(0);
 
       let mut rune_updater = RuneUpdater {
-        event_sender: self.index.event_sender.as_ref(),
+        activity: HashMap::new(),
         block_time: block.header.time,
         burned: HashMap::new(),
         client: &self.index.client,
+        event_emitter: &mut event_emitter,
         height: self.height,
+        id_and_height_to_activity: &mut rune_id_and_height_to_activity,
         id_to_entry: &mut rune_id_to_rune_entry,
         inscription_id_to_sequence_number: &mut inscription_id_to_sequence_number,
         minimum: Rune::minimum_at_height(
@@ -371,21 +418,51 @@ impl<'index> Updater<'index> {
           Height(self.height),
         ),
         outpoint_to_balances: &mut outpoint_to_rune_balances,
+        rune_id_to_balance_and_outpoint: &mut rune_id_to_balance_and_outpoint,
         rune_to_id: &mut rune_to_rune_id,
         runes,
+        script_pubkey_balances: script_pubkey_balances
+          .as_mut()
+          .map(|(balances, holders)| (balances, holders)),
         sequence_number_to_rune_id: &mut sequence_number_to_rune_id,
+        spent_script_pubkeys,
         statistic_to_count: &mut statistic_to_count,
         transaction_id_to_rune: &mut transaction_id_to_rune,
+        transferred: self.index.index_address_history.then(HashMap::new),
       };
 
       for (i, (tx, txid)) in block.txdata.iter().enumerate() {
         rune_updater.index_runes(u32::try_from(i).unwrap(), tx, *txid)?;
       }
 
+      rune_transfers = rune_updater.transferred.take().unwrap_or_default();
+
       rune_updater.update()?;
     }
 
-    height_to_block_header.insert(&self.height, &block.header.store())?;
+    if self.index.index_address_history {
+      let mut script_pubkey_height_and_index_to_history =
+        undo_log.open_table(wtx, SCRIPT_PUBKEY_HEIGHT_AND_INDEX_TO_HISTORY)?;
+
+      for (script_pubkey, tx_index, mut entry) in address_history {
+        if let Some(runes) = rune_transfers.get(&entry.txid) {
+          entry.runes = runes.iter().map(|(id, amount)| (*id, amount.n())).collect();
+        }
+
+        script_pubkey_height_and_index_to_history.insert(
+          (script_pubkey.as_slice(), self.height, tx_index),
+          entry.store(),
+        )?;
+      }
+    }
+
+    let mut header = block.header;
+
+    if !self.index.index_auxpow {
+      header.auxpow = None;
+    }
+
+    height_to_block_header.insert(&self.height, &header.store())?;
 
     self.height += 1;
     self.outputs_traversed += outputs_in_block;
@@ -401,33 +478,64 @@ impl<'index> Updater<'index> {
   fn index_utxo_entries<'wtx>(
     &mut self,
     block: &BlockData,
+    address_history: &mut Vec<(Vec<u8>, u32, AddressHistoryEntry)>,
+    spent_script_pubkeys: &mut HashMap<OutPoint, Vec<u8>>,
     txout_receiver: &mut broadcast::Receiver<TxOut>,
     output_sender: &mut mpsc::Sender<OutPoint>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     wtx: &'wtx WriteTransaction,
-    inscription_id_to_sequence_number: &mut Table<'wtx, (u128, u128, u32), u32>,
-    statistic_to_count: &mut Table<'wtx, u64, u64>,
+    undo_log: &UndoLog,
+    event_emitter: &mut EventEmitter<'wtx>,
+    inscription_id_to_sequence_number: &mut UndoTable<'wtx, (u128, u128, u32), u32>,
+    statistic_to_count: &mut UndoTable<'wtx, u64, u64>,
     sat_ranges_written: &mut u64,
     outputs_in_block: &mut u64,
   ) -> Result<(), Error> {
-    let mut height_to_last_sequence_number = wtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
-    let mut home_inscriptions = wtx.open_table(HOME_INSCRIPTIONS)?;
+    let mut charm_and_sequence_number = undo_log.open_table(wtx, CHARM_AND_SEQUENCE_NUMBER)?;
+    let mut content_type_and_sequence_number =
+      undo_log.open_table(wtx, CONTENT_TYPE_AND_SEQUENCE_NUMBER)?;
+    let mut height_to_last_sequence_number =
+      undo_log.open_table(wtx, HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+    let mut home_inscriptions = undo_log.open_table(wtx, HOME_INSCRIPTIONS)?;
     let mut inscription_number_to_sequence_number =
-      wtx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
-    let mut outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
-    let mut sat_to_satpoint = wtx.open_table(SAT_TO_SATPOINT)?;
-    let mut sat_to_sequence_number = wtx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
-    let mut script_pubkey_to_outpoint = wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
-    let mut sequence_number_to_children = wtx.open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?;
+      undo_log.open_table(wtx, INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
+    let mut metaprotocol_and_sequence_number =
+      undo_log.open_table(wtx, METAPROTOCOL_AND_SEQUENCE_NUMBER)?;
+    let mut outpoint_to_utxo_entry = undo_log.open_table(wtx, OUTPOINT_TO_UTXO_ENTRY)?;
+    let mut rarity_and_sequence_number = undo_log.open_table(wtx, RARITY_AND_SEQUENCE_NUMBER)?;
+    let mut sat_to_satpoint = undo_log.open_table(wtx, SAT_TO_SATPOINT)?;
+    let mut sat_to_sequence_number = undo_log.open_multimap_table(wtx, SAT_TO_SEQUENCE_NUMBER)?;
+    let mut script_pubkey_to_outpoint =
+      undo_log.open_multimap_table(wtx, SCRIPT_PUBKEY_TO_OUTPOINT)?;
+    let mut sequence_number_to_children =
+      undo_log.open_multimap_table(wtx, SEQUENCE_NUMBER_TO_CHILDREN)?;
+    let mut sequence_number_and_index_to_transfer =
+      undo_log.open_table(wtx, SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?;
     let mut sequence_number_to_inscription_entry =
-      wtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
-    let mut transaction_id_to_transaction = wtx.open_table(TRANSACTION_ID_TO_TRANSACTION)?;
-    let mut partials_table = wtx.open_table(PARTIAL_OUTPOINT_TO_PARTIALS)?;
-    let mut inscription_ids_to_outpoints_table = wtx.open_table(INSCRIPTION_ID_TO_OUTPOINTS)?;
+      undo_log.open_table(wtx, SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+    let mut sequence_number_to_term_count =
+      undo_log.open_table(wtx, SEQUENCE_NUMBER_TO_TERM_COUNT)?;
+    let mut sha256_to_sequence_number =
+      undo_log.open_multimap_table(wtx, SHA256_TO_SEQUENCE_NUMBER)?;
+    let mut term_and_sequence_number_to_frequency =
+      undo_log.open_table(wtx, TERM_AND_SEQUENCE_NUMBER_TO_FREQUENCY)?;
+    let mut transaction_id_to_transaction =
+      undo_log.open_table(wtx, TRANSACTION_ID_TO_TRANSACTION)?;
+    let mut partials_table = undo_log.open_table(wtx, PARTIAL_OUTPOINT_TO_PARTIALS)?;
+    let mut inscription_ids_to_outpoints_table =
+      undo_log.open_table(wtx, INSCRIPTION_ID_TO_OUTPOINTS)?;
+    let mut inscription_id_to_partial_content =
+      undo_log.open_table(wtx, INSCRIPTION_ID_TO_PARTIAL_CONTENT)?;
 
     let index_inscriptions = self.height >= self.index.settings.first_inscription_height()
       && self.index.index_inscriptions;
 
+    // rune balances of spent outputs are debited from the script pubkey that
+    // held them, which is only known here, before the entries are removed
+    let index_rune_holders = self.index.index_addresses
+      && self.index.index_runes
+      && self.height >= self.index.settings.first_rune_height();
+
     // If the receiver still has inputs something went wrong in the last
     // block and we shouldn't recover from this and commit the last block
     if index_inscriptions {
@@ -491,6 +599,11 @@ impl<'index> Updater<'index> {
       .map(|unbound_inscriptions| unbound_inscriptions.value())
       .unwrap_or(0);
 
+    let text_terms = statistic_to_count
+      .get(&Statistic::TextTerms.key())?
+      .map(|text_terms| text_terms.value())
+      .unwrap_or(0);
+
     let next_sequence_number = sequence_number_to_inscription_entry
       .iter()?
       .next_back()
@@ -502,7 +615,10 @@ impl<'index> Updater<'index> {
 
     let mut inscription_updater = InscriptionUpdater {
       blessed_inscription_count,
+      charm_and_sequence_number: &mut charm_and_sequence_number,
+      content_type_and_sequence_number: &mut content_type_and_sequence_number,
       cursed_inscription_count,
+      event_emitter,
       flotsam: Vec::new(),
       height: self.height,
       home_inscription_count,
@@ -510,16 +626,24 @@ impl<'index> Updater<'index> {
       id_to_sequence_number: inscription_id_to_sequence_number,
       inscription_number_to_sequence_number: &mut inscription_number_to_sequence_number,
       lost_sats,
+      metaprotocol_and_sequence_number: &mut metaprotocol_and_sequence_number,
       next_sequence_number,
+      rarity_and_sequence_number: &mut rarity_and_sequence_number,
       reward: Height(self.height).subsidy(self.index.settings.chain().network()),
       sat_to_sequence_number: &mut sat_to_sequence_number,
+      sequence_number_and_index_to_transfer: &mut sequence_number_and_index_to_transfer,
       sequence_number_to_children: &mut sequence_number_to_children,
       sequence_number_to_entry: &mut sequence_number_to_inscription_entry,
+      sequence_number_to_term_count: &mut sequence_number_to_term_count,
+      sha256_to_sequence_number: &mut sha256_to_sequence_number,
+      term_and_sequence_number_to_frequency: &mut term_and_sequence_number_to_frequency,
+      text_terms,
       timestamp: block.header.time,
       transaction_buffer: Vec::new(),
       transaction_id_to_transaction: &mut transaction_id_to_transaction,
       partials: &mut partials_table,
       inscription_ids_to_outpoints: &mut inscription_ids_to_outpoints_table,
+      inscription_id_to_partial_content: &mut inscription_id_to_partial_content,
       unbound_inscriptions,
     };
 
@@ -528,11 +652,12 @@ impl<'index> Updater<'index> {
 
     if self.index.index_sats {
       let h = Height(self.height);
-      if h.subsidy(self.index.settings.chain().network()) > 0 {
-        let start = h.starting_sat();
+      let network = self.index.settings.chain().network();
+      if h.subsidy(network) > 0 {
+        let start = h.starting_sat(network);
         coinbase_inputs.extend(SatRange::store((
           start.n(),
-          (start + h.subsidy(self.index.settings.chain().network())).n(),
+          (start + h.subsidy(network)).n(),
         )));
         self.sat_ranges_since_flush += 1;
       }
@@ -595,6 +720,12 @@ impl<'index> Updater<'index> {
         .map(|entry| entry.parse(self.index))
         .collect::<Vec<ParsedUtxoEntry>>();
 
+      if index_rune_holders {
+        for (input, entry) in tx.input.iter().zip(&input_utxo_entries) {
+          spent_script_pubkeys.insert(input.previous_output, entry.script_pubkey().to_vec());
+        }
+      }
+
       let mut output_utxo_entries = tx
         .output
         .iter()
@@ -652,6 +783,17 @@ impl<'index> Updater<'index> {
         )?;
       }
 
+      if self.index.index_address_history {
+        self.index_transaction_address_history(
+          u32::try_from(tx_offset).unwrap(),
+          tx,
+          *txid,
+          &input_utxo_entries,
+          &output_utxo_entries,
+          address_history,
+        );
+      }
+
       for (vout, output_utxo_entry) in output_utxo_entries.into_iter().enumerate() {
         let vout = u32::try_from(vout).unwrap();
         utxo_cache.insert(OutPoint { txid: *txid, vout }, output_utxo_entry);
@@ -720,9 +862,60 @@ impl<'index> Updater<'index> {
       &inscription_updater.unbound_inscriptions,
     )?;
 
+    statistic_to_count.insert(&Statistic::TextTerms.key(), &inscription_updater.text_terms)?;
+
     Ok(())
   }
 
+  /// Records the amounts each script pubkey sent and received in `tx`, along
+  /// with the inscriptions that landed in its outputs. Rune transfers are
+  /// added once the block's runes have been indexed.
+  fn index_transaction_address_history(
+    &self,
+    tx_index: u32,
+    tx: &Transaction,
+    txid: Txid,
+    input_utxo_entries: &[ParsedUtxoEntry],
+    output_utxo_entries: &[UtxoEntryBuf],
+    address_history: &mut Vec<(Vec<u8>, u32, AddressHistoryEntry)>,
+  ) {
+    let mut amounts: BTreeMap<&[u8], (u64, u64)> = BTreeMap::new();
+
+    for entry in input_utxo_entries {
+      amounts.entry(entry.script_pubkey()).or_default().0 += entry.total_value();
+    }
+
+    for txout in &tx.output {
+      if !txout.script_pubkey.is_op_return() {
+        amounts.entry(txout.script_pubkey.as_bytes()).or_default().1 += txout.value;
+      }
+    }
+
+    let inscriptions = if self.index.index_inscriptions {
+      output_utxo_entries
+        .iter()
+        .flat_map(|entry| entry.parse(self.index).parse_inscriptions())
+        .map(|(sequence_number, _offset)| sequence_number)
+        .collect()
+    } else {
+      Vec::new()
+    };
+
+    for (script_pubkey, (sent, received)) in amounts {
+      address_history.push((
+        script_pubkey.to_vec(),
+        tx_index,
+        AddressHistoryEntry {
+          inscriptions: inscriptions.clone(),
+          received,
+          runes: Vec::new(),
+          sent,
+          txid,
+        },
+      ));
+    }
+  }
+
   fn index_transaction_output_script_pubkeys(
     &mut self,
     tx: &Transaction,
@@ -737,7 +930,7 @@ impl<'index> Updater<'index> {
     &mut self,
     tx: &Transaction,
     txid: Txid,
-    sat_to_satpoint: &mut Table<u64, &SatPointValue>,
+    sat_to_satpoint: &mut UndoTable<u64, &SatPointValue>,
     output_utxo_entries: &mut [UtxoEntryBuf],
     input_sat_ranges: &[&[u8]],
     leftover_sat_ranges: &mut Vec<u8>,
@@ -824,6 +1017,7 @@ impl<'index> Updater<'index> {
     &mut self,
     wtx: WriteTransaction,
     utxo_cache: HashMap<OutPoint, UtxoEntryBuf>,
+    undo_log: &UndoLog,
   ) -> Result {
     log::info!(
       "Committing at block height {}, {} outputs traversed, {} in map, {} cached",
@@ -834,9 +1028,11 @@ impl<'index> Updater<'index> {
     );
 
     {
-      let mut outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
-      let mut script_pubkey_to_outpoint = wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
-      let mut sequence_number_to_satpoint = wtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+      let mut outpoint_to_utxo_entry = undo_log.open_table(&wtx, OUTPOINT_TO_UTXO_ENTRY)?;
+      let mut script_pubkey_to_outpoint =
+        undo_log.open_multimap_table(&wtx, SCRIPT_PUBKEY_TO_OUTPOINT)?;
+      let mut sequence_number_to_satpoint =
+        undo_log.open_table(&wtx, SEQUENCE_NUMBER_TO_SATPOINT)?;
 
       for (outpoint, mut utxo_entry) in utxo_cache {
         if Index::is_special_outpoint(outpoint) {
@@ -862,10 +1058,26 @@ impl<'index> Updater<'index> {
       }
     }
 
-    Index::increment_statistic(&wtx, Statistic::OutputsTraversed, self.outputs_traversed)?;
+    {
+      let mut statistic_to_count = undo_log.open_table(&wtx, STATISTIC_TO_COUNT)?;
+
+      for (statistic, n) in [
+        (Statistic::OutputsTraversed, self.outputs_traversed),
+        (Statistic::SatRanges, self.sat_ranges_since_flush),
+      ] {
+        let value = statistic_to_count
+          .get(statistic.key())?
+          .map(|x| x.value())
+          .unwrap_or_default()
+          + n;
+        statistic_to_count.insert(statistic.key(), value)?;
+      }
+    }
     self.outputs_traversed = 0;
-    Index::increment_statistic(&wtx, Statistic::SatRanges, self.sat_ranges_since_flush)?;
     self.sat_ranges_since_flush = 0;
+
+    undo_log.commit(&wtx, self.height, self.index.settings.undo_log_depth())?;
+
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
@@ -0,0 +1,59 @@
+use super::*;
+
+/// Sends events to the event channel, if any, and appends them to the event
+/// log table when events are being indexed.
+pub(crate) struct EventEmitter<'tx> {
+  height: u32,
+  log: Option<UndoTable<'tx, (u32, u32), &'static [u8]>>,
+  next: u32,
+  sender: Option<mpsc::Sender<Event>>,
+}
+
+impl<'tx> EventEmitter<'tx> {
+  pub(crate) fn new(
+    index: &Index,
+    wtx: &'tx WriteTransaction,
+    height: u32,
+    undo_log: &UndoLog,
+  ) -> Result<Self> {
+    let log = if index.index_events {
+      Some(undo_log.open_table(wtx, HEIGHT_AND_INDEX_TO_EVENT)?)
+    } else {
+      None
+    };
+
+    // a rollback marker may already have been logged at this height
+    let next = match &log {
+      Some(log) => log
+        .range((height, 0)..=(height, u32::MAX))?
+        .next_back()
+        .transpose()?
+        .map(|(key, _event)| key.value().1 + 1)
+        .unwrap_or(0),
+      None => 0,
+    };
+
+    Ok(Self {
+      height,
+      log,
+      next,
+      sender: index.event_sender.clone(),
+    })
+  }
+
+  pub(crate) fn emit(&mut self, event: Event) -> Result {
+    if let Some(log) = &mut self.log {
+      log.insert(
+        (self.height, self.next),
+        serde_json::to_vec(&event)?.as_slice(),
+      )?;
+      self.next += 1;
+    }
+
+    if let Some(sender) = &self.sender {
+      sender.blocking_send(event)?;
+    }
+
+    Ok(())
+  }
+}
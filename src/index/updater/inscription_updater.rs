@@ -25,39 +25,60 @@ pub(super) struct Flotsam {
 #[derive(Debug, Clone)]
 enum Origin {
   New {
+    content_type: Option<Vec<u8>>,
     cursed: bool,
     fee: u64,
     hidden: bool,
+    metaprotocol: Option<Vec<u8>>,
     parents: Vec<InscriptionId>,
     reinscription: bool,
+    sha256: Option<[u8; 32]>,
+    text: Option<String>,
     unbound: bool,
     vindicated: bool,
   },
   Old {
     sequence_number: u32,
     old_satpoint: SatPoint,
+    txid: Txid,
   },
 }
 
 pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) blessed_inscription_count: u64,
+  pub(super) charm_and_sequence_number: &'a mut UndoTable<'tx, (u8, u32), ()>,
+  pub(super) content_type_and_sequence_number: &'a mut UndoTable<'tx, (&'static [u8], u32), ()>,
   pub(super) cursed_inscription_count: u64,
+  pub(super) event_emitter: &'a mut EventEmitter<'tx>,
   pub(super) flotsam: Vec<Flotsam>,
   pub(super) height: u32,
   pub(super) home_inscription_count: u64,
-  pub(super) home_inscriptions: &'a mut Table<'tx, u32, InscriptionIdValue>,
-  pub(super) id_to_sequence_number: &'a mut Table<'tx, InscriptionIdValue, u32>,
-  pub(super) inscription_number_to_sequence_number: &'a mut Table<'tx, i32, u32>,
+  pub(super) home_inscriptions: &'a mut UndoTable<'tx, u32, InscriptionIdValue>,
+  pub(super) id_to_sequence_number: &'a mut UndoTable<'tx, InscriptionIdValue, u32>,
+  pub(super) inscription_number_to_sequence_number: &'a mut UndoTable<'tx, i32, u32>,
   pub(super) lost_sats: u64,
+  pub(super) metaprotocol_and_sequence_number: &'a mut UndoTable<'tx, (&'static [u8], u32), ()>,
   pub(super) next_sequence_number: u32,
+  pub(super) rarity_and_sequence_number: &'a mut UndoTable<'tx, (u8, u32), ()>,
   pub(super) reward: u64,
   pub(super) transaction_buffer: Vec<u8>,
-  pub(super) transaction_id_to_transaction: &'a mut Table<'tx, &'static TxidValue, &'static [u8]>,
-  pub(super) sat_to_sequence_number: &'a mut MultimapTable<'tx, u64, u32>,
-  pub(super) sequence_number_to_children: &'a mut MultimapTable<'tx, u32, u32>,
-  pub(super) sequence_number_to_entry: &'a mut Table<'tx, u32, InscriptionEntryValue>,
-  pub(super) partials: &'a mut Table<'tx, &'static OutPointValue, PartialValue>,
-  pub(super) inscription_ids_to_outpoints: &'a mut Table<'tx, InscriptionIdValue, OutPointsValue>,
+  pub(super) transaction_id_to_transaction:
+    &'a mut UndoTable<'tx, &'static TxidValue, &'static [u8]>,
+  pub(super) sat_to_sequence_number: &'a mut UndoMultimapTable<'tx, u64, u32>,
+  pub(super) sequence_number_and_index_to_transfer:
+    &'a mut UndoTable<'tx, (u32, u32), InscriptionTransferEntryValue>,
+  pub(super) sequence_number_to_children: &'a mut UndoMultimapTable<'tx, u32, u32>,
+  pub(super) sequence_number_to_entry: &'a mut UndoTable<'tx, u32, InscriptionEntryValue>,
+  pub(super) sha256_to_sequence_number: &'a mut UndoMultimapTable<'tx, &'static [u8; 32], u32>,
+  pub(super) sequence_number_to_term_count: &'a mut UndoTable<'tx, u32, u32>,
+  pub(super) term_and_sequence_number_to_frequency:
+    &'a mut UndoTable<'tx, (&'static str, u32), u32>,
+  pub(super) text_terms: u64,
+  pub(super) partials: &'a mut UndoTable<'tx, &'static OutPointValue, PartialValue>,
+  pub(super) inscription_ids_to_outpoints:
+    &'a mut UndoTable<'tx, InscriptionIdValue, OutPointsValue>,
+  pub(super) inscription_id_to_partial_content:
+    &'a mut UndoTable<'tx, InscriptionIdValue, PartialContentValue>,
   pub(super) timestamp: u32,
   pub(super) unbound_inscriptions: u64,
 }
@@ -117,6 +138,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           origin: Origin::Old {
             sequence_number,
             old_satpoint,
+            txid,
           },
         });
 
@@ -218,11 +240,21 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
               inscription_id,
               offset,
               origin: Origin::New {
+                content_type: inscription.content_type.clone(),
                 cursed: curse.is_some() && !jubilant,
                 fee: 0,
                 hidden: inscription.hidden(),
+                metaprotocol: inscription.metaprotocol.clone(),
                 parents: inscription.parents(),
                 reinscription: inscribed_offsets.contains_key(&offset),
+                sha256: inscription
+                  .body
+                  .as_deref()
+                  .map(|body| sha256::Hash::hash(body).to_byte_array()),
+                text: index
+                  .index_text
+                  .then(|| fulltext::text(&inscription).map(str::to_string))
+                  .flatten(),
                 unbound: input_value == 0
                   || curse == Some(Curse::UnrecognizedEvenField)
                   || inscription.unrecognized_even_field,
@@ -234,6 +266,16 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
               .inscription_ids_to_outpoints
               .insert(inscription_id.store(), partials.outpoints.store())?;
 
+            if index.index_partials {
+              self.inscription_id_to_partial_content.insert(
+                inscription_id.store(),
+                (
+                  inscription.content_type.unwrap_or_default(),
+                  inscription.body.unwrap_or_default(),
+                ),
+              )?;
+            }
+
             inscribed_offsets
               .entry(offset)
               .or_insert((inscription_id, 0))
@@ -324,11 +366,22 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           inscription_id,
           offset,
           origin: Origin::New {
+            content_type: inscription.payload.content_type.clone(),
             cursed: curse.is_some() && !jubilant,
             fee: 0,
             hidden: inscription.payload.hidden(),
+            metaprotocol: inscription.payload.metaprotocol.clone(),
             parents: inscription.payload.parents(),
             reinscription: inscribed_offsets.contains_key(&offset),
+            sha256: inscription
+              .payload
+              .body
+              .as_deref()
+              .map(|body| sha256::Hash::hash(body).to_byte_array()),
+            text: index
+              .index_text
+              .then(|| fulltext::text(&inscription.payload).map(str::to_string))
+              .flatten(),
             unbound: input_value == 0
               || curse == Some(Curse::UnrecognizedEvenField)
               || inscription.payload.unrecognized_even_field,
@@ -422,14 +475,14 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         new_locations.push((
           new_satpoint,
           inscriptions.next().unwrap(),
-          txout.script_pubkey.is_op_return(),
+          txout.script_pubkey.as_script(),
         ));
       }
 
       output_value = end;
     }
 
-    for (new_satpoint, flotsam, op_return) in new_locations.into_iter() {
+    for (new_satpoint, flotsam, script_pubkey) in new_locations.into_iter() {
       let output_utxo_entry =
         &mut output_utxo_entries[usize::try_from(new_satpoint.outpoint.vout).unwrap()];
 
@@ -437,7 +490,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         input_sat_ranges,
         flotsam,
         new_satpoint,
-        op_return,
+        Some(script_pubkey),
         Some(output_utxo_entry),
         utxo_cache,
         index,
@@ -454,7 +507,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           input_sat_ranges,
           flotsam,
           new_satpoint,
-          false,
+          None,
           None,
           utxo_cache,
           index,
@@ -497,16 +550,18 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
     input_sat_ranges: Option<&Vec<&[u8]>>,
     flotsam: Flotsam,
     new_satpoint: SatPoint,
-    op_return: bool,
+    script_pubkey: Option<&Script>,
     mut normal_output_utxo_entry: Option<&mut UtxoEntryBuf>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     index: &Index,
   ) -> Result {
     let inscription_id = flotsam.inscription_id;
+    let op_return = script_pubkey.is_some_and(Script::is_op_return);
     let (unbound, sequence_number) = match flotsam.origin {
       Origin::Old {
         sequence_number,
         old_satpoint,
+        txid,
       } => {
         if op_return {
           let entry = InscriptionEntry::load(
@@ -524,26 +579,52 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
             sequence_number,
             &InscriptionEntry { charms, ..entry }.store(),
           )?;
-        }
 
-        if let Some(ref sender) = index.event_sender {
-          sender.blocking_send(Event::InscriptionTransferred {
-            block_height: self.height,
-            inscription_id,
-            new_location: new_satpoint,
-            old_location: old_satpoint,
-            sequence_number,
-          })?;
+          self
+            .charm_and_sequence_number
+            .insert((Charm::Burned as u8, sequence_number), ())?;
         }
 
+        self.event_emitter.emit(Event::InscriptionTransferred {
+          block_height: self.height,
+          inscription_id,
+          new_location: new_satpoint,
+          old_location: old_satpoint,
+          sequence_number,
+        })?;
+
+        let transfer_index = self
+          .sequence_number_and_index_to_transfer
+          .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+          .next_back()
+          .transpose()?
+          .map(|(key, _)| key.value().1 + 1)
+          .unwrap_or_default();
+
+        self.sequence_number_and_index_to_transfer.insert(
+          (sequence_number, transfer_index),
+          InscriptionTransferEntry {
+            height: self.height,
+            new_satpoint,
+            old_satpoint,
+            script_pubkey: script_pubkey.map(Script::to_owned).unwrap_or_default(),
+            txid,
+          }
+          .store(),
+        )?;
+
         (false, sequence_number)
       }
       Origin::New {
+        content_type,
         cursed,
         fee,
         hidden,
+        metaprotocol,
         parents,
         reinscription,
+        sha256,
+        text,
         unbound,
         vindicated,
       } => {
@@ -581,7 +662,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         }
 
         if let Some(sat) = sat {
-          charms |= sat.charms();
+          charms |= sat.charms(index.settings.chain().network());
         }
 
         if op_return {
@@ -621,16 +702,14 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           })
           .collect::<Result<Vec<u32>>>()?;
 
-        if let Some(ref sender) = index.event_sender {
-          sender.blocking_send(Event::InscriptionCreated {
-            block_height: self.height,
-            charms,
-            inscription_id,
-            location: (!unbound).then_some(new_satpoint),
-            parent_inscription_ids: parents,
-            sequence_number,
-          })?;
-        }
+        self.event_emitter.emit(Event::InscriptionCreated {
+          block_height: self.height,
+          charms,
+          inscription_id,
+          location: (!unbound).then_some(new_satpoint),
+          parent_inscription_ids: parents,
+          sequence_number,
+        })?;
 
         self.sequence_number_to_entry.insert(
           sequence_number,
@@ -652,6 +731,59 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           .id_to_sequence_number
           .insert(&inscription_id.store(), sequence_number)?;
 
+        self.content_type_and_sequence_number.insert(
+          (content_type.as_deref().unwrap_or_default(), sequence_number),
+          (),
+        )?;
+
+        for charm in Charm::charms(charms) {
+          self
+            .charm_and_sequence_number
+            .insert((charm as u8, sequence_number), ())?;
+        }
+
+        if let Some(sat) = sat {
+          self.rarity_and_sequence_number.insert(
+            (
+              sat.rarity(index.settings.chain().network()).into(),
+              sequence_number,
+            ),
+            (),
+          )?;
+        }
+
+        if let Some(sha256) = sha256 {
+          self
+            .sha256_to_sequence_number
+            .insert(&sha256, sequence_number)?;
+        }
+
+        if let Some(text) = text {
+          let frequencies = fulltext::term_frequencies(&text);
+
+          if !frequencies.is_empty() {
+            let term_count: u32 = frequencies.values().sum();
+
+            for (term, frequency) in &frequencies {
+              self
+                .term_and_sequence_number_to_frequency
+                .insert((term.as_str(), sequence_number), frequency)?;
+            }
+
+            self
+              .sequence_number_to_term_count
+              .insert(sequence_number, term_count)?;
+
+            self.text_terms += u64::from(term_count);
+          }
+        }
+
+        if let Some(metaprotocol) = metaprotocol {
+          self
+            .metaprotocol_and_sequence_number
+            .insert((metaprotocol.as_slice(), sequence_number), ())?;
+        }
+
         if !hidden {
           self
             .home_inscriptions
@@ -1,20 +1,31 @@
 use super::*;
 
 pub(super) struct RuneUpdater<'a, 'tx, 'client> {
+  pub(super) activity: HashMap<RuneId, RuneActivityEntry>,
   pub(super) block_time: u32,
   pub(super) burned: HashMap<RuneId, Lot>,
   pub(super) client: &'client Client,
-  pub(super) event_sender: Option<&'a mpsc::Sender<Event>>,
+  pub(super) event_emitter: &'a mut EventEmitter<'tx>,
   pub(super) height: u32,
-  pub(super) id_to_entry: &'a mut Table<'tx, RuneIdValue, RuneEntryValue>,
-  pub(super) inscription_id_to_sequence_number: &'a Table<'tx, InscriptionIdValue, u32>,
+  pub(super) id_and_height_to_activity:
+    &'a mut UndoTable<'tx, (RuneIdValue, u32), RuneActivityEntryValue>,
+  pub(super) id_to_entry: &'a mut UndoTable<'tx, RuneIdValue, RuneEntryValue>,
+  pub(super) inscription_id_to_sequence_number: &'a UndoTable<'tx, InscriptionIdValue, u32>,
   pub(super) minimum: Rune,
-  pub(super) outpoint_to_balances: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
-  pub(super) rune_to_id: &'a mut Table<'tx, u128, RuneIdValue>,
+  pub(super) outpoint_to_balances: &'a mut UndoTable<'tx, &'static OutPointValue, &'static [u8]>,
+  pub(super) rune_id_to_balance_and_outpoint:
+    &'a mut UndoMultimapTable<'tx, RuneIdValue, (u128, OutPointValue)>,
+  pub(super) rune_to_id: &'a mut UndoTable<'tx, u128, RuneIdValue>,
   pub(super) runes: u64,
-  pub(super) sequence_number_to_rune_id: &'a mut Table<'tx, u32, RuneIdValue>,
-  pub(super) statistic_to_count: &'a mut Table<'tx, u64, u64>,
-  pub(super) transaction_id_to_rune: &'a mut Table<'tx, &'static TxidValue, u128>,
+  pub(super) script_pubkey_balances: Option<(
+    &'a mut UndoTable<'tx, (RuneIdValue, &'static [u8]), u128>,
+    &'a mut UndoMultimapTable<'tx, RuneIdValue, (u128, &'static [u8])>,
+  )>,
+  pub(super) sequence_number_to_rune_id: &'a mut UndoTable<'tx, u32, RuneIdValue>,
+  pub(super) spent_script_pubkeys: HashMap<OutPoint, Vec<u8>>,
+  pub(super) statistic_to_count: &'a mut UndoTable<'tx, u64, u64>,
+  pub(super) transaction_id_to_rune: &'a mut UndoTable<'tx, &'static TxidValue, u128>,
+  pub(super) transferred: Option<HashMap<Txid, BTreeMap<RuneId, Lot>>>,
 }
 
 impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
@@ -30,14 +41,16 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         if let Some(amount) = self.mint(id)? {
           *unallocated.entry(id).or_default() += amount;
 
-          if let Some(sender) = self.event_sender {
-            sender.blocking_send(Event::RuneMinted {
-              block_height: self.height,
-              txid,
-              rune_id: id,
-              amount: amount.n(),
-            })?;
-          }
+          let activity = self.activity.entry(id).or_default();
+          activity.mints += 1;
+          activity.minted += amount.n();
+
+          self.event_emitter.emit(Event::RuneMinted {
+            block_height: self.height,
+            txid,
+            rune_id: id,
+            amount: amount.n(),
+          })?;
         }
       }
 
@@ -198,15 +211,28 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
       for (id, balance) in balances {
         Index::encode_rune_balance(id, balance.n(), &mut buffer);
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::RuneTransferred {
-            outpoint,
-            block_height: self.height,
-            txid,
-            rune_id: id,
-            amount: balance.0,
-          })?;
+        self
+          .rune_id_to_balance_and_outpoint
+          .insert(id.store(), (balance.n(), outpoint.store()))?;
+
+        self.update_script_pubkey_balance(
+          id,
+          tx.output[vout].script_pubkey.as_bytes(),
+          balance.n(),
+          0,
+        )?;
+
+        if let Some(transferred) = &mut self.transferred {
+          *transferred.entry(txid).or_default().entry(id).or_default() += balance;
         }
+
+        self.event_emitter.emit(Event::RuneTransferred {
+          outpoint,
+          block_height: self.height,
+          txid,
+          rune_id: id,
+          amount: balance.0,
+        })?;
       }
 
       self
@@ -217,15 +243,14 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
     // increment entries with burned runes
     for (id, amount) in burned {
       *self.burned.entry(id).or_default() += amount;
+      self.activity.entry(id).or_default().burned += amount.n();
 
-      if let Some(sender) = self.event_sender {
-        sender.blocking_send(Event::RuneBurned {
-          block_height: self.height,
-          txid,
-          rune_id: id,
-          amount: amount.n(),
-        })?;
-      }
+      self.event_emitter.emit(Event::RuneBurned {
+        block_height: self.height,
+        txid,
+        rune_id: id,
+        amount: amount.n(),
+      })?;
     }
 
     Ok(())
@@ -238,6 +263,12 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
       self.id_to_entry.insert(&rune_id.store(), entry.store())?;
     }
 
+    for (rune_id, activity) in self.activity {
+      self
+        .id_and_height_to_activity
+        .insert((rune_id.store(), self.height), activity.store())?;
+    }
+
     Ok(())
   }
 
@@ -308,13 +339,11 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
 
     self.id_to_entry.insert(id.store(), entry.store())?;
 
-    if let Some(sender) = self.event_sender {
-      sender.blocking_send(Event::RuneEtched {
-        block_height: self.height,
-        txid,
-        rune_id: id,
-      })?;
-    }
+    self.event_emitter.emit(Event::RuneEtched {
+      block_height: self.height,
+      txid,
+      rune_id: id,
+    })?;
 
     let inscription_id = InscriptionId { txid, index: 0 };
 
@@ -472,20 +501,82 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
 
     // increment unallocated runes with the runes in tx inputs
     for input in &tx.input {
-      if let Some(guard) = self
-        .outpoint_to_balances
-        .remove(&input.previous_output.store())?
-      {
+      let outpoint = input.previous_output;
+
+      let mut balances = Vec::new();
+
+      if let Some(guard) = self.outpoint_to_balances.remove(&outpoint.store())? {
         let buffer = guard.value();
         let mut i = 0;
         while i < buffer.len() {
           let ((id, balance), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
           i += len;
-          *unallocated.entry(id).or_default() += balance;
+          balances.push((id, balance));
+        }
+      }
+
+      if balances.is_empty() {
+        continue;
+      }
+
+      let script_pubkey = self.spent_script_pubkeys.remove(&outpoint);
+
+      for (id, balance) in balances {
+        *unallocated.entry(id).or_default() += balance;
+
+        let activity = self.activity.entry(id).or_default();
+        activity.transferred = activity.transferred.saturating_add(balance);
+
+        self
+          .rune_id_to_balance_and_outpoint
+          .remove(id.store(), (balance, outpoint.store()))?;
+
+        if self.script_pubkey_balances.is_some() {
+          let script_pubkey = script_pubkey
+            .as_ref()
+            .unwrap_or_else(|| panic!("script pubkey for {outpoint} not found"));
+
+          self.update_script_pubkey_balance(id, script_pubkey, 0, balance)?;
         }
       }
     }
 
     Ok(unallocated)
   }
+
+  /// Credits and debits the balance of `id` held by `script_pubkey`, keeping
+  /// the holders of each rune ordered by balance.
+  fn update_script_pubkey_balance(
+    &mut self,
+    id: RuneId,
+    script_pubkey: &[u8],
+    credit: u128,
+    debit: u128,
+  ) -> Result {
+    let Some((balances, holders)) = self.script_pubkey_balances.as_mut() else {
+      return Ok(());
+    };
+
+    let id = id.store();
+
+    let old = balances
+      .get((id, script_pubkey))?
+      .map(|balance| balance.value())
+      .unwrap_or_default();
+
+    let new = old + credit - debit;
+
+    if old > 0 {
+      holders.remove(id, (old, script_pubkey))?;
+    }
+
+    if new > 0 {
+      balances.insert((id, script_pubkey), new)?;
+      holders.insert(id, (new, script_pubkey))?;
+    } else {
+      balances.remove((id, script_pubkey))?;
+    }
+
+    Ok(())
+  }
 }
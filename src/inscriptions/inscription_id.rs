@@ -42,6 +42,16 @@ impl Display for InscriptionId {
   }
 }
 
+impl schemars::JsonSchema for InscriptionId {
+  fn schema_name() -> String {
+    "InscriptionId".into()
+  }
+
+  fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    String::json_schema(generator)
+  }
+}
+
 #[derive(Debug)]
 pub enum ParseError {
   Character(char),
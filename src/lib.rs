@@ -35,7 +35,7 @@ use {
     blockdata::{constants::MAX_SCRIPT_ELEMENT_SIZE, locktime::absolute::LockTime},
     consensus::{self, Decodable, Encodable},
     hash_types::{BlockHash, TxMerkleNode},
-    hashes::Hash,
+    hashes::{sha256, Hash},
     script, Amount, Block, Network, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn,
     TxOut, Txid, Witness,
   },
@@ -85,7 +85,7 @@ use {
 pub use self::{
   chain::Chain,
   fee_rate::FeeRate,
-  index::{Index, RuneEntry},
+  index::{Index, InscriptionFilter, RuneEntry},
   inscriptions::{Envelope, Inscription, InscriptionId, PROTOCOL_ID},
   object::Object,
   options::Options,
@@ -42,13 +42,33 @@ pub struct Options {
   pub(crate) height_limit: Option<u32>,
   #[arg(long, help = "Use index at <INDEX>.")]
   pub(crate) index: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Track transactions funding and spending each address. Implies `--index-addresses`."
+  )]
+  pub(crate) index_address_history: bool,
   #[arg(long, help = "Track unspent output addresses.")]
   pub(crate) index_addresses: bool,
+  #[arg(
+    long,
+    help = "Store AuxPoW parent chain proofs of merge-mined blocks in index."
+  )]
+  pub(crate) index_auxpow: bool,
   #[arg(
     long,
     help = "Set index cache size to <INDEX_CACHE_SIZE> bytes. [default: 1/4 available RAM]"
   )]
   pub(crate) index_cache_size: Option<usize>,
+  #[arg(
+    long,
+    help = "Store inscription and rune events in index, so they can be replayed at `/events`."
+  )]
+  pub(crate) index_events: bool,
+  #[arg(
+    long,
+    help = "Store assembled content of multi-transaction partial inscriptions in index."
+  )]
+  pub(crate) index_partials: bool,
   #[arg(
     long,
     help = "Track location of runes. RUNES ARE IN AN UNFINISHED PRE-ALPHA STATE AND SUBJECT TO CHANGE AT ANY TIME."
@@ -56,10 +76,20 @@ pub struct Options {
   pub(crate) index_runes: bool,
   #[arg(long, help = "Track location of all satoshis.")]
   pub(crate) index_sats: bool,
+  #[arg(
+    long,
+    help = "Index words of text, JSON and markdown inscriptions for full-text search."
+  )]
+  pub(crate) index_text: bool,
   #[arg(long, help = "Store transactions in index.")]
   pub(crate) index_transactions: bool,
   #[arg(long, help = "Run in integration test mode.")]
   pub(crate) integration_test: bool,
+  #[arg(
+    long,
+    help = "Keep <MAX_SAVEPOINTS> persistent savepoints for reorg recovery. [default: 2]"
+  )]
+  pub(crate) max_savepoints: Option<u32>,
   #[clap(long, short, long, help = "Specify output format. [default: json]")]
   pub(crate) format: Option<OutputFormat>,
   #[arg(
@@ -69,6 +99,11 @@ pub struct Options {
     help = "Do not index inscriptions."
   )]
   pub(crate) no_index_inscriptions: bool,
+  #[arg(
+    long,
+    help = "Create a persistent savepoint every <SAVEPOINT_INTERVAL> blocks near the chain tip. [default: 10]"
+  )]
+  pub(crate) savepoint_interval: Option<u32>,
   #[arg(
     long,
     help = "Require basic HTTP authentication with <SERVER_PASSWORD>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
@@ -85,4 +120,9 @@ pub struct Options {
   pub(crate) signet: bool,
   #[arg(long, short, help = "Use testnet. Equivalent to `--chain testnet`.")]
   pub(crate) testnet: bool,
+  #[arg(
+    long,
+    help = "Record undo log for the last <UNDO_LOG_DEPTH> blocks, allowing reorgs deeper than savepoints to be rolled back. [default: 0]"
+  )]
+  pub(crate) undo_log_depth: Option<u32>,
 }
@@ -14,47 +14,79 @@ pub enum ParsedInscription {
   Complete(Inscription),
 }
 
+/// How far a chain of scriptSigs gets through a partial inscription.
+#[derive(Debug, PartialEq)]
+pub struct Progress {
+  pub body: Vec<u8>,
+  pub content_type: String,
+  pub pieces: u64,
+  pub remaining: u64,
+  pub scripts: usize,
+}
+
 pub struct InscriptionParser {}
 
 impl InscriptionParser {
   pub fn parse(sig_scripts: Vec<&Script>) -> ParsedInscription {
+    match Self::progress(sig_scripts) {
+      None => ParsedInscription::None,
+      Some(progress) if progress.remaining == 0 => {
+        let inscription = Inscription {
+          content_type: Some(progress.content_type.as_bytes().to_vec()),
+          body: Some(progress.body),
+          content_encoding: None,
+          delegate: None,
+          duplicate_field: false,
+          incomplete_field: false,
+          metadata: None,
+          metaprotocol: None,
+          parents: vec![],
+          pointer: None,
+          rune: None,
+          unrecognized_even_field: false,
+        };
+
+        ParsedInscription::Complete(inscription)
+      }
+      Some(_) => ParsedInscription::Partial,
+    }
+  }
+
+  /// Reads pieces from `sig_scripts` until the inscription is complete or the
+  /// scripts run out, returning `None` if they don't form a partial
+  /// inscription.
+  pub fn progress(sig_scripts: Vec<&Script>) -> Option<Progress> {
     let sig_script = &sig_scripts[0];
 
-    let mut push_datas_vec = match Self::decode_push_datas(sig_script) {
-      Some(push_datas) => push_datas,
-      None => return ParsedInscription::None,
-    };
+    let mut push_datas_vec = Self::decode_push_datas(sig_script)?;
 
     let mut push_datas = push_datas_vec.as_slice();
 
     // read protocol
 
     if push_datas.len() < 3 {
-      return ParsedInscription::None;
+      return None;
     }
 
     let protocol = &push_datas[0];
 
     if *protocol != PROTOCOL_ID {
-      return ParsedInscription::None;
+      return None;
     }
 
     // read npieces
 
-    let mut npieces = match Self::push_data_to_number(&push_datas[1]) {
-      Some(n) => n,
-      None => return ParsedInscription::None,
-    };
+    let pieces = Self::push_data_to_number(&push_datas[1])?;
 
-    if npieces == 0 {
-      return ParsedInscription::None;
+    if pieces == 0 {
+      return None;
     }
 
+    let mut npieces = pieces;
+
     // read content type
 
-    let Result::Ok(content_type) = String::from_utf8(push_datas[2].clone()) else {
-      return ParsedInscription::None;
-    };
+    let content_type = String::from_utf8(push_datas[2].clone()).ok()?;
 
     push_datas = &push_datas[3..];
 
@@ -62,6 +94,8 @@ impl InscriptionParser {
 
     let mut body = vec![];
 
+    let mut scripts = 1;
+
     let mut sig_scripts = sig_scripts.as_slice();
 
     // loop over transactions
@@ -69,22 +103,13 @@ impl InscriptionParser {
       // loop over chunks
       loop {
         if npieces == 0 {
-          let inscription = Inscription {
-            content_type: Some(content_type.as_bytes().to_vec()),
-            body: Some(body),
-            content_encoding: None,
-            delegate: None,
-            duplicate_field: false,
-            incomplete_field: false,
-            metadata: None,
-            metaprotocol: None,
-            parents: vec![],
-            pointer: None,
-            rune: None,
-            unrecognized_even_field: false,
-          };
-
-          return ParsedInscription::Complete(inscription);
+          return Some(Progress {
+            body,
+            content_type,
+            pieces,
+            remaining: 0,
+            scripts,
+          });
         }
 
         if push_datas.len() < 2 {
@@ -107,27 +132,28 @@ impl InscriptionParser {
       }
 
       if sig_scripts.len() <= 1 {
-        return ParsedInscription::Partial;
+        return Some(Progress {
+          body,
+          content_type,
+          pieces,
+          remaining: npieces,
+          scripts,
+        });
       }
 
       sig_scripts = &sig_scripts[1..];
+      scripts += 1;
 
-      push_datas_vec = match Self::decode_push_datas(sig_scripts[0]) {
-        Some(push_datas) => push_datas,
-        None => return ParsedInscription::None,
-      };
+      push_datas_vec = Self::decode_push_datas(sig_scripts[0])?;
 
       if push_datas_vec.len() < 2 {
-        return ParsedInscription::None;
+        return None;
       }
 
-      let next = match Self::push_data_to_number(&push_datas_vec[0]) {
-        Some(n) => n,
-        None => return ParsedInscription::None,
-      };
+      let next = Self::push_data_to_number(&push_datas_vec[0])?;
 
       if next != npieces - 1 {
-        return ParsedInscription::None;
+        return None;
       }
 
       push_datas = push_datas_vec.as_slice();
@@ -18,16 +18,24 @@ pub struct Settings {
   hidden: Option<HashSet<InscriptionId>>,
   http_port: Option<u16>,
   index: Option<PathBuf>,
+  index_address_history: bool,
   index_addresses: bool,
+  index_auxpow: bool,
   index_cache_size: Option<usize>,
+  index_events: bool,
+  index_partials: bool,
   index_runes: bool,
   index_sats: bool,
+  index_text: bool,
   index_transactions: bool,
   integration_test: bool,
+  max_savepoints: Option<u32>,
   no_index_inscriptions: bool,
+  savepoint_interval: Option<u32>,
   server_password: Option<String>,
   server_url: Option<String>,
   server_username: Option<String>,
+  undo_log_depth: Option<u32>,
 }
 
 impl Settings {
@@ -131,16 +139,24 @@ impl Settings {
       ),
       http_port: self.http_port.or(source.http_port),
       index: self.index.or(source.index),
+      index_address_history: self.index_address_history || source.index_address_history,
       index_addresses: self.index_addresses || source.index_addresses,
+      index_auxpow: self.index_auxpow || source.index_auxpow,
       index_cache_size: self.index_cache_size.or(source.index_cache_size),
+      index_events: self.index_events || source.index_events,
+      index_partials: self.index_partials || source.index_partials,
       index_runes: self.index_runes || source.index_runes,
       index_sats: self.index_sats || source.index_sats,
+      index_text: self.index_text || source.index_text,
       index_transactions: self.index_transactions || source.index_transactions,
       integration_test: self.integration_test || source.integration_test,
+      max_savepoints: self.max_savepoints.or(source.max_savepoints),
       no_index_inscriptions: self.no_index_inscriptions || source.no_index_inscriptions,
+      savepoint_interval: self.savepoint_interval.or(source.savepoint_interval),
       server_password: self.server_password.or(source.server_password),
       server_url: self.server_url.or(source.server_url),
       server_username: self.server_username.or(source.server_username),
+      undo_log_depth: self.undo_log_depth.or(source.undo_log_depth),
     }
   }
 
@@ -166,16 +182,24 @@ impl Settings {
       hidden: None,
       http_port: None,
       index: options.index,
+      index_address_history: options.index_address_history,
       index_addresses: options.index_addresses,
+      index_auxpow: options.index_auxpow,
       index_cache_size: options.index_cache_size,
+      index_events: options.index_events,
+      index_partials: options.index_partials,
       index_runes: options.index_runes,
       index_sats: options.index_sats,
+      index_text: options.index_text,
       index_transactions: options.index_transactions,
       integration_test: options.integration_test,
+      max_savepoints: options.max_savepoints,
       no_index_inscriptions: options.no_index_inscriptions,
+      savepoint_interval: options.savepoint_interval,
       server_password: options.server_password,
       server_url: None,
       server_username: options.server_username,
+      undo_log_depth: options.undo_log_depth,
     }
   }
 
@@ -254,16 +278,24 @@ impl Settings {
       hidden: inscriptions("HIDDEN")?,
       http_port: get_u16("HTTP_PORT")?,
       index: get_path("INDEX"),
+      index_address_history: get_bool("INDEX_ADDRESS_HISTORY"),
       index_addresses: get_bool("INDEX_ADDRESSES"),
+      index_auxpow: get_bool("INDEX_AUXPOW"),
       index_cache_size: get_usize("INDEX_CACHE_SIZE")?,
+      index_events: get_bool("INDEX_EVENTS"),
+      index_partials: get_bool("INDEX_PARTIALS"),
       index_runes: get_bool("INDEX_RUNES"),
       index_sats: get_bool("INDEX_SATS"),
+      index_text: get_bool("INDEX_TEXT"),
       index_transactions: get_bool("INDEX_TRANSACTIONS"),
       integration_test: get_bool("INTEGRATION_TEST"),
+      max_savepoints: get_u32("MAX_SAVEPOINTS")?,
       no_index_inscriptions: get_bool("NO_INDEX_INSCRIPTIONS"),
+      savepoint_interval: get_u32("SAVEPOINT_INTERVAL")?,
       server_password: get_string("SERVER_PASSWORD"),
       server_url: get_string("SERVER_URL"),
       server_username: get_string("SERVER_USERNAME"),
+      undo_log_depth: get_u32("UNDO_LOG_DEPTH")?,
     })
   }
 
@@ -284,16 +316,24 @@ impl Settings {
       hidden: None,
       http_port: None,
       index: None,
+      index_address_history: false,
       index_addresses: true,
+      index_auxpow: false,
       index_cache_size: None,
+      index_events: false,
+      index_partials: false,
       index_runes: true,
       index_sats: true,
+      index_text: false,
       index_transactions: false,
       integration_test: false,
+      max_savepoints: None,
       no_index_inscriptions: false,
+      savepoint_interval: None,
       server_password: None,
       server_url: Some(server_url.into()),
       server_username: None,
+      undo_log_depth: None,
     }
   }
 
@@ -330,6 +370,20 @@ impl Settings {
       None => data_dir.join("index.redb"),
     };
 
+    let max_savepoints = self.max_savepoints.unwrap_or(2);
+
+    ensure!(
+      max_savepoints > 0,
+      "max savepoints must be greater than zero"
+    );
+
+    let savepoint_interval = self.savepoint_interval.unwrap_or(10);
+
+    ensure!(
+      savepoint_interval > 0,
+      "savepoint interval must be greater than zero"
+    );
+
     Ok(Self {
       bitcoin_data_dir: Some(bitcoin_data_dir),
       bitcoin_rpc_limit: Some(self.bitcoin_rpc_limit.unwrap_or(12)),
@@ -351,7 +405,9 @@ impl Settings {
       hidden: self.hidden,
       http_port: self.http_port,
       index: Some(index),
+      index_address_history: self.index_address_history,
       index_addresses: self.index_addresses,
+      index_auxpow: self.index_auxpow,
       index_cache_size: Some(match self.index_cache_size {
         Some(index_cache_size) => index_cache_size,
         None => {
@@ -360,14 +416,20 @@ impl Settings {
           usize::try_from(sys.total_memory() / 4)?
         }
       }),
+      index_events: self.index_events,
+      index_partials: self.index_partials,
       index_runes: self.index_runes,
       index_sats: self.index_sats,
+      index_text: self.index_text,
       index_transactions: self.index_transactions,
       integration_test: self.integration_test,
+      max_savepoints: Some(max_savepoints),
       no_index_inscriptions: self.no_index_inscriptions,
+      savepoint_interval: Some(savepoint_interval),
       server_password: self.server_password,
       server_url: self.server_url,
       server_username: self.server_username,
+      undo_log_depth: Some(self.undo_log_depth.unwrap_or(0)),
     })
   }
 
@@ -468,6 +530,18 @@ impl Settings {
     self.commit_interval.unwrap()
   }
 
+  pub fn max_savepoints(&self) -> u32 {
+    self.max_savepoints.unwrap()
+  }
+
+  pub fn savepoint_interval(&self) -> u32 {
+    self.savepoint_interval.unwrap()
+  }
+
+  pub fn undo_log_depth(&self) -> u32 {
+    self.undo_log_depth.unwrap()
+  }
+
   pub fn cookie_file(&self) -> Result<PathBuf> {
     if let Some(cookie_file) = &self.cookie_file {
       return Ok(cookie_file.clone());
@@ -525,14 +599,30 @@ impl Settings {
     self.index.as_ref().unwrap()
   }
 
+  pub fn index_address_history_raw(&self) -> bool {
+    self.index_address_history
+  }
+
   pub fn index_addresses_raw(&self) -> bool {
     self.index_addresses
   }
 
+  pub fn index_auxpow_raw(&self) -> bool {
+    self.index_auxpow
+  }
+
   pub fn index_inscriptions_raw(&self) -> bool {
     !self.no_index_inscriptions
   }
 
+  pub fn index_events_raw(&self) -> bool {
+    self.index_events
+  }
+
+  pub fn index_partials_raw(&self) -> bool {
+    self.index_partials
+  }
+
   pub fn index_runes_raw(&self) -> bool {
     self.index_runes
   }
@@ -545,6 +635,10 @@ impl Settings {
     self.index_sats
   }
 
+  pub fn index_text_raw(&self) -> bool {
+    self.index_text
+  }
+
   pub fn index_transactions_raw(&self) -> bool {
     self.index_transactions
   }
@@ -908,6 +1002,42 @@ mod tests {
     assert_eq!(arguments.options.commit_interval, Some(500));
   }
 
+  #[test]
+  fn setting_savepoints() {
+    let settings = parse(&[]);
+    assert_eq!(settings.max_savepoints(), 2);
+    assert_eq!(settings.savepoint_interval(), 10);
+    assert_eq!(settings.undo_log_depth(), 0);
+
+    let settings = parse(&[
+      "--max-savepoints=5",
+      "--savepoint-interval=20",
+      "--undo-log-depth=100",
+    ]);
+    assert_eq!(settings.max_savepoints(), 5);
+    assert_eq!(settings.savepoint_interval(), 20);
+    assert_eq!(settings.undo_log_depth(), 100);
+  }
+
+  #[test]
+  fn savepoint_settings_must_be_nonzero() {
+    assert_eq!(
+      Settings::from_options(Options::try_parse_from(["ord", "--max-savepoints=0"]).unwrap())
+        .or_defaults()
+        .unwrap_err()
+        .to_string(),
+      "max savepoints must be greater than zero"
+    );
+
+    assert_eq!(
+      Settings::from_options(Options::try_parse_from(["ord", "--savepoint-interval=0"]).unwrap())
+        .or_defaults()
+        .unwrap_err()
+        .to_string(),
+      "savepoint interval must be greater than zero"
+    );
+  }
+
   #[test]
   fn index_runes() {
     assert!(parse(&["--chain=signet", "--index-runes"]).index_runes_raw());
@@ -1016,15 +1146,23 @@ mod tests {
     ("HTTP_PORT", "8080"),
       ("INDEX", "index"),
       ("INDEX_CACHE_SIZE", "4"),
+      ("INDEX_ADDRESS_HISTORY", "1"),
       ("INDEX_ADDRESSES", "1"),
+      ("INDEX_AUXPOW", "1"),
+      ("INDEX_EVENTS", "1"),
+      ("INDEX_PARTIALS", "1"),
       ("INDEX_RUNES", "1"),
       ("INDEX_SATS", "1"),
+      ("INDEX_TEXT", "1"),
       ("INDEX_TRANSACTIONS", "1"),
       ("INTEGRATION_TEST", "1"),
+      ("MAX_SAVEPOINTS", "5"),
       ("NO_INDEX_INSCRIPTIONS", "1"),
+      ("SAVEPOINT_INTERVAL", "20"),
       ("SERVER_PASSWORD", "server password"),
       ("SERVER_URL", "server url"),
       ("SERVER_USERNAME", "server username"),
+      ("UNDO_LOG_DEPTH", "100"),
     ]
     .into_iter()
     .map(|(key, value)| (key.into(), value.into()))
@@ -1059,16 +1197,24 @@ mod tests {
         ),
         http_port: Some(8080),
         index: Some("index".into()),
+        index_address_history: true,
         index_addresses: true,
+        index_auxpow: true,
         index_cache_size: Some(4),
+        index_events: true,
+        index_partials: true,
         index_runes: true,
         index_sats: true,
+        index_text: true,
         index_transactions: true,
         integration_test: true,
+        max_savepoints: Some(5),
         no_index_inscriptions: true,
+        savepoint_interval: Some(20),
         server_password: Some("server password".into()),
         server_url: Some("server url".into()),
         server_username: Some("server username".into()),
+        undo_log_depth: Some(100),
       }
     );
   }
@@ -1091,16 +1237,24 @@ mod tests {
           "--cookie-file=cookie file",
           "--datadir=/data/dir",
           "--height-limit=3",
+          "--index-address-history",
           "--index-addresses",
+          "--index-auxpow",
           "--index-cache-size=4",
+          "--index-events",
+          "--index-partials",
           "--index-runes",
           "--index-sats",
+          "--index-text",
           "--index-transactions",
           "--index=index",
           "--integration-test",
+          "--max-savepoints=5",
           "--no-index-inscriptions",
+          "--savepoint-interval=20",
           "--server-password=server password",
           "--server-username=server username",
+          "--undo-log-depth=100",
         ])
         .unwrap()
       ),
@@ -1120,16 +1274,24 @@ mod tests {
         hidden: None,
         http_port: None,
         index: Some("index".into()),
+        index_address_history: true,
         index_addresses: true,
+        index_auxpow: true,
         index_cache_size: Some(4),
+        index_events: true,
+        index_partials: true,
         index_runes: true,
         index_sats: true,
+        index_text: true,
         index_transactions: true,
         integration_test: true,
+        max_savepoints: Some(5),
         no_index_inscriptions: true,
+        savepoint_interval: Some(20),
         server_password: Some("server password".into()),
         server_url: None,
         server_username: Some("server username".into()),
+        undo_log_depth: Some(100),
       }
     );
   }
@@ -1,17 +1,84 @@
-use super::*;
+use {
+  super::*,
+  crate::partials::{InscriptionParser, Progress},
+};
 
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
 pub struct CompactOutput {
   pub inscriptions: Vec<CompactInscription>,
+  #[serde(default)]
+  pub partial: Option<CompactPartial>,
   pub runestone: Option<Artifact>,
 }
 
-#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, schemars::JsonSchema)]
 pub struct RawOutput {
+  #[schemars(with = "Vec<serde_json::Value>")]
   pub inscriptions: Vec<ParsedEnvelope>,
+  #[serde(default)]
+  pub partial: Option<RawPartial>,
+  #[schemars(with = "Option<serde_json::Value>")]
   pub runestone: Option<Artifact>,
 }
 
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, Copy, Clone, schemars::JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PartialStatus {
+  Complete,
+  Partial,
+}
+
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+pub struct CompactPartial {
+  pub body: String,
+  pub content_type: String,
+  pub pieces: u64,
+  pub pieces_remaining: u64,
+  pub status: PartialStatus,
+  pub transactions: Vec<Txid>,
+}
+
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, schemars::JsonSchema)]
+pub struct RawPartial {
+  pub body: Vec<u8>,
+  pub content_type: String,
+  pub pieces: u64,
+  pub pieces_remaining: u64,
+  pub status: PartialStatus,
+  #[schemars(with = "Vec<String>")]
+  pub transactions: Vec<Txid>,
+}
+
+impl RawPartial {
+  fn new(progress: Progress, transactions: Vec<Txid>) -> Self {
+    Self {
+      body: progress.body,
+      content_type: progress.content_type,
+      pieces: progress.pieces,
+      pieces_remaining: progress.remaining,
+      status: if progress.remaining == 0 {
+        PartialStatus::Complete
+      } else {
+        PartialStatus::Partial
+      },
+      transactions,
+    }
+  }
+}
+
+impl From<RawPartial> for CompactPartial {
+  fn from(partial: RawPartial) -> Self {
+    Self {
+      body: hex::encode(partial.body),
+      content_type: partial.content_type,
+      pieces: partial.pieces,
+      pieces_remaining: partial.pieces_remaining,
+      status: partial.status,
+      transactions: partial.transactions,
+    }
+  }
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
 pub struct CompactInscription {
@@ -61,8 +128,12 @@ pub(crate) struct Decode {
     help = "Fetch transaction with <TXID> from Bitcoin Core."
   )]
   txid: Option<Txid>,
-  #[arg(long, conflicts_with = "txid", help = "Load transaction from <FILE>.")]
-  file: Option<PathBuf>,
+  #[arg(
+    long,
+    conflicts_with = "txid",
+    help = "Load transaction from <FILE>. May be repeated to decode a partial inscription spread across several transactions, in chain order."
+  )]
+  file: Vec<PathBuf>,
   #[arg(
     long,
     help = "Serialize inscriptions in a compact, human-readable format."
@@ -72,14 +143,32 @@ pub(crate) struct Decode {
 
 impl Decode {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
-    let transaction = if let Some(txid) = self.txid {
-      settings
-        .bitcoin_rpc_client(None)?
-        .get_raw_transaction(&txid, None)?
-    } else if let Some(file) = self.file {
-      Transaction::consensus_decode(&mut fs::File::open(file)?)?
+    let (transaction, partial) = if let Some(txid) = self.txid {
+      let client = settings.bitcoin_rpc_client(None)?;
+
+      let transaction = client.get_raw_transaction(&txid, None)?;
+
+      let partial = Self::follow_partial(vec![transaction.clone()], |outpoint| {
+        Self::find_spender(&client, outpoint)
+      })?;
+
+      (transaction, partial)
+    } else if !self.file.is_empty() {
+      let transactions = self
+        .file
+        .iter()
+        .map(|file| Ok(Transaction::consensus_decode(&mut fs::File::open(file)?)?))
+        .collect::<Result<Vec<Transaction>>>()?;
+
+      let partial = Self::follow_partial(transactions.clone(), |_outpoint| Ok(None))?;
+
+      (transactions.into_iter().next().unwrap(), partial)
     } else {
-      Transaction::consensus_decode(&mut io::stdin())?
+      let transaction = Transaction::consensus_decode(&mut io::stdin())?;
+
+      let partial = Self::follow_partial(vec![transaction.clone()], |_outpoint| Ok(None))?;
+
+      (transaction, partial)
     };
 
     let inscriptions = ParsedEnvelope::from_transaction(&transaction);
@@ -93,13 +182,281 @@ impl Decode {
           .into_iter()
           .map(|inscription| inscription.payload.try_into())
           .collect::<Result<Vec<CompactInscription>>>()?,
+        partial: partial.map(CompactPartial::from),
         runestone,
       })))
     } else {
       Ok(Some(Box::new(RawOutput {
         inscriptions,
+        partial,
         runestone,
       })))
     }
   }
+
+  /// Follows the partial inscription started by the first of `transactions`
+  /// through the rest of them, and then through the transactions returned by
+  /// `next`, which is called with the outpoint the next piece must spend.
+  pub(crate) fn follow_partial(
+    transactions: Vec<Transaction>,
+    mut next: impl FnMut(OutPoint) -> Result<Option<Transaction>>,
+  ) -> Result<Option<RawPartial>> {
+    let mut transactions = transactions.into_iter();
+
+    let first = transactions.next().unwrap();
+
+    let Some(input) = first
+      .input
+      .iter()
+      .position(|txin| InscriptionParser::progress(vec![txin.script_sig.as_script()]).is_some())
+    else {
+      return Ok(None);
+    };
+
+    let mut chain = vec![(first, input)];
+
+    loop {
+      let progress = InscriptionParser::progress(
+        chain
+          .iter()
+          .map(|(tx, input)| tx.input[*input].script_sig.as_script())
+          .collect(),
+      )
+      .ok_or_else(|| {
+        anyhow!(
+          "transaction {} does not continue partial inscription",
+          chain.last().unwrap().0.txid()
+        )
+      })?;
+
+      if progress.remaining == 0 {
+        if let Some(tx) = transactions.next() {
+          bail!(
+            "partial inscription is complete before transaction {}",
+            tx.txid()
+          );
+        }
+      }
+
+      let (tx, input) = chain.last().unwrap();
+
+      let outpoint = OutPoint {
+        txid: tx.txid(),
+        vout: (*input).try_into().unwrap(),
+      };
+
+      let next = if progress.remaining == 0 {
+        None
+      } else if let Some(tx) = transactions.next() {
+        Some(tx)
+      } else {
+        next(outpoint)?
+      };
+
+      let Some(next) = next else {
+        return Ok(Some(RawPartial::new(
+          progress,
+          chain.iter().map(|(tx, _input)| tx.txid()).collect(),
+        )));
+      };
+
+      let input = next
+        .input
+        .iter()
+        .position(|txin| txin.previous_output == outpoint)
+        .ok_or_else(|| anyhow!("transaction {} does not spend {outpoint}", next.txid()))?;
+
+      chain.push((next, input));
+    }
+  }
+
+  /// Looks for the confirmed transaction that spends `outpoint`, scanning
+  /// forward from the block that contains it. Blocks are only scanned once
+  /// the output is known to exist and to be spent.
+  fn find_spender(client: &Client, outpoint: OutPoint) -> Result<Option<Transaction>> {
+    let Some(info) = client
+      .get_raw_transaction_info(&outpoint.txid, None)
+      .into_option()?
+    else {
+      return Ok(None);
+    };
+
+    if outpoint.vout.into_usize() >= info.vout.len() {
+      return Ok(None);
+    }
+
+    if client
+      .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+      .is_some()
+    {
+      return Ok(None);
+    }
+
+    let Some(blockhash) = info.blockhash else {
+      return Ok(None);
+    };
+
+    let start = client.get_block_header_info(&blockhash)?.height;
+
+    let count = client.get_block_count()?;
+
+    for height in u64::try_from(start).unwrap()..=count {
+      let block = client.get_block(&client.get_block_hash(height)?)?;
+
+      for tx in block.txdata {
+        if tx.input.iter().any(|txin| txin.previous_output == outpoint) {
+          return Ok(Some(tx));
+        }
+      }
+    }
+
+    Ok(None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn transaction(previous_output: OutPoint, script_sig: ScriptBuf) -> Transaction {
+    Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![
+        tx_in(OutPoint::null()),
+        TxIn {
+          previous_output,
+          script_sig,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        },
+      ],
+      output: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn partial_inscriptions_are_followed_to_spenders() {
+    let first = transaction(
+      OutPoint::null(),
+      script::Builder::new()
+        .push_slice(b"ord")
+        .push_int(3)
+        .push_slice(b"text/plain")
+        .push_int(2)
+        .push_slice(b"foo")
+        .into_script(),
+    );
+
+    let second = transaction(
+      OutPoint {
+        txid: first.txid(),
+        vout: 1,
+      },
+      script::Builder::new()
+        .push_int(1)
+        .push_slice(b"bar")
+        .into_script(),
+    );
+
+    let third = transaction(
+      OutPoint {
+        txid: second.txid(),
+        vout: 1,
+      },
+      script::Builder::new()
+        .push_int(0)
+        .push_slice(b"baz")
+        .into_script(),
+    );
+
+    let spenders = [second.clone(), third.clone()];
+
+    let mut lookups = Vec::new();
+
+    assert_eq!(
+      Decode::follow_partial(vec![first.clone()], |outpoint| {
+        lookups.push(outpoint);
+        Ok(
+          spenders
+            .iter()
+            .find(|tx| tx.input[1].previous_output == outpoint)
+            .cloned(),
+        )
+      })
+      .unwrap(),
+      Some(RawPartial {
+        body: b"foobarbaz".into(),
+        content_type: "text/plain".into(),
+        pieces: 3,
+        pieces_remaining: 0,
+        status: PartialStatus::Complete,
+        transactions: vec![first.txid(), second.txid(), third.txid()],
+      }),
+    );
+
+    assert_eq!(
+      lookups,
+      [
+        OutPoint {
+          txid: first.txid(),
+          vout: 1
+        },
+        OutPoint {
+          txid: second.txid(),
+          vout: 1
+        },
+      ]
+    );
+
+    assert_eq!(
+      Decode::follow_partial(vec![first.clone(), second.clone()], |_outpoint| Ok(None))
+        .unwrap()
+        .map(|partial| (partial.status, partial.pieces_remaining)),
+      Some((PartialStatus::Partial, 1)),
+    );
+  }
+
+  #[test]
+  fn transactions_without_partial_inscriptions_are_ignored() {
+    assert_eq!(
+      Decode::follow_partial(
+        vec![transaction(OutPoint::null(), ScriptBuf::new())],
+        |_outpoint| panic!()
+      )
+      .unwrap(),
+      None,
+    );
+  }
+
+  #[test]
+  fn spenders_are_only_searched_for_spent_outputs() {
+    let core = mockcore::spawn();
+
+    let client = Client::new(&core.url(), bellscoincore_rpc::Auth::None).unwrap();
+
+    core.mine_blocks(1);
+
+    let txid = core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Witness::new())],
+      ..default()
+    });
+
+    core.mine_blocks(1);
+
+    let find_spender = |txid, vout| Decode::find_spender(&client, OutPoint { txid, vout }).unwrap();
+
+    assert_eq!(find_spender(Txid::all_zeros(), 0), None);
+    assert_eq!(find_spender(txid, 1), None);
+    assert_eq!(find_spender(txid, 0), None);
+
+    let spender = core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      ..default()
+    });
+
+    core.mine_blocks(1);
+
+    assert_eq!(find_spender(txid, 0).unwrap().txid(), spender);
+  }
 }
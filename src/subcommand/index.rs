@@ -1,15 +1,21 @@
 use super::*;
 
-mod export;
+pub mod export;
 pub mod info;
+mod rollback;
+mod snapshot;
 mod update;
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
-  #[command(about = "Write inscription numbers and ids to a tab-separated file")]
+  #[command(about = "Export index data to a JSONL, CSV or TSV file")]
   Export(export::Export),
   #[command(about = "Print index statistics")]
   Info(info::Info),
+  #[command(about = "Roll back the index to before a block height")]
+  Rollback(rollback::Rollback),
+  #[command(subcommand, about = "Create or restore index snapshots")]
+  Snapshot(snapshot::Snapshot),
   #[command(about = "Update the index", alias = "run")]
   Update,
 }
@@ -19,6 +25,8 @@ impl IndexSubcommand {
     match self {
       Self::Export(export) => export.run(settings),
       Self::Info(info) => info.run(settings),
+      Self::Rollback(rollback) => rollback.run(settings),
+      Self::Snapshot(snapshot) => snapshot.run(settings),
       Self::Update => update::run(settings),
     }
   }
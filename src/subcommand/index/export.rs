@@ -1,11 +1,132 @@
-use super::*;
+use {
+  super::*,
+  crate::index::{ChildRecord, InscriptionRecord, RuneBalanceRecord, RuneRecord, Tabular},
+  clap::ValueEnum,
+  std::io::{BufWriter, Write},
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Export {
   #[arg(long, help = "Include addresses in export")]
   include_addresses: bool,
-  #[arg(long, help = "Write export to <TSV>")]
-  tsv: String,
+  #[arg(
+    long,
+    conflicts_with_all = ["output", "format", "dataset", "from_height", "to_height"],
+    help = "Write legacy inscription number, id and satpoint export to <TSV>"
+  )]
+  tsv: Option<String>,
+  #[arg(
+    long,
+    required_unless_present = "tsv",
+    help = "Write export to <OUTPUT>"
+  )]
+  output: Option<PathBuf>,
+  #[arg(long, value_enum, default_value_t, help = "Write export as <FORMAT>")]
+  format: Format,
+  #[arg(long, value_enum, default_value_t, help = "Export <DATASET>")]
+  dataset: Dataset,
+  #[arg(long, help = "Only export records from <FROM_HEIGHT> onward")]
+  from_height: Option<u32>,
+  #[arg(long, help = "Only export records up to and including <TO_HEIGHT>")]
+  to_height: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+enum Format {
+  Csv,
+  #[default]
+  Jsonl,
+  Tsv,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+enum Dataset {
+  Children,
+  #[default]
+  Inscriptions,
+  RuneBalances,
+  Runes,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub height: u32,
+  pub records: u64,
+}
+
+struct Writer<W: Write> {
+  format: Format,
+  inner: W,
+  records: u64,
+}
+
+impl<W: Write> Writer<W> {
+  fn new<R: Tabular>(format: Format, mut inner: W) -> Result<Self> {
+    match format {
+      Format::Csv | Format::Tsv => Self::write_row(format, &mut inner, R::HEADER)?,
+      Format::Jsonl => {}
+    }
+
+    Ok(Self {
+      format,
+      inner,
+      records: 0,
+    })
+  }
+
+  fn write<R: Serialize + Tabular>(&mut self, record: R) -> Result {
+    match self.format {
+      Format::Csv | Format::Tsv => Self::write_row(self.format, &mut self.inner, &record.fields())?,
+      Format::Jsonl => {
+        serde_json::to_writer(&mut self.inner, &record)?;
+        writeln!(self.inner)?;
+      }
+    }
+
+    self.records += 1;
+
+    Ok(())
+  }
+
+  fn write_row(format: Format, writer: &mut W, fields: &[impl AsRef<str>]) -> Result {
+    let (separator, escape): (char, fn(&str) -> String) = match format {
+      Format::Csv => (',', Self::escape_csv),
+      Format::Tsv => ('\t', Self::escape_tsv),
+      Format::Jsonl => unreachable!(),
+    };
+
+    for (i, field) in fields.iter().enumerate() {
+      if i > 0 {
+        write!(writer, "{separator}")?;
+      }
+      write!(writer, "{}", escape(field.as_ref()))?;
+    }
+
+    writeln!(writer)?;
+
+    Ok(())
+  }
+
+  fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+      format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+      field.into()
+    }
+  }
+
+  fn escape_tsv(field: &str) -> String {
+    field
+      .replace('\\', "\\\\")
+      .replace('\t', "\\t")
+      .replace('\n', "\\n")
+      .replace('\r', "\\r")
+  }
+
+  fn finish(mut self) -> Result<u64> {
+    self.inner.flush()?;
+    Ok(self.records)
+  }
 }
 
 impl Export {
@@ -13,8 +134,118 @@ impl Export {
     let index = Index::open(&settings)?;
 
     index.update()?;
-    index.export(&self.tsv, self.include_addresses)?;
 
-    Ok(None)
+    if let Some(tsv) = &self.tsv {
+      index.export(tsv, self.include_addresses)?;
+      return Ok(None);
+    }
+
+    let height = index.block_count()?;
+
+    let heights = self.from_height.unwrap_or_default()..=self.to_height.unwrap_or(u32::MAX);
+
+    ensure!(
+      heights.start() <= heights.end(),
+      "--from-height must not be greater than --to-height"
+    );
+
+    let file = BufWriter::new(fs::File::create(self.output.as_ref().unwrap())?);
+
+    let records = match self.dataset {
+      Dataset::Children => {
+        let mut writer = Writer::new::<ChildRecord>(self.format, file)?;
+        index.export_children(heights, |record| writer.write(record))?;
+        writer.finish()?
+      }
+      Dataset::Inscriptions => {
+        let mut writer = Writer::new::<InscriptionRecord>(self.format, file)?;
+        index.export_inscriptions(heights, self.include_addresses, |record| {
+          writer.write(record)
+        })?;
+        writer.finish()?
+      }
+      Dataset::RuneBalances => {
+        ensure!(
+          self.from_height.is_none() && self.to_height.is_none(),
+          "rune balances are a snapshot of the current UTXO set and cannot be exported by height"
+        );
+        let mut writer = Writer::new::<RuneBalanceRecord>(self.format, file)?;
+        index.export_rune_balances(|record| writer.write(record))?;
+        writer.finish()?
+      }
+      Dataset::Runes => {
+        let mut writer = Writer::new::<RuneRecord>(self.format, file)?;
+        index.export_runes(heights, |record| writer.write(record))?;
+        writer.finish()?
+      }
+    };
+
+    Ok(Some(Box::new(Output { height, records })))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn csv_fields_are_quoted_when_needed() {
+    assert_eq!(Writer::<Vec<u8>>::escape_csv("text/plain"), "text/plain");
+    assert_eq!(
+      Writer::<Vec<u8>>::escape_csv("text/plain;charset=utf-8,latin"),
+      "\"text/plain;charset=utf-8,latin\""
+    );
+    assert_eq!(Writer::<Vec<u8>>::escape_csv("a\"b"), "\"a\"\"b\"");
+    assert_eq!(Writer::<Vec<u8>>::escape_csv("a\nb"), "\"a\nb\"");
+  }
+
+  #[test]
+  fn tsv_fields_are_escaped() {
+    assert_eq!(
+      Writer::<Vec<u8>>::escape_tsv("a\tb\nc\\d"),
+      "a\\tb\\nc\\\\d"
+    );
+  }
+
+  #[test]
+  fn rows_are_written_in_each_format() {
+    let record = ChildRecord {
+      parent: inscription_id(1),
+      child: inscription_id(2),
+      height: 3,
+    };
+
+    let write = |format| {
+      let mut writer = Writer::new::<ChildRecord>(format, Vec::new()).unwrap();
+      writer
+        .write(ChildRecord {
+          parent: record.parent,
+          child: record.child,
+          height: record.height,
+        })
+        .unwrap();
+      String::from_utf8(writer.inner).unwrap()
+    };
+
+    assert_eq!(
+      write(Format::Csv),
+      format!(
+        "parent,child,height\n{},{},3\n",
+        record.parent, record.child
+      ),
+    );
+
+    assert_eq!(
+      write(Format::Tsv),
+      format!(
+        "parent\tchild\theight\n{}\t{}\t3\n",
+        record.parent, record.child
+      ),
+    );
+
+    assert_eq!(
+      serde_json::from_str::<ChildRecord>(write(Format::Jsonl).trim_end()).unwrap(),
+      record,
+    );
   }
 }
@@ -0,0 +1,18 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Rollback {
+  #[arg(
+    long,
+    help = "Remove blocks at and above <HEIGHT> from the index. Rolls back to the newest savepoint or undo log entry before <HEIGHT>, so more blocks may be removed."
+  )]
+  height: u32,
+}
+
+impl Rollback {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    Ok(Some(Box::new(index.rollback(self.height)?)))
+  }
+}
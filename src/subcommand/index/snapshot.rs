@@ -0,0 +1,42 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Snapshot {
+  #[command(about = "Write a compacted, checksummed snapshot of the index")]
+  Create(Create),
+  #[command(about = "Install an index snapshot after verifying it against the node")]
+  Restore(Restore),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[arg(long, help = "Write snapshot to <OUTPUT>")]
+  output: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Restore {
+  #[arg(long, help = "Replace an existing index")]
+  force: bool,
+  #[arg(long, help = "Read snapshot from <INPUT>")]
+  input: PathBuf,
+}
+
+impl Snapshot {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    match self {
+      Self::Create(create) => {
+        let index = Index::open(&settings)?;
+
+        index.update()?;
+
+        Ok(Some(Box::new(index.create_snapshot(&create.output)?)))
+      }
+      Self::Restore(restore) => Ok(Some(Box::new(Index::restore_snapshot(
+        &settings,
+        &restore.input,
+        restore.force,
+      )?))),
+    }
+  }
+}
@@ -54,7 +54,9 @@ impl List {
       indexed: list.indexed,
       inscriptions: list.inscriptions,
       runes: list.runes,
-      sat_ranges: list.sat_ranges.map(output_ranges),
+      sat_ranges: list
+        .sat_ranges
+        .map(|ranges| output_ranges(ranges, settings.chain().network())),
       script_pubkey: list.script_pubkey.to_asm_string(),
       spent: list.spent,
       transaction: list.transaction.to_string(),
@@ -63,7 +65,7 @@ impl List {
   }
 }
 
-fn output_ranges(ranges: Vec<(u64, u64)>) -> Vec<Range> {
+fn output_ranges(ranges: Vec<(u64, u64)>, network: Network) -> Vec<Range> {
   let mut offset = 0;
   ranges
     .into_iter()
@@ -73,7 +75,7 @@ fn output_ranges(ranges: Vec<(u64, u64)>) -> Vec<Range> {
         end,
         name: Sat(start).name(),
         offset,
-        rarity: Sat(start).rarity(),
+        rarity: Sat(start).rarity(network),
         size,
         start,
       };
@@ -92,11 +94,14 @@ mod tests {
   #[test]
   fn list_ranges() {
     assert_eq!(
-      output_ranges(vec![
-        (50 * COIN_VALUE, 55 * COIN_VALUE),
-        (10, 100),
-        (1050000000000000, 1150000000000000),
-      ]),
+      output_ranges(
+        vec![
+          (50 * COIN_VALUE, 55 * COIN_VALUE),
+          (10, 100),
+          (1050000000000000, 1150000000000000),
+        ],
+        Network::Bellscoin
+      ),
       vec![
         Range {
           end: 55 * COIN_VALUE,
@@ -2,26 +2,38 @@ use {
   self::{
     accept_encoding::AcceptEncoding,
     accept_json::AcceptJson,
+    bounded_cache::BoundedCache,
+    byte_range::ByteRange,
+    decompression::DecompressionCache,
     error::{OptionExt, ServerError, ServerResult},
+    event_filter::{EventFilter, EventQuery},
+    openapi::route,
+    thumbnail::{ThumbnailCache, ThumbnailQuery},
+    webhook::Webhook,
   },
   super::*,
+  crate::index::event::Event,
   crate::templates::{
-    rune::RuneOutputJson, AddressHtml, BlockHtml, BlocksHtml, ChildrenHtml, CollectionsHtml,
-    HomeHtml, InputHtml, InscriptionHtml, InscriptionsBlockHtml, InscriptionsHtml, OutputHtml,
-    PageContent, PageHtml, ParentsHtml, PreviewAudioHtml, PreviewCodeHtml, PreviewFontHtml,
-    PreviewImageHtml, PreviewMarkdownHtml, PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml,
-    PreviewUnknownHtml, PreviewVideoHtml, RareTxt, RuneHtml, RunesHtml, SatHtml, TransactionHtml,
+    rune::RuneOutputJson, AddressHistoryHtml, AddressHtml, BlockHtml, BlocksHtml, ChildrenHtml,
+    CollectionsHtml, HomeHtml, InputHtml, InscriptionHistoryHtml, InscriptionHtml,
+    InscriptionsBlockHtml, InscriptionsHashHtml, InscriptionsHtml, InscriptionsSearchHtml,
+    OutputHtml, PageContent, PageHtml, ParentsHtml, PreviewAudioHtml, PreviewCodeHtml,
+    PreviewFontHtml, PreviewImageHtml, PreviewMarkdownHtml, PreviewModelHtml, PreviewPdfHtml,
+    PreviewTextHtml, PreviewUnknownHtml, PreviewVideoHtml, RareTxt, RuneHoldersHtml, RuneHtml,
+    RunesHtml, SatHtml, TransactionHtml,
   },
   axum::{
     body,
     extract::{DefaultBodyLimit, Extension, Json, Path, Query},
     http::{header, HeaderValue, StatusCode, Uri},
-    response::{IntoResponse, Redirect, Response},
+    response::{
+      sse::{self, KeepAlive, Sse},
+      IntoResponse, Redirect, Response,
+    },
     routing::{get, post},
     Router,
   },
   axum_server::Handle,
-  brotli::Decompressor,
   rust_embed::RustEmbed,
   rustls_acme::{
     acme::{LETS_ENCRYPT_PRODUCTION_DIRECTORY, LETS_ENCRYPT_STAGING_DIRECTORY},
@@ -30,9 +42,13 @@ use {
     AcmeConfig,
   },
   std::{str, sync::Arc},
+  tokio::sync::{broadcast, mpsc},
   tokio_stream::StreamExt,
   tower_http::{
-    compression::CompressionLayer,
+    compression::{
+      predicate::{DefaultPredicate, NotForContentType, Predicate},
+      CompressionLayer,
+    },
     cors::{Any, CorsLayer},
     set_header::SetResponseHeaderLayer,
     validate_request::ValidateRequestHeaderLayer,
@@ -43,9 +59,17 @@ pub(crate) use server_config::ServerConfig;
 
 mod accept_encoding;
 mod accept_json;
+mod bounded_cache;
+mod byte_range;
+mod decompression;
 mod error;
+mod event_filter;
+mod graphql;
+mod openapi;
 pub mod query;
 mod server_config;
+mod thumbnail;
+mod webhook;
 
 enum SpawnConfig {
   Https(AxumAcceptor),
@@ -58,6 +82,19 @@ struct Search {
   query: String,
 }
 
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct EventLogQuery {
+  from_height: u32,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RuneActivityQuery {
+  from: Option<u32>,
+  to: Option<u32>,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -81,11 +118,30 @@ pub struct Server {
   pub(crate) csp_origin: Option<String>,
   #[arg(
     long,
-    help = "Decompress encoded content. Currently only supports brotli. Be careful using this on production instances. A decompressed inscription may be arbitrarily large, making decompression a DoS vector."
+    help = "Decompress content encoded with brotli, deflate, gzip or zstd for clients that do not accept the encoding. Output is limited by `--decompress-max-size` and `--decompress-max-ratio`."
   )]
   pub(crate) decompress: bool,
+  #[arg(
+    long,
+    default_value = "16777216",
+    help = "Do not decompress content to more than <DECOMPRESS_MAX_SIZE> bytes."
+  )]
+  pub(crate) decompress_max_size: usize,
+  #[arg(
+    long,
+    default_value = "100",
+    help = "Do not decompress content to more than <DECOMPRESS_MAX_RATIO> times its encoded size."
+  )]
+  pub(crate) decompress_max_ratio: usize,
   #[arg(long, help = "Disable JSON API.")]
   pub(crate) disable_json_api: bool,
+  #[arg(
+    long,
+    help = "Publish inscription and rune events as they are indexed at `/events/stream`."
+  )]
+  pub(crate) events: bool,
+  #[arg(long, help = "Serve a GraphQL API over the index at `/graphql`.")]
+  pub(crate) graphql: bool,
   #[arg(
     long,
     help = "Listen on <HTTP_PORT> for incoming HTTP requests. [default: 3333]"
@@ -120,14 +176,65 @@ pub struct Server {
     help = "Poll Bitcoin Core every <POLLING_INTERVAL>."
   )]
   pub(crate) polling_interval: humantime::Duration,
+  #[arg(
+    long,
+    help = "POST a JSON batch of events to <WEBHOOK> for each indexed block. Delivery resumes after restarts unless <WEBHOOK> changes. Requires an index built with `--index-events`."
+  )]
+  pub(crate) webhook: Option<Url>,
+  #[arg(
+    long,
+    requires = "webhook",
+    help = "Sign webhook payloads with an HMAC-SHA256 of <WEBHOOK_SECRET>, sent in the `X-Ord-Signature` header."
+  )]
+  pub(crate) webhook_secret: Option<String>,
 }
 
 impl Server {
-  pub fn run(self, settings: Settings, index: Arc<Index>, handle: Handle) -> SubcommandResult {
+  const EVENT_BUFFER_SIZE: usize = 16_384;
+  const EVENT_PAGE_SIZE: usize = 1000;
+  const RUNE_ACTIVITY_PAGE_SIZE: usize = 1000;
+  const RUNE_CHART_BLOCKS: u32 = 1000;
+
+  pub(crate) fn event_channel(
+    &self,
+  ) -> (Option<mpsc::Sender<Event>>, Option<mpsc::Receiver<Event>>) {
+    if self.events {
+      let (sender, receiver) = mpsc::channel(Self::EVENT_BUFFER_SIZE);
+      (Some(sender), Some(receiver))
+    } else {
+      (None, None)
+    }
+  }
+
+  pub fn run(
+    self,
+    settings: Settings,
+    index: Arc<Index>,
+    handle: Handle,
+    event_receiver: Option<mpsc::Receiver<Event>>,
+  ) -> SubcommandResult {
     Runtime::new()?.block_on(async {
       let index_clone = index.clone();
       let integration_test = settings.integration_test();
 
+      if let Some(url) = self.webhook.clone() {
+        ensure!(
+          index.has_event_index(),
+          "webhook requires index created with `--index-events` flag"
+        );
+
+        let webhook = Webhook::new(
+          url,
+          self.webhook_secret.clone(),
+          settings.data_dir().join("webhook-cursor.json"),
+        )?;
+
+        let index = index.clone();
+        let polling_interval = self.polling_interval.into();
+
+        thread::spawn(move || webhook.run(&index, polling_interval));
+      }
+
       let index_thread = thread::spawn(move || loop {
         if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
           break;
@@ -148,124 +255,52 @@ impl Server {
 
       INDEXER.lock().unwrap().replace(index_thread);
 
+      // The updater blocks when its event channel is full, so events are
+      // always drained here and rebroadcast to however many subscribers exist.
+      let event_sender = event_receiver.map(|mut event_receiver| {
+        let (event_sender, _) = broadcast::channel(Self::EVENT_BUFFER_SIZE);
+
+        let forwarder = event_sender.clone();
+        tokio::spawn(async move {
+          while let Some(event) = event_receiver.recv().await {
+            forwarder.send(event).ok();
+          }
+        });
+
+        event_sender
+      });
+
       let settings = Arc::new(settings);
       let acme_domains = self.acme_domains()?;
 
+      let routes = Self::routes();
+
+      let openapi = openapi::spec(&routes);
+
       let server_config = Arc::new(ServerConfig {
         chain: settings.chain(),
         csp_origin: self.csp_origin.clone(),
         decompress: self.decompress,
+        decompress_max_ratio: self.decompress_max_ratio,
+        decompress_max_size: self.decompress_max_size,
+        decompression_cache: default(),
         domain: acme_domains.first().cloned(),
+        event_sender,
+        graphql: self
+          .graphql
+          .then(|| graphql::schema(index.clone(), settings.chain())),
         index_sats: index.has_sat_index(),
         json_api_enabled: !self.disable_json_api,
+        openapi,
         proxy: self.proxy.clone(),
+        thumbnail_cache: default(),
       });
 
-      let router = Router::new()
-        .route("/", get(Self::home))
-        .route("/address/:address", get(Self::address))
-        .route("/address/:address/runes", get(Self::address_runes))
-        .route("/block/:query", get(Self::block))
-        .route("/blockcount", get(Self::block_count))
-        .route("/blockhash", get(Self::block_hash))
-        .route("/blockhash/:height", get(Self::block_hash_from_height))
-        .route("/blockheight", get(Self::block_height))
-        .route("/blocks", get(Self::blocks))
-        .route("/blocktime", get(Self::block_time))
-        .route("/bounties", get(Self::bounties))
-        .route("/children/:inscription_id", get(Self::children))
-        .route(
-          "/children/:inscription_id/:page",
-          get(Self::children_paginated),
-        )
-        .route("/collections", get(Self::collections))
-        .route("/collections/:page", get(Self::collections_paginated))
-        .route("/content/:inscription_id", get(Self::content))
-        .route("/faq", get(Self::faq))
-        .route("/favicon.ico", get(Self::favicon))
-        .route("/feed.xml", get(Self::feed))
-        .route("/input/:block/:transaction/:input", get(Self::input))
-        .route("/inscription/:inscription_query", get(Self::inscription))
-        .route(
-          "/inscription/:inscription_query/:child",
-          get(Self::inscription_child),
-        )
-        .route("/inscriptions", get(Self::inscriptions))
-        .route("/inscriptions", post(Self::inscriptions_json))
-        .route("/inscriptions/:page", get(Self::inscriptions_paginated))
-        .route(
-          "/inscriptions/block/:height",
-          get(Self::inscriptions_in_block),
-        )
-        .route(
-          "/inscriptions/block/:height/:page",
-          get(Self::inscriptions_in_block_paginated),
-        )
-        .route("/install.sh", get(Self::install_script))
-        .route("/ordinal/:sat", get(Self::ordinal))
-        .route("/output/:output", get(Self::output))
-        .route("/outputs", post(Self::outputs))
-        .route("/parents/:inscription_id", get(Self::parents))
-        .route(
-          "/parents/:inscription_id/:page",
-          get(Self::parents_paginated),
-        )
-        .route("/preview/:inscription_id", get(Self::preview))
-        .route("/r/blockhash", get(Self::block_hash_json))
-        .route(
-          "/r/blockhash/:height",
-          get(Self::block_hash_from_height_json),
-        )
-        .route("/r/blockheight", get(Self::block_height))
-        .route("/r/blocktime", get(Self::block_time))
-        .route("/r/blockinfo/:query", get(Self::block_info))
-        .route(
-          "/r/inscription/:inscription_id",
-          get(Self::inscription_recursive),
-        )
-        .route("/r/children/:inscription_id", get(Self::children_recursive))
-        .route(
-          "/r/children/:inscription_id/:page",
-          get(Self::children_recursive_paginated),
-        )
-        .route(
-          "/r/children/:inscription_id/inscriptions",
-          get(Self::child_inscriptions_recursive),
-        )
-        .route(
-          "/r/children/:inscription_id/inscriptions/:page",
-          get(Self::child_inscriptions_recursive_paginated),
-        )
-        .route("/r/metadata/:inscription_id", get(Self::metadata))
-        .route("/r/parents/:inscription_id", get(Self::parents_recursive))
-        .route(
-          "/r/parents/:inscription_id/:page",
-          get(Self::parents_recursive_paginated),
-        )
-        .route("/r/sat/:sat_number", get(Self::sat_inscriptions))
-        .route(
-          "/r/sat/:sat_number/:page",
-          get(Self::sat_inscriptions_paginated),
-        )
-        .route(
-          "/r/sat/:sat_number/at/:index",
-          get(Self::sat_inscription_at_index),
-        )
-        .route("/rare.txt", get(Self::rare_txt))
-        .route("/rune/:rune", get(Self::rune))
-        .route("/runes", get(Self::runes))
-        .route("/runes/:page", get(Self::runes_paginated))
-        // .route("/runes/balances", get(Self::runes_balances))
-        .route("/runes_on_output/:output", get(Self::runes_by_output))
-        .route("/sat/:sat", get(Self::sat))
-        .route("/satpoint/:satpoint", get(Self::satpoint))
-        .route("/search", get(Self::search_by_query))
-        .route("/search/*query", get(Self::search_by_path))
-        .route("/static/*path", get(Self::static_asset))
-        .route("/status", get(Self::status))
-        .route("/tx/:txid", get(Self::transaction))
-        .route("/decode/:txid", get(Self::decode))
-        .route("/update", get(Self::update))
+      let router = routes
+        .into_iter()
+        .fold(Router::new(), |router, route| {
+          router.route(route.path, route.handler)
+        })
         .fallback(Self::fallback)
         .layer(Extension(index))
         .layer(Extension(server_config.clone()))
@@ -283,7 +318,22 @@ impl Server {
             .allow_methods([http::Method::GET])
             .allow_origin(Any),
         )
-        .layer(CompressionLayer::new())
+        .layer(
+          CompressionLayer::new().compress_when(
+            DefaultPredicate::new()
+              .and(NotForContentType::const_new("text/event-stream"))
+              .and(
+                // a strong `ETag` identifies the exact bytes of a response,
+                // so responses carrying one are never re-encoded
+                |status: StatusCode,
+                 _: http::Version,
+                 headers: &HeaderMap,
+                 _: &http::Extensions| {
+                  status != StatusCode::PARTIAL_CONTENT && !headers.contains_key(header::ETAG)
+                },
+              ),
+          ),
+        )
         .with_state(server_config.clone());
 
       let router = if server_config.json_api_enabled {
@@ -422,6 +472,276 @@ impl Server {
     }
   }
 
+  /// Every route served, from which both the router and the OpenAPI spec are
+  /// built.
+  fn routes() -> Vec<openapi::Route> {
+    vec![
+      route!(get "/", Self::home),
+      route!(
+        get "/address/:address",
+        Self::address,
+        "Outputs, inscriptions and balances of an address",
+        api::AddressInfo
+      ),
+      route!(
+        get "/address/:address/history",
+        Self::address_history,
+        "Transactions of an address",
+        api::AddressHistory
+      ),
+      route!(
+        get "/address/:address/history/:page",
+        Self::address_history_paginated,
+        "Page of transactions of an address",
+        api::AddressHistory
+      ),
+      route!(
+        get "/address/:address/runes",
+        Self::address_runes,
+        "Rune balances of an address",
+        api::AddressRunes
+      ),
+      route!(get "/block/:query", Self::block, "Block by height or hash", api::Block),
+      route!(get "/blockcount", Self::block_count),
+      route!(get "/blockhash", Self::block_hash),
+      route!(get "/blockhash/:height", Self::block_hash_from_height),
+      route!(get "/blockheight", Self::block_height),
+      route!(get "/blocks", Self::blocks, "Latest blocks", api::Blocks),
+      route!(get "/blocktime", Self::block_time),
+      route!(get "/bounties", Self::bounties),
+      route!(get "/children/:inscription_id", Self::children),
+      route!(get "/children/:inscription_id/:page", Self::children_paginated),
+      route!(get "/collections", Self::collections),
+      route!(get "/collections/:page", Self::collections_paginated),
+      route!(get "/content/:inscription_id", Self::content),
+      route!(get "/faq", Self::faq),
+      route!(get "/favicon.ico", Self::favicon),
+      route!(get "/feed.xml", Self::feed),
+      route!(post "/graphql", Self::graphql),
+      route!(get "/input/:block/:transaction/:input", Self::input),
+      route!(
+        get "/inscription/:inscription_query",
+        Self::inscription,
+        "Inscription by id or number",
+        api::Inscription
+      ),
+      route!(
+        get "/inscription/:inscription_query/:child",
+        Self::inscription_child,
+        "Child of an inscription by index",
+        api::Inscription
+      ),
+      route!(
+        get "/inscription/:inscription_query/history",
+        Self::inscription_history,
+        "Transfers of an inscription",
+        api::InscriptionHistory
+      ),
+      route!(
+        get "/inscription/:inscription_query/history/:page",
+        Self::inscription_history_paginated,
+        "Page of transfers of an inscription",
+        api::InscriptionHistory
+      ),
+      route!(get "/inscriptions", Self::inscriptions, "Latest inscriptions", api::Inscriptions),
+      route!(
+        post "/inscriptions",
+        Self::inscriptions_json,
+        "Inscriptions by id",
+        Vec<InscriptionId> => Vec<api::Inscription>
+      ),
+      route!(
+        get "/inscriptions/:page",
+        Self::inscriptions_paginated,
+        "Page of latest inscriptions",
+        api::Inscriptions
+      ),
+      route!(
+        get "/inscriptions/block/:height",
+        Self::inscriptions_in_block,
+        "Inscriptions in a block",
+        api::Inscriptions
+      ),
+      route!(
+        get "/inscriptions/block/:height/:page",
+        Self::inscriptions_in_block_paginated,
+        "Page of inscriptions in a block",
+        api::Inscriptions
+      ),
+      route!(
+        get "/inscriptions/hash/:sha256",
+        Self::inscriptions_with_hash,
+        "Inscriptions with a body SHA-256 digest",
+        api::Inscriptions
+      ),
+      route!(
+        get "/inscriptions/hash/:sha256/:page",
+        Self::inscriptions_with_hash_paginated,
+        "Page of inscriptions with a body SHA-256 digest",
+        api::Inscriptions
+      ),
+      route!(
+        get "/inscriptions/search",
+        Self::inscriptions_search,
+        "Inscriptions matching a text query",
+        api::Inscriptions
+      ),
+      route!(
+        get "/inscriptions/search/:page",
+        Self::inscriptions_search_paginated,
+        "Page of inscriptions matching a text query",
+        api::Inscriptions
+      ),
+      route!(get "/install.sh", Self::install_script),
+      route!(get "/openapi.json", Self::openapi),
+      route!(get "/ordinal/:sat", Self::ordinal),
+      route!(get "/output/:output", Self::output, "Output by outpoint", api::Output),
+      route!(
+        post "/outputs",
+        Self::outputs,
+        "Outputs by outpoint",
+        Vec<String> => Vec<api::Output>
+      ),
+      route!(get "/parents/:inscription_id", Self::parents),
+      route!(get "/parents/:inscription_id/:page", Self::parents_paginated),
+      route!(get "/preview/:inscription_id", Self::preview),
+      route!(get "/r/blockhash", Self::block_hash_json, "Latest block hash", String),
+      route!(
+        get "/r/blockhash/:height",
+        Self::block_hash_from_height_json,
+        "Block hash at a height",
+        String
+      ),
+      route!(get "/r/blockheight", Self::block_height),
+      route!(get "/r/blocktime", Self::block_time),
+      route!(
+        get "/r/blockinfo/:query",
+        Self::block_info,
+        "Block header and statistics",
+        api::BlockInfo
+      ),
+      route!(
+        get "/r/inscription/:inscription_id",
+        Self::inscription_recursive,
+        "Inscription",
+        api::InscriptionRecursive
+      ),
+      route!(
+        get "/r/inscription/:inscription_id/history",
+        Self::inscription_history_recursive,
+        "Transfers of an inscription",
+        api::InscriptionHistory
+      ),
+      route!(
+        get "/r/inscription/:inscription_id/history/:page",
+        Self::inscription_history_recursive_paginated,
+        "Page of transfers of an inscription",
+        api::InscriptionHistory
+      ),
+      route!(
+        get "/r/children/:inscription_id",
+        Self::children_recursive,
+        "Child inscription ids",
+        api::Children
+      ),
+      route!(
+        get "/r/children/:inscription_id/:page",
+        Self::children_recursive_paginated,
+        "Page of child inscription ids",
+        api::Children
+      ),
+      route!(
+        get "/r/children/:inscription_id/inscriptions",
+        Self::child_inscriptions_recursive,
+        "Child inscriptions",
+        api::ChildInscriptions
+      ),
+      route!(
+        get "/r/children/:inscription_id/inscriptions/:page",
+        Self::child_inscriptions_recursive_paginated,
+        "Page of child inscriptions",
+        api::ChildInscriptions
+      ),
+      route!(
+        get "/r/metadata/:inscription_id",
+        Self::metadata,
+        "Hex-encoded CBOR metadata of an inscription",
+        String
+      ),
+      route!(
+        get "/r/parents/:inscription_id",
+        Self::parents_recursive,
+        "Parent inscription ids",
+        api::Inscriptions
+      ),
+      route!(
+        get "/r/parents/:inscription_id/:page",
+        Self::parents_recursive_paginated,
+        "Page of parent inscription ids",
+        api::Inscriptions
+      ),
+      route!(
+        get "/r/sat/:sat_number",
+        Self::sat_inscriptions,
+        "Inscriptions on a sat",
+        api::SatInscriptions
+      ),
+      route!(
+        get "/r/sat/:sat_number/:page",
+        Self::sat_inscriptions_paginated,
+        "Page of inscriptions on a sat",
+        api::SatInscriptions
+      ),
+      route!(
+        get "/r/sat/:sat_number/at/:index",
+        Self::sat_inscription_at_index,
+        "Inscription on a sat at an index",
+        api::SatInscription
+      ),
+      route!(get "/rare.txt", Self::rare_txt),
+      route!(get "/rune/:rune", Self::rune, "Rune by name or id", api::Rune),
+      route!(
+        get "/rune/:rune/activity",
+        Self::rune_activity,
+        "Per-block activity of a rune",
+        api::RuneActivity
+      ),
+      route!(get "/rune/:rune/holders", Self::rune_holders, "Holders of a rune", api::RuneHolders),
+      route!(
+        get "/rune/:rune/holders/:page",
+        Self::rune_holders_paginated,
+        "Page of holders of a rune",
+        api::RuneHolders
+      ),
+      route!(get "/runes", Self::runes, "Latest runes", api::Runes),
+      route!(get "/runes/:page", Self::runes_paginated, "Page of latest runes", api::Runes),
+      // route!(get "/runes/balances", Self::runes_balances),
+      route!(
+        get "/runes_on_output/:output",
+        Self::runes_by_output,
+        "Rune balances of an output",
+        Vec<RuneOutputJson>
+      ),
+      route!(get "/sat/:sat", Self::sat, "Sat", api::Sat),
+      route!(get "/satpoint/:satpoint", Self::satpoint),
+      route!(get "/search", Self::search_by_query),
+      route!(get "/search/*query", Self::search_by_path),
+      route!(get "/static/*path", Self::static_asset),
+      route!(get "/status", Self::status, "Server and index status", api::Status),
+      route!(get "/thumbnail/:inscription_id", Self::thumbnail),
+      route!(get "/tx/:txid", Self::transaction, "Transaction", api::Transaction),
+      route!(
+        get "/decode/:txid",
+        Self::decode,
+        "Inscriptions and runestone decoded from a transaction",
+        api::Decode
+      ),
+      route!(get "/events", Self::events, "Indexed events", api::Events),
+      route!(get "/events/stream", Self::events_stream),
+      route!(get "/update", Self::update),
+    ]
+  }
+
   fn acme_domains(&self) -> Result<Vec<String>> {
     if !self.acme_domain.is_empty() {
       Ok(self.acme_domain.clone())
@@ -515,10 +835,13 @@ impl Server {
   async fn sat(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<Sat>>,
+    Path(sat): Path<String>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
+      let sat = Sat::parse(&sat, server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(format!("Invalid URL: {err}")))?;
+
       let inscriptions = index.get_inscription_ids_by_sat(sat)?;
       let satpoint = index.rare_sat_satpoint(sat)?.or_else(|| {
         inscriptions.first().and_then(|&first_inscription_id| {
@@ -528,20 +851,20 @@ impl Server {
             .flatten()
         })
       });
-      let blocktime = index.block_time(sat.height())?;
+      let blocktime = index.block_time(sat.height(server_config.chain.network()))?;
 
-      let charms = sat.charms();
+      let charms = sat.charms(server_config.chain.network());
 
       Ok(if accept_json {
         Json(api::Sat {
           number: sat.0,
           decimal: sat.decimal(server_config.chain.network()).to_string(),
           name: sat.name(),
-          block: sat.height().0,
+          block: sat.height(server_config.chain.network()).0,
           cycle: sat.cycle(),
           epoch: sat.epoch().0,
           offset: sat.third(server_config.chain.network()),
-          rarity: sat.rarity(),
+          rarity: sat.rarity(server_config.chain.network()),
           percentile: sat.percentile(),
           satpoint,
           timestamp: blocktime.timestamp().timestamp(),
@@ -665,15 +988,7 @@ impl Server {
         ));
       }
 
-      let rune = match rune_query {
-        query::Rune::Spaced(spaced_rune) => spaced_rune.rune,
-        query::Rune::Id(rune_id) => index
-          .get_rune_by_id(rune_id)?
-          .ok_or_not_found(|| format!("rune {rune_id}"))?,
-        query::Rune::Number(number) => index
-          .get_rune_by_number(usize::try_from(number).unwrap())?
-          .ok_or_not_found(|| format!("rune number {number}"))?,
-      };
+      let rune = Self::resolve_rune(&index, rune_query)?;
 
       let (id, entry, parent) = index
         .rune(rune)?
@@ -692,7 +1007,20 @@ impl Server {
         })
         .into_response()
       } else {
+        let activity_start = u32::try_from(entry.block)
+          .unwrap()
+          .max((block_height.n() + 1).saturating_sub(Self::RUNE_CHART_BLOCKS));
+
+        let (activity, _more) = index.get_rune_activity(
+          id,
+          activity_start,
+          block_height.n(),
+          Self::RUNE_CHART_BLOCKS.try_into().unwrap(),
+        )?;
+
         RuneHtml {
+          activity,
+          activity_start,
           entry,
           id,
           mintable,
@@ -704,6 +1032,124 @@ impl Server {
     })
   }
 
+  fn resolve_rune(index: &Index, rune_query: query::Rune) -> ServerResult<Rune> {
+    Ok(match rune_query {
+      query::Rune::Spaced(spaced_rune) => spaced_rune.rune,
+      query::Rune::Id(rune_id) => index
+        .get_rune_by_id(rune_id)?
+        .ok_or_not_found(|| format!("rune {rune_id}"))?,
+      query::Rune::Number(number) => index
+        .get_rune_by_number(usize::try_from(number).unwrap())?
+        .ok_or_not_found(|| format!("rune number {number}"))?,
+    })
+  }
+
+  async fn rune_activity(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+    Query(query): Query<RuneActivityQuery>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let rune = Self::resolve_rune(&index, rune_query)?;
+
+      let (id, entry, _parent) = index
+        .rune(rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?;
+
+      let from = query.from.unwrap_or_default();
+      let to = query.to.unwrap_or(u32::MAX);
+
+      if from > to {
+        return Err(ServerError::BadRequest(format!(
+          "`from` height {from} is greater than `to` height {to}"
+        )));
+      }
+
+      let (activity, more) =
+        index.get_rune_activity(id, from, to, Self::RUNE_ACTIVITY_PAGE_SIZE)?;
+
+      let next_height = more.then(|| activity.last().unwrap().height + 1);
+
+      Ok(
+        Json(api::RuneActivity {
+          activity,
+          id,
+          more,
+          next_height,
+          rune: entry.spaced_rune,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  async fn rune_holders(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(rune_query): Path<DeserializeFromStr<query::Rune>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::rune_holders_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((rune_query, 0)),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn rune_holders_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((DeserializeFromStr(rune_query), page_index)): Path<(
+      DeserializeFromStr<query::Rune>,
+      usize,
+    )>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let rune = Self::resolve_rune(&index, rune_query)?;
+
+      let (id, entry, _parent) = index
+        .rune(rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?;
+
+      let (holders, outputs, addresses, more) = index.get_rune_holders(id, 100, page_index)?;
+
+      let holders = RuneHoldersHtml {
+        addresses,
+        holders: holders
+          .into_iter()
+          .map(|(holder, amount)| (holder, entry.pile(amount)))
+          .collect(),
+        id,
+        more,
+        next: more.then_some(page_index + 1),
+        outputs,
+        prev: page_index.checked_sub(1),
+        rune: entry.spaced_rune,
+      };
+
+      Ok(if accept_json {
+        Json(holders).into_response()
+      } else {
+        holders.page(server_config).into_response()
+      })
+    })
+  }
+
   async fn runes(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -885,6 +1331,60 @@ impl Server {
     })
   }
 
+  async fn address_history(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::address_history_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((address, 0)),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn address_history_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((address, page)): Path<(Address<NetworkUnchecked>, usize)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_address_history_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address history index".to_string(),
+        ));
+      }
+
+      let address = address
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let (transactions, more) = index.get_address_history(&address, 100, page)?;
+
+      Ok(if accept_json {
+        Json(api::AddressHistory {
+          more,
+          page,
+          transactions,
+        })
+        .into_response()
+      } else {
+        AddressHistoryHtml {
+          address,
+          transactions,
+          prev_page: page.checked_sub(1),
+          next_page: more.then_some(page + 1),
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
   async fn address_runes(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -914,7 +1414,7 @@ impl Server {
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (block, height) = match query {
+      let (mut block, height) = match query {
         query::Block::Height(height) => {
           let block = index
             .get_block_by_height(height)?
@@ -935,6 +1435,8 @@ impl Server {
         }
       };
 
+      Self::load_indexed_auxpow(&index, height, &mut block.header)?;
+
       let runes = index.get_runes_in_block(u64::from(height))?;
       Ok(if accept_json {
         let inscriptions = index.get_inscriptions_in_block(height)?;
@@ -1010,11 +1512,14 @@ impl Server {
         .ok_or_not_found(|| format!("transaction {txid}"))?;
 
       let inscriptions = ParsedEnvelope::from_transaction(&transaction);
+      let partial =
+        super::decode::Decode::follow_partial(vec![transaction.clone()], |_outpoint| Ok(None))?;
       let runestone = Runestone::decipher(&transaction);
 
       Ok(if accept_json {
         Json(api::Decode {
           inscriptions,
+          partial,
           runestone,
         })
         .into_response()
@@ -1024,6 +1529,81 @@ impl Server {
     })
   }
 
+  async fn events(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<EventLogQuery>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_event_index() {
+        return Err(ServerError::NotFound(
+          "this server has no event index".to_string(),
+        ));
+      }
+
+      let (events, more, next_height) =
+        index.get_events(query.from_height, Self::EVENT_PAGE_SIZE)?;
+
+      Ok(
+        Json(api::Events {
+          events,
+          more,
+          next_height,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  async fn events_stream(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<EventQuery>,
+  ) -> ServerResult {
+    let event_sender = server_config
+      .event_sender
+      .as_ref()
+      .ok_or_not_found(|| "event stream")?;
+
+    let filter = EventFilter::new(query, server_config.chain)?;
+
+    let stream = futures::stream::unfold(
+      (event_sender.subscribe(), filter, index),
+      |(mut event_receiver, filter, index)| async move {
+        loop {
+          let event = match event_receiver.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+              let lagged = sse::Event::default()
+                .event("lagged")
+                .data(skipped.to_string());
+              return Some((Ok(lagged), (event_receiver, filter, index)));
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+          };
+
+          match task::block_in_place(|| filter.matches(&index, &event)) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+              log::warn!("failed to filter event: {err}");
+              continue;
+            }
+          }
+
+          let event = sse::Event::default().event(event.kind()).json_data(&event);
+
+          return Some((event, (event_receiver, filter, index)));
+        }
+      },
+    );
+
+    Ok(
+      Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response(),
+    )
+  }
+
   async fn update(
     Extension(settings): Extension<Arc<Settings>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1197,21 +1777,46 @@ impl Server {
         Ok(Redirect::to(&format!("/address/{query}")))
       } else if re::SATPOINT.is_match(query) {
         Ok(Redirect::to(&format!("/satpoint/{query}")))
+      } else if index.has_text_index() && query.parse::<Sat>().is_err() {
+        Ok(Redirect::to(&format!(
+          "/inscriptions/search?query={}",
+          urlencoding::encode(query)
+        )))
       } else {
         Ok(Redirect::to(&format!("/sat/{query}")))
       }
     })
   }
 
-  async fn favicon() -> ServerResult {
-    Ok(
-      Self::static_asset(Path("/favicon.png".to_string()))
-        .await
-        .into_response(),
-    )
-  }
-
-  async fn feed(
+  async fn graphql(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Json(request): Json<async_graphql::Request>,
+  ) -> ServerResult {
+    let schema = server_config
+      .graphql
+      .as_ref()
+      .ok_or_not_found(|| "graphql")?;
+
+    task::block_in_place(|| {
+      Ok(Json(futures::executor::block_on(schema.execute(request))).into_response())
+    })
+  }
+
+  async fn openapi(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+  ) -> Json<serde_json::Value> {
+    Json(server_config.openapi.clone())
+  }
+
+  async fn favicon() -> ServerResult {
+    Ok(
+      Self::static_asset(Path("/favicon.png".to_string()))
+        .await
+        .into_response(),
+    )
+  }
+
+  async fn feed(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
   ) -> ServerResult {
@@ -1337,6 +1942,20 @@ impl Server {
     })
   }
 
+  /// Replaces the AuxPoW returned by the node with the one stored by
+  /// `--index-auxpow`, if the index has reached `height`.
+  fn load_indexed_auxpow(
+    index: &Index,
+    height: u32,
+    header: &mut bellscoin::block::Header,
+  ) -> ServerResult<()> {
+    if index.has_auxpow_index() && height < index.block_count()? {
+      header.auxpow = index.block_auxpow(height)?;
+    }
+
+    Ok(())
+  }
+
   async fn block_info(
     Extension(index): Extension<Arc<Index>>,
     Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
@@ -1349,7 +1968,7 @@ impl Server {
           .ok_or_not_found(|| format!("block {height}"))?,
       };
 
-      let header = index
+      let mut header = index
         .block_header(hash)?
         .ok_or_not_found(|| format!("block {hash}"))?;
 
@@ -1357,6 +1976,8 @@ impl Server {
         .block_header_info(hash)?
         .ok_or_not_found(|| format!("block {hash}"))?;
 
+      Self::load_indexed_auxpow(&index, info.height.try_into().unwrap(), &mut header)?;
+
       let stats = index
         .block_stats(info.height.try_into().unwrap())?
         .ok_or_not_found(|| format!("block {hash}"))?;
@@ -1364,6 +1985,7 @@ impl Server {
       Ok(Json(api::BlockInfo {
         average_fee: stats.avg_fee.to_sat(),
         average_fee_rate: stats.avg_fee_rate.to_sat(),
+        auxpow: header.auxpow.clone().map(api::AuxPow::new),
         bits: header.bits.to_consensus(),
         chainwork: info.chainwork.try_into().unwrap(),
         confirmations: info.confirmations,
@@ -1482,6 +2104,7 @@ impl Server {
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Path(inscription_id): Path<InscriptionId>,
     accept_encoding: AcceptEncoding,
+    request_headers: HeaderMap,
   ) -> ServerResult {
     task::block_in_place(|| {
       if settings.is_hidden(inscription_id) {
@@ -1499,21 +2122,94 @@ impl Server {
         };
       };
 
+      let mut content_id = inscription_id;
+
       if let Some(delegate) = inscription.delegate() {
         inscription = index
           .get_inscription_by_id(delegate)?
-          .ok_or_not_found(|| format!("delegate {inscription_id}"))?
+          .ok_or_not_found(|| format!("delegate {inscription_id}"))?;
+        content_id = delegate;
       }
 
-      Ok(
-        Self::content_response(inscription, accept_encoding, &server_config)?
-          .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
-          .into_response(),
-      )
+      let encoded = inscription.content_encoding().is_some();
+
+      let (headers, body) =
+        Self::content_response(content_id, inscription, accept_encoding, &server_config)?
+          .ok_or_not_found(|| format!("inscription {inscription_id} content"))?;
+
+      let etag = if encoded && !headers.contains_key(header::CONTENT_ENCODING) {
+        format!("\"{inscription_id}-decompressed\"")
+      } else {
+        format!("\"{inscription_id}\"")
+      };
+
+      Self::conditional_response(&request_headers, headers, body, &etag)
     })
   }
 
+  /// Adds an `ETag` to a content response and answers `If-None-Match`,
+  /// `If-Range` and single byte `Range` requests against it.
+  fn conditional_response(
+    request_headers: &HeaderMap,
+    mut headers: HeaderMap,
+    mut body: Vec<u8>,
+    etag: &str,
+  ) -> ServerResult {
+    let etag = HeaderValue::from_str(etag).map_err(|err| ServerError::Internal(err.into()))?;
+
+    headers.insert(header::ETAG, etag.clone());
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    let not_modified = request_headers
+      .get_all(header::IF_NONE_MATCH)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .flat_map(|value| value.split(','))
+      .map(|tag| tag.trim())
+      .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+
+    if not_modified {
+      let mut not_modified_headers = HeaderMap::new();
+      not_modified_headers.insert(header::ETAG, etag);
+      if let Some(cache_control) = headers.remove(header::CACHE_CONTROL) {
+        not_modified_headers.insert(header::CACHE_CONTROL, cache_control);
+      }
+      return Ok((StatusCode::NOT_MODIFIED, not_modified_headers).into_response());
+    }
+
+    let range = match request_headers.get(header::IF_RANGE) {
+      Some(if_range) if *if_range != etag => None,
+      _ => request_headers
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok()),
+    };
+
+    let len = body.len();
+
+    match ByteRange::new(range, len) {
+      ByteRange::Full => Ok((headers, body).into_response()),
+      ByteRange::Partial { start, end } => {
+        headers.insert(
+          header::CONTENT_RANGE,
+          HeaderValue::from_str(&format!("bytes {start}-{end}/{len}"))
+            .map_err(|err| ServerError::Internal(err.into()))?,
+        );
+        body.truncate(end + 1);
+        body.drain(..start);
+        Ok((StatusCode::PARTIAL_CONTENT, headers, body).into_response())
+      }
+      ByteRange::Unsatisfiable => Ok(
+        (
+          StatusCode::RANGE_NOT_SATISFIABLE,
+          [(header::CONTENT_RANGE, format!("bytes */{len}"))],
+        )
+          .into_response(),
+      ),
+    }
+  }
+
   fn content_response(
+    inscription_id: InscriptionId,
     inscription: Inscription,
     accept_encoding: AcceptEncoding,
     server_config: &ServerConfig,
@@ -1556,18 +2252,36 @@ impl Server {
     if let Some(content_encoding) = inscription.content_encoding() {
       if accept_encoding.is_acceptable(&content_encoding) {
         headers.insert(header::CONTENT_ENCODING, content_encoding);
-      } else if server_config.decompress && content_encoding == "br" {
-        let Some(body) = inscription.into_body() else {
+      } else if let Some(encoding) =
+        decompression::supported_encoding(&content_encoding).filter(|_| server_config.decompress)
+      {
+        if let Some(decompressed) = server_config.decompression_cache.get(inscription_id) {
+          return Ok(Some((headers, decompressed.to_vec())));
+        }
+
+        let Some(body) = inscription.body() else {
           return Ok(None);
         };
 
-        let mut decompressed = Vec::new();
+        if let Some(decompressed) = decompression::decompress(
+          encoding,
+          body,
+          server_config.decompress_max_size,
+          server_config.decompress_max_ratio,
+        )
+        .map_err(ServerError::Internal)?
+        {
+          server_config
+            .decompression_cache
+            .insert(inscription_id, Arc::new(decompressed.clone()));
 
-        Decompressor::new(body.as_slice(), 4096)
-          .read_to_end(&mut decompressed)
-          .map_err(|err| ServerError::Internal(err.into()))?;
+          return Ok(Some((headers, decompressed)));
+        }
 
-        return Ok(Some((headers, decompressed)));
+        return Err(ServerError::NotAcceptable {
+          accept_encoding,
+          content_encoding,
+        });
       } else {
         return Err(ServerError::NotAcceptable {
           accept_encoding,
@@ -1599,17 +2313,20 @@ impl Server {
         .get_inscription_by_id(inscription_id)?
         .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
+      let mut content_id = inscription_id;
+
       if let Some(delegate) = inscription.delegate() {
         inscription = index
           .get_inscription_by_id(delegate)?
-          .ok_or_not_found(|| format!("delegate {inscription_id}"))?
+          .ok_or_not_found(|| format!("delegate {inscription_id}"))?;
+        content_id = delegate;
       }
 
       let media = inscription.media();
 
       if let Media::Iframe = media {
         return Ok(
-          Self::content_response(inscription, accept_encoding, &server_config)?
+          Self::content_response(content_id, inscription, accept_encoding, &server_config)?
             .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
             .into_response(),
         );
@@ -1669,6 +2386,97 @@ impl Server {
     })
   }
 
+  async fn thumbnail(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(settings): Extension<Arc<Settings>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path(inscription_id): Path<InscriptionId>,
+    Query(query): Query<ThumbnailQuery>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let Some(size) = thumbnail::bucket(query.size.unwrap_or(thumbnail::DEFAULT_SIZE)) else {
+        return Err(ServerError::BadRequest(format!(
+          "thumbnail size must be between 1 and {}",
+          thumbnail::MAX_SIZE
+        )));
+      };
+
+      // placeholders are not cached, since they stand in for hidden
+      // inscriptions and for images that failed to render
+      let placeholder = |media| {
+        (
+          [
+            (
+              header::CONTENT_TYPE,
+              HeaderValue::from_static("image/svg+xml"),
+            ),
+            (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+          ],
+          thumbnail::placeholder(media, size),
+        )
+          .into_response()
+      };
+
+      if settings.is_hidden(inscription_id) {
+        return Ok(placeholder(Media::Unknown));
+      }
+
+      let mut inscription = index
+        .get_inscription_by_id(inscription_id)?
+        .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+      let mut content_id = inscription_id;
+
+      if let Some(delegate) = inscription.delegate() {
+        inscription = index
+          .get_inscription_by_id(delegate)?
+          .ok_or_not_found(|| format!("delegate {inscription_id}"))?;
+        content_id = delegate;
+      }
+
+      let media = inscription.media();
+
+      let (Media::Image(image_rendering), None, Some(body)) =
+        (media, inscription.content_encoding(), inscription.body())
+      else {
+        return Ok(placeholder(media));
+      };
+
+      let dir = settings.data_dir().join("thumbnails");
+
+      let cache = &server_config.thumbnail_cache;
+
+      let png = match cache.get(&dir, content_id, size)? {
+        Some(png) => png,
+        None => {
+          let Ok(png) = cache.render(body, size, image_rendering) else {
+            return Ok(placeholder(media));
+          };
+
+          if let Err(err) = cache.insert(&dir, content_id, size, &png) {
+            log::warn!("failed to cache thumbnail of {content_id}: {err}");
+          }
+
+          png
+        }
+      };
+
+      Ok(
+        (
+          [
+            (header::CONTENT_TYPE, HeaderValue::from_static("image/png")),
+            (
+              header::CACHE_CONTROL,
+              HeaderValue::from_static("public, max-age=1209600, immutable"),
+            ),
+          ],
+          png,
+        )
+          .into_response(),
+      )
+    })
+  }
+
   async fn inscription(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1687,6 +2495,53 @@ impl Server {
     Self::inscription_inner(server_config, &index, accept_json, query, Some(child)).await
   }
 
+  async fn inscription_history(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::inscription_history_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((inscription_id, 0)),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn inscription_history_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((inscription_id, page)): Path<(InscriptionId, usize)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let (transfers, more) = index
+        .get_inscription_history(inscription_id, 100, page)?
+        .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+      Ok(if accept_json {
+        Json(api::InscriptionHistory {
+          id: inscription_id,
+          more,
+          page,
+          transfers,
+        })
+        .into_response()
+      } else {
+        InscriptionHistoryHtml {
+          id: inscription_id,
+          transfers,
+          prev_page: page.checked_sub(1),
+          next_page: more.then_some(page + 1),
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
   async fn inscription_inner(
     server_config: Arc<ServerConfig>,
     index: &Index,
@@ -1715,6 +2570,7 @@ impl Server {
             acc
           })),
           children: info.children,
+          duplicate_of: info.duplicate_of,
           fee: info.fee,
           height: info.height,
           inscription,
@@ -1836,6 +2692,52 @@ impl Server {
     })
   }
 
+  async fn inscription_history_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult {
+    Self::inscription_history_recursive_paginated(
+      Extension(index),
+      Extension(server_config),
+      Path((inscription_id, 0)),
+    )
+    .await
+  }
+
+  async fn inscription_history_recursive_paginated(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path((inscription_id, page)): Path<(InscriptionId, usize)>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let Some((transfers, more)) = index.get_inscription_history(inscription_id, 100, page)?
+      else {
+        return if let Some(proxy) = server_config.proxy.as_ref() {
+          Self::proxy(
+            proxy,
+            &format!("r/inscription/{}/history/{}", inscription_id, page),
+          )
+        } else {
+          Err(ServerError::NotFound(format!(
+            "inscription {} not found",
+            inscription_id
+          )))
+        };
+      };
+
+      Ok(
+        Json(api::InscriptionHistory {
+          id: inscription_id,
+          more,
+          page,
+          transfers,
+        })
+        .into_response(),
+      )
+    })
+  }
+
   async fn children_recursive(
     Extension(index): Extension<Arc<Index>>,
     Extension(server_config): Extension<Arc<ServerConfig>>,
@@ -1937,12 +2839,14 @@ impl Server {
   async fn inscriptions(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
+    filter: Query<InscriptionFilter>,
     accept_json: AcceptJson,
   ) -> ServerResult {
     Self::inscriptions_paginated(
       Extension(server_config),
       Extension(index),
       Path(0),
+      filter,
       accept_json,
     )
     .await
@@ -1952,10 +2856,23 @@ impl Server {
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(page_index): Path<u32>,
+    Query(filter): Query<InscriptionFilter>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (inscriptions, more) = index.get_inscriptions_paginated(100, page_index)?;
+      if filter.rarity.is_some() && !index.has_sat_index() {
+        return Err(ServerError::NotFound("sat index required".into()));
+      }
+
+      if let (Some(from_height), Some(to_height)) = (filter.from_height, filter.to_height) {
+        if from_height > to_height {
+          return Err(ServerError::BadRequest(format!(
+            "`from_height` {from_height} is greater than `to_height` {to_height}"
+          )));
+        }
+      }
+
+      let (inscriptions, more) = index.get_inscriptions_paginated(&filter, 100, page_index)?;
 
       let prev = page_index.checked_sub(1);
 
@@ -1970,6 +2887,7 @@ impl Server {
         .into_response()
       } else {
         InscriptionsHtml {
+          filter,
           inscriptions,
           next,
           prev,
@@ -2041,64 +2959,162 @@ impl Server {
     })
   }
 
-  async fn parents(
+  async fn inscriptions_with_hash(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(inscription_id): Path<InscriptionId>,
-  ) -> ServerResult<Response> {
-    Self::parents_paginated(
+    Path(sha256): Path<DeserializeFromStr<sha256::Hash>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::inscriptions_with_hash_paginated(
       Extension(server_config),
       Extension(index),
-      Path((inscription_id, 0)),
+      Path((sha256, 0)),
+      accept_json,
     )
     .await
   }
 
-  async fn parents_paginated(
+  async fn inscriptions_with_hash_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path((id, page)): Path<(InscriptionId, usize)>,
-  ) -> ServerResult<Response> {
+    Path((DeserializeFromStr(sha256), page_index)): Path<(DeserializeFromStr<sha256::Hash>, u32)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
     task::block_in_place(|| {
-      let child = index
-        .get_inscription_entry(id)?
-        .ok_or_not_found(|| format!("inscription {id}"))?;
-
-      let (parents, more) = index.get_parents_by_sequence_number_paginated(child.parents, page)?;
-
-      let prev_page = page.checked_sub(1);
-
-      let next_page = more.then_some(page + 1);
+      let (inscriptions, more) =
+        index.get_inscriptions_by_sha256(sha256, 100, page_index.into_usize())?;
 
-      Ok(
-        ParentsHtml {
-          id,
-          number: child.inscription_number,
-          parents,
-          prev_page,
-          next_page,
+      Ok(if accept_json {
+        Json(api::Inscriptions {
+          ids: inscriptions,
+          page_index,
+          more,
+        })
+        .into_response()
+      } else {
+        InscriptionsHashHtml {
+          hash: sha256,
+          inscriptions,
+          prev: page_index.into_usize().checked_sub(1),
+          next: more.then_some(page_index.into_usize() + 1),
         }
         .page(server_config)
-        .into_response(),
-      )
+        .into_response()
+      })
     })
   }
 
-  async fn parents_recursive(
+  async fn inscriptions_search(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(inscription_id): Path<InscriptionId>,
+    search: Query<Search>,
+    accept_json: AcceptJson,
   ) -> ServerResult {
-    Self::parents_recursive_paginated(Extension(index), Path((inscription_id, 0))).await
+    Self::inscriptions_search_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path(0),
+      search,
+      accept_json,
+    )
+    .await
   }
 
-  async fn parents_recursive_paginated(
+  async fn inscriptions_search_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path((inscription_id, page)): Path<(InscriptionId, usize)>,
+    Path(page_index): Path<u32>,
+    Query(search): Query<Search>,
+    AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let child = index
-        .get_inscription_entry(inscription_id)?
-        .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+      if !index.has_text_index() {
+        return Err(ServerError::NotFound(
+          "this server has no text index".to_string(),
+        ));
+      }
+
+      let (inscriptions, more) =
+        index.search_inscriptions(&search.query, 100, page_index.into_usize())?;
+
+      Ok(if accept_json {
+        Json(api::Inscriptions {
+          ids: inscriptions,
+          page_index,
+          more,
+        })
+        .into_response()
+      } else {
+        InscriptionsSearchHtml {
+          inscriptions,
+          next: more.then_some(page_index.into_usize() + 1),
+          prev: page_index.into_usize().checked_sub(1),
+          query: search.query,
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
+  async fn parents(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult<Response> {
+    Self::parents_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((inscription_id, 0)),
+    )
+    .await
+  }
+
+  async fn parents_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((id, page)): Path<(InscriptionId, usize)>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      let child = index
+        .get_inscription_entry(id)?
+        .ok_or_not_found(|| format!("inscription {id}"))?;
+
+      let (parents, more) = index.get_parents_by_sequence_number_paginated(child.parents, page)?;
+
+      let prev_page = page.checked_sub(1);
+
+      let next_page = more.then_some(page + 1);
+
+      Ok(
+        ParentsHtml {
+          id,
+          number: child.inscription_number,
+          parents,
+          prev_page,
+          next_page,
+        }
+        .page(server_config)
+        .into_response(),
+      )
+    })
+  }
+
+  async fn parents_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult {
+    Self::parents_recursive_paginated(Extension(index), Path((inscription_id, 0))).await
+  }
+
+  async fn parents_recursive_paginated(
+    Extension(index): Extension<Arc<Index>>,
+    Path((inscription_id, page)): Path<(InscriptionId, usize)>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let child = index
+        .get_inscription_entry(inscription_id)?
+        .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
       let (ids, more) = index.get_parents_by_sequence_number_paginated(child.parents, page)?;
 
@@ -2172,7 +3188,11 @@ impl Server {
 #[cfg(test)]
 mod tests {
   use {
-    super::*, reqwest::Url, serde::de::DeserializeOwned, std::net::TcpListener, tempfile::TempDir,
+    super::*,
+    reqwest::Url,
+    serde::de::DeserializeOwned,
+    std::{io::Write, net::TcpListener},
+    tempfile::TempDir,
   };
 
   const RUNE: u128 = 99246114928149462;
@@ -2304,13 +3324,18 @@ mod tests {
         .or_defaults()
         .unwrap();
 
-      let index = Arc::new(Index::open(&settings).unwrap());
+      let (event_sender, event_receiver) = server.event_channel();
+      let index = Arc::new(Index::open_with_event_sender(&settings, event_sender).unwrap());
       let ord_server_handle = Handle::new();
 
       {
         let index = index.clone();
         let ord_server_handle = ord_server_handle.clone();
-        thread::spawn(|| server.run(settings, index, ord_server_handle).unwrap());
+        thread::spawn(|| {
+          server
+            .run(settings, index, ord_server_handle, event_receiver)
+            .unwrap()
+        });
       }
 
       while index.statistic(crate::index::Statistic::Commits) == 0 {
@@ -2388,7 +3413,7 @@ mod tests {
     ) -> (Txid, RuneId) {
       let block_count = usize::try_from(self.index.block_count().unwrap()).unwrap();
 
-      self.mine_blocks(1);
+      self.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
       self.core.broadcast_tx(TransactionTemplate {
         inputs: &[(block_count, 0, 0, Default::default())],
@@ -2396,7 +3421,7 @@ mod tests {
         ..default()
       });
 
-      self.mine_blocks((Runestone::COMMIT_CONFIRMATIONS - 1).into());
+      self.mine_blocks_with_subsidy((Runestone::COMMIT_CONFIRMATIONS - 1).into(), 2 * COIN_VALUE);
 
       let witness = witness.unwrap_or_else(|| {
         let tapscript = script::Builder::new()
@@ -2425,7 +3450,7 @@ mod tests {
         ..default()
       });
 
-      self.mine_blocks(1);
+      self.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
       (
         txid,
@@ -2949,6 +3974,179 @@ mod tests {
       .assert_response("/runes/balances", StatusCode::NOT_FOUND, "");
   }
 
+  #[test]
+  fn address_history_not_found_without_index() {
+    let address = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    TestServer::builder()
+      .chain(Chain::Regtest)
+      .build()
+      .assert_response(
+        format!("/address/{address}/history"),
+        StatusCode::NOT_FOUND,
+        "this server has no address history index",
+      );
+  }
+
+  #[test]
+  fn address_history() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-address-history")
+      .build();
+
+    let coinbase = server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE)[0].txdata[0].clone();
+
+    let address = Chain::Regtest
+      .address_from_script(&coinbase.output[0].script_pubkey)
+      .unwrap();
+
+    pretty_assert_eq!(
+      server.get_json::<api::AddressHistory>(format!("/address/{address}/history")),
+      api::AddressHistory {
+        more: false,
+        page: 0,
+        transactions: vec![api::AddressTransaction {
+          height: 1,
+          inscriptions: Vec::new(),
+          received: 2 * COIN_VALUE,
+          runes: BTreeMap::new(),
+          sent: 0,
+          txid: coinbase.txid(),
+        }],
+      }
+    );
+
+    server.assert_response_regex(
+      format!("/address/{address}/history"),
+      StatusCode::OK,
+      format!(
+        ".*<h1><a href=/address/{address}>Address {address}</a> History</h1>
+<dl>
+  <dt>transaction</dt><dd><a class=monospace href=/tx/{0}>{0}</a></dd>
+  <dt>block</dt><dd><a href=/block/1>1</a></dd>
+  <dt>received</dt><dd>200000000</dd>
+  <dt>sent</dt><dd>0</dd>
+</dl>
+<div class=center>
+prev
+next
+</div>.*",
+        coinbase.txid(),
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/address/{address}/history/1"),
+      StatusCode::OK,
+      ".*<h3>No transactions</h3>.*",
+    );
+  }
+
+  #[test]
+  fn inscription_history() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..default()
+    });
+
+    let id = InscriptionId { txid, index: 0 };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    server.assert_response_regex(
+      format!("/inscription/{id}/history"),
+      StatusCode::OK,
+      ".*<h3>No transfers</h3>.*",
+    );
+
+    let send = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let address = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    let transfer = api::InscriptionTransfer {
+      address: Some(address.to_string()),
+      height: 3,
+      new_satpoint: SatPoint {
+        outpoint: OutPoint {
+          txid: send,
+          vout: 0,
+        },
+        offset: 0,
+      },
+      old_satpoint: SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      txid: send,
+    };
+
+    let history = api::InscriptionHistory {
+      id,
+      more: false,
+      page: 0,
+      transfers: vec![transfer],
+    };
+
+    pretty_assert_eq!(
+      server.get_json::<api::InscriptionHistory>(format!("/inscription/{id}/history")),
+      history,
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::InscriptionHistory>(format!("/r/inscription/{id}/history")),
+      history,
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::InscriptionHistory>(format!("/r/inscription/{id}/history/1")),
+      api::InscriptionHistory {
+        id,
+        more: false,
+        page: 1,
+        transfers: Vec::new(),
+      },
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{id}/history"),
+      StatusCode::OK,
+      format!(
+        ".*<h1><a href=/inscription/{id}>Inscription {id}</a> History</h1>
+<dl>
+  <dt>transaction</dt><dd><a class=monospace href=/tx/{send}>{send}</a></dd>
+  <dt>block</dt><dd><a href=/block/3>3</a></dd>
+  <dt>from</dt><dd><a class=monospace href=/satpoint/{txid}:0:0>{txid}:0:0</a></dd>
+  <dt>to</dt><dd><a class=monospace href=/satpoint/{send}:0:0>{send}:0:0</a></dd>
+  <dt>address</dt><dd><a class=monospace href=/address/{address}>{address}</a></dd>
+</dl>
+<div class=center>
+prev
+next
+</div>.*",
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/r/inscription/{txid}i1/history"),
+      StatusCode::NOT_FOUND,
+      ".*",
+    );
+  }
+
   #[test]
   fn fallback() {
     let server = TestServer::new();
@@ -3027,72 +4225,245 @@ mod tests {
   }
 
   #[test]
-  fn runes_can_be_queried_by_rune_number() {
+  fn rune_holders() {
     let server = TestServer::builder()
       .chain(Chain::Regtest)
       .index_runes()
       .build();
 
-    server.mine_blocks(1);
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-    server.assert_response_regex("/rune/0", StatusCode::NOT_FOUND, ".*");
+    let rune = Rune(RUNE);
 
-    for i in 0..10 {
-      let rune = Rune(RUNE + i);
-      server.etch(
-        Runestone {
-          edicts: vec![Edict {
-            id: RuneId::default(),
-            amount: u128::MAX,
-            output: 0,
-          }],
-          etching: Some(Etching {
-            rune: Some(rune),
-            ..default()
-          }),
+    server.assert_response_regex(format!("/rune/{rune}/holders"), StatusCode::NOT_FOUND, ".*");
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
           ..default()
-        },
-        1,
-        None,
-      );
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
 
-      server.mine_blocks(1);
-    }
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
 
-    server.assert_response_regex(
-      "/rune/0",
-      StatusCode::OK,
-      ".*<title>Rune AAAAAAAAAAAAA</title>.*",
+    pretty_assert_eq!(
+      server.get_json::<api::RuneHolders>(format!("/rune/{rune}/holders")),
+      api::RuneHolders {
+        addresses: None,
+        holders: vec![(
+          api::RuneHolder::Output(OutPoint { txid, vout: 0 }),
+          Pile {
+            amount: 1000,
+            divisibility: 0,
+            symbol: None,
+          },
+        )],
+        id,
+        more: false,
+        next: None,
+        outputs: 1,
+        prev: None,
+        rune: SpacedRune { rune, spacers: 0 },
+      },
     );
 
-    for i in 1..6 {
-      server.assert_response_regex(
-        format!("/rune/{}", i),
-        StatusCode::OK,
-        ".*<title>Rune AAAAAAAAAAAA.*</title>.*",
-      );
-    }
-
     server.assert_response_regex(
-      "/rune/9",
+      format!("/rune/{id}/holders"),
       StatusCode::OK,
-      ".*<title>Rune AAAAAAAAAAAAJ</title>.*",
+      format!(
+        ".*<h1><a href=/rune/{rune}>{rune}</a> Holders</h1>
+<dl>
+  <dt>outputs</dt>
+  <dd>1</dd>
+</dl>
+<dl>
+  <dt><a class=monospace href=/output/{txid}:0>{txid}:0</a></dt>
+  <dd>1000\u{A0}¤</dd>
+</dl>.*"
+      ),
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneHolders>(format!("/rune/{rune}/holders/1"))
+        .holders,
+      Vec::new(),
     );
   }
 
   #[test]
-  fn runes_are_displayed_on_runes_page() {
+  fn rune_activity() {
     let server = TestServer::builder()
       .chain(Chain::Regtest)
       .index_runes()
       .build();
 
-    server.mine_blocks(1);
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let rune = Rune(RUNE);
 
     server.assert_response_regex(
-      "/runes",
-      StatusCode::OK,
-      ".*<title>Runes</title>.*<h1>Runes</h1>\n<ul>\n</ul>\n<div class=center>\n    prev\n      next\n  </div>.*",
+      format!("/rune/{rune}/activity"),
+      StatusCode::NOT_FOUND,
+      ".*",
+    );
+
+    let (_txid, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          terms: Some(Terms {
+            amount: Some(100),
+            cap: Some(10),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          mint: Some(id),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let height = u32::try_from(id.block).unwrap() + 1;
+
+    let activity = api::RuneActivity {
+      activity: vec![api::RuneBlockActivity {
+        burned: 0,
+        height,
+        minted: 100,
+        mints: 1,
+        transferred: 0,
+      }],
+      id,
+      more: false,
+      next_height: None,
+      rune: SpacedRune { rune, spacers: 0 },
+    };
+
+    pretty_assert_eq!(
+      server.get_json::<api::RuneActivity>(format!("/rune/{rune}/activity")),
+      activity,
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneActivity>(format!("/rune/{id}/activity?from={height}&to={height}")),
+      activity,
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneActivity>(format!("/rune/{rune}/activity?to={}", height - 1))
+        .activity,
+      Vec::new(),
+    );
+
+    server.assert_response(
+      format!("/rune/{rune}/activity?from=2&to=1"),
+      StatusCode::BAD_REQUEST,
+      "`from` height 2 is greater than `to` height 1",
+    );
+
+    server.assert_response_regex(
+      format!("/rune/{rune}"),
+      StatusCode::OK,
+      ".*<dt>supply chart</dt>.*",
+    );
+  }
+
+  #[test]
+  fn runes_can_be_queried_by_rune_number() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response_regex("/rune/0", StatusCode::NOT_FOUND, ".*");
+
+    for i in 0..10 {
+      let rune = Rune(RUNE + i);
+      server.etch(
+        Runestone {
+          edicts: vec![Edict {
+            id: RuneId::default(),
+            amount: u128::MAX,
+            output: 0,
+          }],
+          etching: Some(Etching {
+            rune: Some(rune),
+            ..default()
+          }),
+          ..default()
+        },
+        1,
+        None,
+      );
+
+      server.mine_blocks(1);
+    }
+
+    server.assert_response_regex(
+      "/rune/0",
+      StatusCode::OK,
+      ".*<title>Rune AAAAAAAAAAAAA</title>.*",
+    );
+
+    for i in 1..6 {
+      server.assert_response_regex(
+        format!("/rune/{}", i),
+        StatusCode::OK,
+        ".*<title>Rune AAAAAAAAAAAA.*</title>.*",
+      );
+    }
+
+    server.assert_response_regex(
+      "/rune/9",
+      StatusCode::OK,
+      ".*<title>Rune AAAAAAAAAAAAJ</title>.*",
+    );
+  }
+
+  #[test]
+  fn runes_are_displayed_on_runes_page() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      "/runes",
+      StatusCode::OK,
+      ".*<title>Runes</title>.*<h1>Runes</h1>\n<ul>\n</ul>\n<div class=center>\n    prev\n      next\n  </div>.*",
     );
 
     let (txid, id) = server.etch(
@@ -3242,6 +4613,8 @@ mod tests {
   <dd>100%</dd>
   <dt>burned</dt>
   <dd>0\u{A0}%</dd>
+  <dt>holders</dt>
+  <dd><a href=/rune/AAAAAAAAAAAAA/holders>holders</a></dd>
   <dt>divisibility</dt>
   <dd>0</dd>
   <dt>symbol</dt>
@@ -4416,6 +5789,7 @@ mod tests {
   fn content_response_no_content() {
     assert_eq!(
       Server::content_response(
+        inscription_id(1),
         Inscription {
           content_type: Some("text/plain".as_bytes().to_vec()),
           body: None,
@@ -4432,6 +5806,7 @@ mod tests {
   #[test]
   fn content_response_with_content() {
     let (headers, body) = Server::content_response(
+      inscription_id(1),
       Inscription {
         content_type: Some("text/plain".as_bytes().to_vec()),
         body: Some(vec![1, 2, 3]),
@@ -4450,6 +5825,7 @@ mod tests {
   #[test]
   fn content_security_policy_no_origin() {
     let (headers, _) = Server::content_response(
+      inscription_id(1),
       Inscription {
         content_type: Some("text/plain".as_bytes().to_vec()),
         body: Some(vec![1, 2, 3]),
@@ -4470,6 +5846,7 @@ mod tests {
   #[test]
   fn content_security_policy_with_origin() {
     let (headers, _) = Server::content_response(
+      inscription_id(1),
       Inscription {
         content_type: Some("text/plain".as_bytes().to_vec()),
         body: Some(vec![1, 2, 3]),
@@ -4565,6 +5942,7 @@ mod tests {
   #[test]
   fn content_response_no_content_type() {
     let (headers, body) = Server::content_response(
+      inscription_id(1),
       Inscription {
         content_type: None,
         body: Some(Vec::new()),
@@ -4583,6 +5961,7 @@ mod tests {
   #[test]
   fn content_response_bad_content_type() {
     let (headers, body) = Server::content_response(
+      inscription_id(1),
       Inscription {
         content_type: Some("\n".as_bytes().to_vec()),
         body: Some(Vec::new()),
@@ -5006,13 +6385,819 @@ mod tests {
 
     let response = server.get(format!("/content/{}", InscriptionId { txid, index: 0 }));
 
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "public, max-age=1209600, immutable"
+    );
+  }
+
+  #[test]
+  fn content_responses_support_byte_ranges() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello world").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let response = server.get(format!("/content/{inscription_id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::ACCEPT_RANGES).unwrap(),
+      "bytes"
+    );
+    assert_eq!(
+      response.headers().get(header::ETAG).unwrap(),
+      &format!("\"{inscription_id}\"")
+    );
+
+    let client = reqwest::blocking::Client::new();
+    let url = server.join_url(&format!("/content/{inscription_id}"));
+
+    let response = client
+      .get(url.clone())
+      .header(header::RANGE, "bytes=6-")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+      response.headers().get(header::CONTENT_RANGE).unwrap(),
+      "bytes 6-10/11"
+    );
+    assert_eq!(response.text().unwrap(), "world");
+
+    let response = client
+      .get(url.clone())
+      .header(header::RANGE, "bytes=-5")
+      .header(header::IF_RANGE, format!("\"{inscription_id}\""))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.text().unwrap(), "world");
+
+    let response = client
+      .get(url.clone())
+      .header(header::RANGE, "bytes=0-4")
+      .header(header::IF_RANGE, "\"foo\"")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().unwrap(), "hello world");
+
+    let response = client
+      .get(url)
+      .header(header::RANGE, "bytes=11-")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(
+      response.headers().get(header::CONTENT_RANGE).unwrap(),
+      "bytes */11"
+    );
+  }
+
+  #[test]
+  fn content_responses_support_if_none_match() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let client = reqwest::blocking::Client::new();
+    let url = server.join_url(&format!("/content/{inscription_id}"));
+
+    let response = client
+      .get(url.clone())
+      .header(
+        header::IF_NONE_MATCH,
+        format!("\"foo\", \"{inscription_id}\""),
+      )
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(
+      response.headers().get(header::ETAG).unwrap(),
+      &format!("\"{inscription_id}\"")
+    );
+    assert_eq!(response.text().unwrap(), "");
+
+    let response = client
+      .get(url)
+      .header(header::IF_NONE_MATCH, "\"foo\"")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().unwrap(), "hello");
+  }
+
+  #[test]
+  fn content_responses_are_not_compressed() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain", "hello world").to_witness(),
+      )],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let etag = format!("\"{inscription_id}\"");
+
+    let client = reqwest::blocking::Client::new();
+
+    let url = server.join_url(&format!("/content/{inscription_id}"));
+
+    let response = client
+      .get(url.clone())
+      .header(header::ACCEPT_ENCODING, "gzip")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+    assert_eq!(response.headers().get(header::CONTENT_ENCODING), None);
+    assert_eq!(response.text().unwrap(), "hello world");
+
+    let response = client
+      .get(url)
+      .header(header::ACCEPT_ENCODING, "gzip")
+      .header(header::RANGE, "bytes=6-")
+      .header(header::IF_RANGE, &etag)
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.text().unwrap(), "world");
+  }
+
+  #[test]
+  fn decompressed_content_has_distinct_etag() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--decompress")
+      .build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let mut body = Vec::new();
+    {
+      let mut writer = brotli::CompressorWriter::new(&mut body, 4096, 11, 22);
+      writer.write_all(b"hello world").unwrap();
+    }
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        Inscription {
+          content_type: Some("text/plain".into()),
+          content_encoding: Some("br".into()),
+          body: Some(body),
+          ..default()
+        }
+        .to_witness(),
+      )],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let response = reqwest::blocking::Client::builder()
+      .brotli(false)
+      .build()
+      .unwrap()
+      .get(server.join_url(&format!("/content/{inscription_id}")))
+      .header(header::RANGE, "bytes=0-4")
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+      response.headers().get(header::ETAG).unwrap(),
+      &format!("\"{inscription_id}-decompressed\"")
+    );
+    assert_eq!(response.text().unwrap(), "hello");
+  }
+
+  #[test]
+  fn gzip_content_is_decompressed_within_limits() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--decompress")
+      .server_flag("--decompress-max-ratio=2")
+      .build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscribe = |block: usize, content_encoding: &str, body: &[u8]| {
+      let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+      encoder.write_all(body).unwrap();
+
+      let txid = server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          block,
+          0,
+          0,
+          Inscription {
+            content_type: Some("text/plain".into()),
+            content_encoding: Some(content_encoding.into()),
+            body: Some(encoder.finish().unwrap()),
+            ..default()
+          }
+          .to_witness(),
+        )],
+        ..default()
+      });
+
+      server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+      InscriptionId { txid, index: 0 }
+    };
+
+    let small = inscribe(1, "GZIP", b"hello world");
+    let large = inscribe(2, "gzip", &[0; 10_000]);
+
+    let client = reqwest::blocking::Client::builder()
+      .brotli(false)
+      .build()
+      .unwrap();
+
+    let response = client
+      .get(server.join_url(&format!("/content/{small}")))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(header::CONTENT_ENCODING), None);
+    assert_eq!(response.text().unwrap(), "hello world");
+
+    let response = client
+      .get(server.join_url(&format!("/content/{large}")))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+  }
+
+  #[test]
+  fn thumbnail() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let mut png = Vec::new();
+    image::RgbaImage::new(400, 200)
+      .write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+      .unwrap();
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("image/png", png).to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    for _ in 0..2 {
+      let response = server.get(format!("/thumbnail/{inscription_id}?size=100"));
+      assert_eq!(response.status(), StatusCode::OK);
+      assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "image/png"
+      );
+
+      let thumbnail = image::load_from_memory(&response.bytes().unwrap()).unwrap();
+      assert_eq!((thumbnail.width(), thumbnail.height()), (128, 64));
+    }
+
+    server.assert_response(
+      format!("/thumbnail/{inscription_id}?size=0"),
+      StatusCode::BAD_REQUEST,
+      "thumbnail size must be between 1 and 1024",
+    );
+  }
+
+  #[test]
+  fn thumbnail_placeholder() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("video/mp4", "foo").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let response = server.get(format!("/thumbnail/{}", InscriptionId { txid, index: 0 }));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "image/svg+xml"
+    );
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "no-store"
+    );
+    assert_regex_match!(
+      response.text().unwrap(),
+      r#"<svg .* width="256" height="256" .*>video</text></svg>"#
+    );
+  }
+
+  #[test]
+  fn openapi_spec_matches_json_responses() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .index_sats()
+      .ord_flag("--index-addresses")
+      .ord_flag("--index-address-history")
+      .ord_flag("--index-events")
+      .ord_flag("--index-text")
+      .server_flag("--events")
+      .build();
+
+    let coinbase = server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE)[0].txdata[0].clone();
+
+    let address = Chain::Regtest
+      .address_from_script(&coinbase.output[0].script_pubkey)
+      .unwrap();
+
+    let mut metadata = Vec::new();
+    ciborium::into_writer("bar", &mut metadata).unwrap();
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        Inscription {
+          content_type: Some("text/plain".into()),
+          body: Some("hello world".into()),
+          metadata: Some(metadata),
+          ..default()
+        }
+        .to_witness(),
+      )],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let child = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[
+          (
+            3,
+            0,
+            0,
+            Inscription {
+              content_type: Some("text/plain".into()),
+              body: Some("child".into()),
+              parents: vec![id.value()],
+              ..default()
+            }
+            .to_witness(),
+          ),
+          (2, 1, 0, Default::default()),
+        ],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let rune = Rune(RUNE);
+
+    server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let sha256 = sha256::Hash::hash(b"hello world");
+
+    let samples = [
+      ("/", "/".into()),
+      ("/address/:address", format!("/address/{address}")),
+      (
+        "/address/:address/history",
+        format!("/address/{address}/history"),
+      ),
+      (
+        "/address/:address/history/:page",
+        format!("/address/{address}/history/0"),
+      ),
+      (
+        "/address/:address/runes",
+        format!("/address/{address}/runes"),
+      ),
+      ("/block/:query", "/block/2".into()),
+      ("/blockcount", "/blockcount".into()),
+      ("/blockhash", "/blockhash".into()),
+      ("/blockhash/:height", "/blockhash/2".into()),
+      ("/blockheight", "/blockheight".into()),
+      ("/blocks", "/blocks".into()),
+      ("/blocktime", "/blocktime".into()),
+      ("/bounties", "/bounties".into()),
+      ("/children/:inscription_id", format!("/children/{id}")),
+      (
+        "/children/:inscription_id/:page",
+        format!("/children/{id}/0"),
+      ),
+      ("/collections", "/collections".into()),
+      ("/collections/:page", "/collections/0".into()),
+      ("/content/:inscription_id", format!("/content/{id}")),
+      ("/decode/:txid", format!("/decode/{txid}")),
+      ("/events", "/events".into()),
+      ("/events/stream", "/events/stream".into()),
+      ("/faq", "/faq".into()),
+      ("/favicon.ico", "/favicon.ico".into()),
+      ("/feed.xml", "/feed.xml".into()),
+      ("/input/:block/:transaction/:input", "/input/2/1/0".into()),
+      (
+        "/inscription/:inscription_query",
+        format!("/inscription/{id}"),
+      ),
+      (
+        "/inscription/:inscription_query/:child",
+        format!("/inscription/{id}/0"),
+      ),
+      (
+        "/inscription/:inscription_query/history",
+        format!("/inscription/{id}/history"),
+      ),
+      (
+        "/inscription/:inscription_query/history/:page",
+        format!("/inscription/{id}/history/0"),
+      ),
+      ("/inscriptions", "/inscriptions".into()),
+      ("/inscriptions/:page", "/inscriptions/0".into()),
+      (
+        "/inscriptions/block/:height",
+        "/inscriptions/block/2".into(),
+      ),
+      (
+        "/inscriptions/block/:height/:page",
+        "/inscriptions/block/2/0".into(),
+      ),
+      (
+        "/inscriptions/hash/:sha256",
+        format!("/inscriptions/hash/{sha256}"),
+      ),
+      (
+        "/inscriptions/hash/:sha256/:page",
+        format!("/inscriptions/hash/{sha256}/0"),
+      ),
+      (
+        "/inscriptions/search",
+        "/inscriptions/search?query=hello".into(),
+      ),
+      (
+        "/inscriptions/search/:page",
+        "/inscriptions/search/0?query=hello".into(),
+      ),
+      ("/install.sh", "/install.sh".into()),
+      ("/openapi.json", "/openapi.json".into()),
+      ("/ordinal/:sat", "/ordinal/0".into()),
+      ("/output/:output", format!("/output/{txid}:0")),
+      ("/parents/:inscription_id", format!("/parents/{child}")),
+      (
+        "/parents/:inscription_id/:page",
+        format!("/parents/{child}/0"),
+      ),
+      ("/preview/:inscription_id", format!("/preview/{id}")),
+      ("/r/blockhash", "/r/blockhash".into()),
+      ("/r/blockhash/:height", "/r/blockhash/2".into()),
+      ("/r/blockheight", "/r/blockheight".into()),
+      ("/r/blockinfo/:query", "/r/blockinfo/2".into()),
+      ("/r/blocktime", "/r/blocktime".into()),
+      ("/r/children/:inscription_id", format!("/r/children/{id}")),
+      (
+        "/r/children/:inscription_id/:page",
+        format!("/r/children/{id}/0"),
+      ),
+      (
+        "/r/children/:inscription_id/inscriptions",
+        format!("/r/children/{id}/inscriptions"),
+      ),
+      (
+        "/r/children/:inscription_id/inscriptions/:page",
+        format!("/r/children/{id}/inscriptions/0"),
+      ),
+      (
+        "/r/inscription/:inscription_id",
+        format!("/r/inscription/{id}"),
+      ),
+      (
+        "/r/inscription/:inscription_id/history",
+        format!("/r/inscription/{id}/history"),
+      ),
+      (
+        "/r/inscription/:inscription_id/history/:page",
+        format!("/r/inscription/{id}/history/0"),
+      ),
+      ("/r/metadata/:inscription_id", format!("/r/metadata/{id}")),
+      ("/r/parents/:inscription_id", format!("/r/parents/{id}")),
+      (
+        "/r/parents/:inscription_id/:page",
+        format!("/r/parents/{id}/0"),
+      ),
+      ("/r/sat/:sat_number", "/r/sat/0".into()),
+      ("/r/sat/:sat_number/:page", "/r/sat/0/0".into()),
+      ("/r/sat/:sat_number/at/:index", "/r/sat/0/at/-1".into()),
+      ("/rare.txt", "/rare.txt".into()),
+      ("/rune/:rune", format!("/rune/{rune}")),
+      ("/rune/:rune/activity", format!("/rune/{rune}/activity")),
+      ("/rune/:rune/holders", format!("/rune/{rune}/holders")),
+      (
+        "/rune/:rune/holders/:page",
+        format!("/rune/{rune}/holders/0"),
+      ),
+      ("/runes", "/runes".into()),
+      ("/runes/:page", "/runes/0".into()),
+      (
+        "/runes_on_output/:output",
+        format!("/runes_on_output/{txid}:0"),
+      ),
+      ("/sat/:sat", "/sat/0".into()),
+      (
+        "/satpoint/:satpoint",
+        format!("/satpoint/{}:0:0", child.txid),
+      ),
+      ("/search", "/search?query=2".into()),
+      ("/search/*query", "/search/2".into()),
+      ("/static/*path", "/static/index.css".into()),
+      ("/status", "/status".into()),
+      ("/thumbnail/:inscription_id", format!("/thumbnail/{id}")),
+      ("/tx/:txid", format!("/tx/{txid}")),
+      ("/update", "/update".into()),
+    ];
+
+    let routes = Server::routes();
+
+    pretty_assert_eq!(
+      samples
+        .iter()
+        .map(|(route, _)| *route)
+        .collect::<BTreeSet<&str>>(),
+      routes
+        .iter()
+        .filter(|route| route.method == "get")
+        .map(|route| route.path)
+        .collect::<BTreeSet<&str>>(),
+    );
+
+    let posts = [
+      ("/inscriptions", serde_json::json!([id])),
+      ("/outputs", serde_json::json!([format!("{txid}:0")])),
+    ];
+
+    pretty_assert_eq!(
+      posts.iter().map(|(url, _)| *url).collect::<Vec<&str>>(),
+      routes
+        .iter()
+        .filter(|route| route.method == "post" && route.endpoint.is_some())
+        .map(|route| route.path)
+        .collect::<Vec<&str>>(),
+    );
+
+    let spec = server.get_json::<serde_json::Value>("/openapi.json");
+
+    let check = |method: &str, endpoint: &str, url: &str, value: serde_json::Value| {
+      let schema = &spec["paths"][openapi::path(endpoint)][method]["responses"]["200"]["content"]
+        ["application/json"]["schema"];
+
+      if let Err(err) = openapi::validate(&spec, schema, &value, "") {
+        panic!("{} {url} does not match spec: {err}", method.to_uppercase());
+      }
+    };
+
+    let client = reqwest::blocking::Client::builder()
+      .redirect(reqwest::redirect::Policy::none())
+      .build()
+      .unwrap();
+
+    for (path, url) in samples {
+      let route = routes
+        .iter()
+        .find(|route| route.method == "get" && route.path == path)
+        .unwrap();
+
+      if route.endpoint.is_some() {
+        check("get", path, &url, server.get_json(&url));
+        continue;
+      }
+
+      if openapi::UNDESCRIBED.contains(&("get", path)) {
+        continue;
+      }
+
+      let response = client
+        .get(server.join_url(&url))
+        .header(header::ACCEPT, "application/json")
+        .send()
+        .unwrap();
+
+      assert!(
+        response.status().is_success()
+          || response.status().is_redirection()
+          // only served to integration tests
+          || path == "/update",
+        "GET {url} returned {}",
+        response.status(),
+      );
+
+      assert!(
+        !response
+          .headers()
+          .get(header::CONTENT_TYPE)
+          .and_then(|content_type| content_type.to_str().ok())
+          .unwrap_or_default()
+          .starts_with("application/json"),
+        "GET {url} responds with JSON but is not described",
+      );
+    }
+
+    for (url, body) in posts {
+      let response = reqwest::blocking::Client::new()
+        .post(server.join_url(url))
+        .header(header::ACCEPT, "application/json")
+        .json(&body)
+        .send()
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+
+      check("post", url, url, response.json().unwrap());
+    }
+  }
+
+  fn graphql(server: &TestServer, query: &str) -> serde_json::Value {
+    reqwest::blocking::Client::new()
+      .post(server.join_url("/graphql"))
+      .json(&serde_json::json!({ "query": query }))
+      .send()
+      .unwrap()
+      .json()
+      .unwrap()
+  }
+
+  #[test]
+  fn graphql_queries_index() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .ord_flag("--index-addresses")
+      .server_flag("--graphql")
+      .build();
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain", "hello world").to_witness(),
+      )],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    let inscription = server.get_json::<api::Inscription>(format!("/inscription/{id}"));
+
+    let address = inscription.address.clone().unwrap();
+
+    let outpoint = inscription.satpoint.outpoint;
+
+    pretty_assert_eq!(
+      graphql(
+        &server,
+        &format!(
+          "{{
+            inscription(query: \"{id}\") {{
+              id
+              number
+              contentType
+              sat {{ number inscriptions {{ id }} next: inscriptions(page: 1) {{ id }} }}
+              output {{ outpoint inscriptions {{ id }} next: inscriptions(page: 1) {{ id }} }}
+            }}
+            address(address: \"{address}\") {{
+              outputs {{ outpoint }}
+              inscriptions {{ id }}
+              nextOutputs: outputs(page: 1) {{ outpoint }}
+              nextInscriptions: inscriptions(page: 1) {{ id }}
+            }}
+            block(query: \"2\") {{ height inscriptions {{ number }} }}
+          }}"
+        ),
+      ),
+      serde_json::json!({
+        "data": {
+          "inscription": {
+            "id": id.to_string(),
+            "number": 0,
+            "contentType": "text/plain",
+            "sat": {
+              "number": inscription.sat.unwrap().n(),
+              "inscriptions": [{ "id": id.to_string() }],
+              "next": [],
+            },
+            "output": {
+              "outpoint": outpoint.to_string(),
+              "inscriptions": [{ "id": id.to_string() }],
+              "next": [],
+            },
+          },
+          "address": {
+            "outputs": [{ "outpoint": outpoint.to_string() }],
+            "inscriptions": [{ "id": id.to_string() }],
+            "nextOutputs": [],
+            "nextInscriptions": [],
+          },
+          "block": { "height": 2, "inscriptions": [{ "number": 0 }] },
+        },
+      }),
+    );
+
+    pretty_assert_eq!(
+      graphql(&server, "{ inscription(query: \"1\") { id } }"),
+      serde_json::json!({ "data": { "inscription": null } }),
+    );
+  }
+
+  #[test]
+  fn graphql_limits_query_depth_and_complexity() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--graphql")
+      .build();
+
+    assert_eq!(
+      graphql(
+        &server,
+        "{ inscription(query: \"0\") { children { children { children { id } } } } }",
+      )["errors"][0]["message"],
+      "Query is too complex.",
+    );
+
     assert_eq!(
-      response.headers().get(header::CACHE_CONTROL).unwrap(),
-      "public, max-age=1209600, immutable"
+      graphql(
+        &server,
+        "{ inscription(query: \"0\") { rune { parent { rune { parent { \
+          rune { parent { rune { parent { rune { parent { id } } } } } } } } } } } }",
+      )["errors"][0]["message"],
+      "Query is nested too deep.",
     );
   }
 
+  #[test]
+  fn graphql_requires_flag() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/graphql"))
+      .json(&serde_json::json!({ "query": "{ block(query: \"0\") { height } }" }))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
   #[test]
   fn error_content_responses_have_max_age_zero_cache_control_headers() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
@@ -5083,6 +7268,196 @@ mod tests {
     );
   }
 
+  #[test]
+  fn inscriptions_filtered() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let plain = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, inscription("image/png", [1; 100]).to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>("/inscriptions?content_type=text%2F"),
+      api::Inscriptions {
+        ids: vec![plain],
+        more: false,
+        page_index: 0,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>("/inscriptions/0?content_type=text%2F&to_height=1"),
+      api::Inscriptions {
+        ids: Vec::new(),
+        more: false,
+        page_index: 0,
+      }
+    );
+
+    server.assert_response(
+      "/inscriptions?from_height=3&to_height=2",
+      StatusCode::BAD_REQUEST,
+      "`from_height` 3 is greater than `to_height` 2",
+    );
+
+    server.assert_response(
+      "/inscriptions?rarity=uncommon",
+      StatusCode::NOT_FOUND,
+      "sat index required",
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>("/inscriptions?charm=cursed"),
+      api::Inscriptions {
+        ids: Vec::new(),
+        more: false,
+        page_index: 0,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>("/inscriptions?charm=cursed&from_height=0&to_height=3"),
+      api::Inscriptions {
+        ids: Vec::new(),
+        more: false,
+        page_index: 0,
+      }
+    );
+  }
+
+  #[test]
+  fn inscriptions_with_hash() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let first = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let second = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let hash = sha256::Hash::hash(b"hello");
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>(format!("/inscriptions/hash/{hash}")),
+      api::Inscriptions {
+        ids: vec![first, second],
+        more: false,
+        page_index: 0,
+      }
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::Inscription>(format!("/inscription/{first}"))
+        .duplicate_of,
+      None,
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::Inscription>(format!("/inscription/{second}"))
+        .duplicate_of,
+      Some(first),
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{second}"),
+      StatusCode::OK,
+      format!(".*<dt>duplicate of</dt>\n  <dd><a class=monospace href=/inscription/{first}>{first}</a></dd>.*"),
+    );
+
+    server.assert_response_regex(
+      format!("/inscriptions/hash/{hash}"),
+      StatusCode::OK,
+      format!(".*<h1>Inscriptions with Content <span class=monospace>{hash}</span></h1>.*"),
+    );
+  }
+
+  #[test]
+  fn inscriptions_search() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-text")
+      .build();
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let id = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          1,
+          0,
+          0,
+          inscription("text/plain", "hello world").to_witness(),
+        )],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>("/inscriptions/search?query=Hello"),
+      api::Inscriptions {
+        ids: vec![id],
+        more: false,
+        page_index: 0,
+      }
+    );
+
+    server.assert_response_regex(
+      "/inscriptions/search?query=hello%20world",
+      StatusCode::OK,
+      format!(".*<h1>Inscriptions Matching hello world</h1>\n<div class=thumbnails>\n  <a href=/inscription/{id}>.*"),
+    );
+
+    server.assert_redirect(
+      "/search?query=hello%20world",
+      "/inscriptions/search?query=hello%20world",
+    );
+  }
+
+  #[test]
+  fn inscriptions_search_requires_text_index() {
+    TestServer::new().assert_response(
+      "/inscriptions/search?query=foo",
+      StatusCode::NOT_FOUND,
+      "this server has no text index",
+    );
+  }
+
   #[test]
   fn collections_page_prev_and_next() {
     let server = TestServer::builder()
@@ -6890,6 +9265,7 @@ next
       api::BlockInfo {
         average_fee: 0,
         average_fee_rate: 0,
+        auxpow: None,
         bits: 486604799,
         chainwork: [0; 32],
         confirmations: 0,
@@ -6930,6 +9306,7 @@ next
       api::BlockInfo {
         average_fee: 0,
         average_fee_rate: 0,
+        auxpow: None,
         bits: 0,
         chainwork: [0; 32],
         confirmations: 0,
@@ -6962,6 +9339,21 @@ next
     )
   }
 
+  #[test]
+  fn block_auxpow_is_served_from_index() {
+    let server = TestServer::builder().ord_flag("--index-auxpow").build();
+
+    server.mine_blocks(1);
+
+    assert_eq!(server.get_json::<api::Block>("/block/1").auxpow, None);
+    assert_eq!(
+      server.get_json::<api::BlockInfo>("/r/blockinfo/1").auxpow,
+      None
+    );
+
+    server.assert_response_regex("/block/1", StatusCode::OK, ".*<h1>Block 1</h1>.*");
+  }
+
   #[test]
   fn authentication_requires_username_and_password() {
     assert!(Arguments::try_parse_from(["ord", "--server-username", "server", "foo"]).is_err());
@@ -7176,4 +9568,198 @@ next
       "output 0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef:123 not found",
     );
   }
+
+  #[test]
+  fn event_log_requires_index_events() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.assert_response(
+      "/events",
+      StatusCode::NOT_FOUND,
+      "this server has no event index",
+    );
+  }
+
+  #[test]
+  fn event_log_returns_events_from_height() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-events")
+      .build();
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let created = Event::InscriptionCreated {
+      block_height: 2,
+      charms: 0,
+      inscription_id: InscriptionId { txid, index: 0 },
+      location: Some(SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      }),
+      parent_inscription_ids: Vec::new(),
+      sequence_number: 0,
+    };
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>("/events"),
+      api::Events {
+        events: vec![created],
+        more: false,
+        next_height: 3,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>("/events?from_height=3"),
+      api::Events {
+        events: Vec::new(),
+        more: false,
+        next_height: 3,
+      }
+    );
+  }
+
+  #[test]
+  fn event_stream_requires_events_flag() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.assert_response(
+      "/events/stream",
+      StatusCode::NOT_FOUND,
+      "event stream not found",
+    );
+  }
+
+  #[test]
+  fn event_stream_rejects_unknown_kind() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--events")
+      .build();
+    server.assert_response(
+      "/events/stream?kind=inscription_created,foo",
+      StatusCode::BAD_REQUEST,
+      "unknown event kind `foo`",
+    );
+  }
+
+  #[test]
+  fn event_stream_publishes_filtered_events() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--events")
+      .build();
+
+    server.mine_blocks_with_subsidy(2, 2 * COIN_VALUE);
+
+    let response =
+      reqwest::blocking::get(server.join_url("/events/stream?kind=inscription_transferred"))
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/event-stream"
+    );
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let transfer = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 0, Default::default())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let mut lines = io::BufRead::lines(io::BufReader::new(response));
+
+    let mut next_field = |field: &str| loop {
+      let line = lines.next().unwrap().unwrap();
+      if let Some(value) = line.strip_prefix(field) {
+        return value.to_string();
+      }
+    };
+
+    assert_eq!(next_field("event:"), "inscription_transferred");
+
+    pretty_assert_eq!(
+      serde_json::from_str::<Event>(&next_field("data:")).unwrap(),
+      Event::InscriptionTransferred {
+        block_height: 4,
+        inscription_id,
+        new_location: SatPoint {
+          outpoint: OutPoint {
+            txid: transfer,
+            vout: 0,
+          },
+          offset: 0,
+        },
+        old_location: SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        },
+        sequence_number: 0,
+      }
+    );
+  }
+
+  #[test]
+  fn event_stream_filters_by_address() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-addresses")
+      .server_flag("--events")
+      .build();
+
+    server.mine_blocks_with_subsidy(2, 2 * COIN_VALUE);
+
+    let address = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_p2pkh(&bellscoin::PubkeyHash::all_zeros()))
+      .unwrap();
+
+    let response = reqwest::blocking::get(server.join_url(&format!(
+      "/events/stream?kind=inscription_created&address={address}"
+    )))
+    .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, inscription("text/plain", "bar").to_witness())],
+      receiver: Some(address),
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let data = io::BufRead::lines(io::BufReader::new(response))
+      .find_map(|line| line.unwrap().strip_prefix("data:").map(str::to_string))
+      .unwrap();
+
+    assert_matches!(
+      serde_json::from_str::<Event>(&data).unwrap(),
+      Event::InscriptionCreated { inscription_id, .. }
+      if inscription_id == InscriptionId { txid, index: 0 }
+    );
+  }
 }
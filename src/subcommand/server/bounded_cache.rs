@@ -0,0 +1,99 @@
+use {
+  super::*,
+  std::{collections::VecDeque, hash::Hash},
+};
+
+/// Values with a size in bytes, evicted oldest first once their total size
+/// would exceed the capacity. Values larger than the capacity are not cached.
+pub(crate) struct BoundedCache<K, V> {
+  capacity: usize,
+  entries: HashMap<K, (V, usize)>,
+  order: VecDeque<K>,
+  size: usize,
+}
+
+impl<K: Clone + Eq + Hash, V> BoundedCache<K, V> {
+  pub(crate) fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      entries: HashMap::new(),
+      order: VecDeque::new(),
+      size: 0,
+    }
+  }
+
+  pub(crate) fn contains(&self, key: &K) -> bool {
+    self.entries.contains_key(key)
+  }
+
+  pub(crate) fn get(&self, key: &K) -> Option<&V> {
+    self.entries.get(key).map(|(value, _size)| value)
+  }
+
+  /// Inserts `value` under `key` unless it is already cached or larger than
+  /// the capacity, returning the keys evicted to make room, oldest first.
+  pub(crate) fn insert(&mut self, key: K, value: V, size: usize) -> Vec<K> {
+    if size > self.capacity || self.entries.contains_key(&key) {
+      return Vec::new();
+    }
+
+    let mut evicted = Vec::new();
+
+    while self.size + size > self.capacity {
+      let Some(oldest) = self.order.pop_front() else {
+        break;
+      };
+
+      if let Some((_value, size)) = self.entries.remove(&oldest) {
+        self.size -= size;
+      }
+
+      evicted.push(oldest);
+    }
+
+    self.size += size;
+    self.order.push_back(key.clone());
+    self.entries.insert(key, (value, size));
+
+    evicted
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn oldest_values_are_evicted() {
+    let mut cache = BoundedCache::new(6);
+
+    assert!(cache.insert("a", 'a', 3).is_empty());
+    assert!(cache.insert("b", 'b', 3).is_empty());
+    assert_eq!(cache.insert("c", 'c', 4), ["a", "b"]);
+
+    assert_eq!(cache.get(&"a"), None);
+    assert_eq!(cache.get(&"b"), None);
+    assert_eq!(cache.get(&"c"), Some(&'c'));
+  }
+
+  #[test]
+  fn oversized_values_are_not_cached() {
+    let mut cache = BoundedCache::new(6);
+
+    assert!(cache.insert("a", 'a', 3).is_empty());
+    assert!(cache.insert("b", 'b', 7).is_empty());
+
+    assert!(cache.contains(&"a"));
+    assert!(!cache.contains(&"b"));
+  }
+
+  #[test]
+  fn cached_keys_are_not_replaced() {
+    let mut cache = BoundedCache::new(6);
+
+    assert!(cache.insert("a", 'a', 3).is_empty());
+    assert!(cache.insert("a", 'b', 6).is_empty());
+
+    assert_eq!(cache.get(&"a"), Some(&'a'));
+  }
+}
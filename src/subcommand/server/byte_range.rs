@@ -0,0 +1,126 @@
+/// The part of a body requested by a `Range` header.
+#[derive(Debug, PartialEq)]
+pub(super) enum ByteRange {
+  Full,
+  Partial { start: usize, end: usize },
+  Unsatisfiable,
+}
+
+impl ByteRange {
+  /// Resolves `header` against a body of `len` bytes. Headers that are
+  /// malformed, use a unit other than bytes, or request multiple ranges are
+  /// ignored, and the full body is served, as permitted by RFC 9110.
+  pub(super) fn new(header: Option<&str>, len: usize) -> Self {
+    let Some(ranges) = header.and_then(|header| header.trim().strip_prefix("bytes=")) else {
+      return Self::Full;
+    };
+
+    if ranges.contains(',') {
+      return Self::Full;
+    }
+
+    let Some((start, end)) = ranges.trim().split_once('-') else {
+      return Self::Full;
+    };
+
+    if start.is_empty() {
+      let Ok(suffix) = end.parse::<usize>() else {
+        return Self::Full;
+      };
+
+      if suffix == 0 || len == 0 {
+        return Self::Unsatisfiable;
+      }
+
+      return Self::Partial {
+        start: len.saturating_sub(suffix),
+        end: len - 1,
+      };
+    }
+
+    let Ok(start) = start.parse::<usize>() else {
+      return Self::Full;
+    };
+
+    let end = if end.is_empty() {
+      usize::MAX
+    } else {
+      match end.parse::<usize>() {
+        Ok(end) if end >= start => end,
+        _ => return Self::Full,
+      }
+    };
+
+    if start >= len {
+      return Self::Unsatisfiable;
+    }
+
+    Self::Partial {
+      start,
+      end: end.min(len - 1),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn missing_or_unsupported_ranges_are_ignored() {
+    assert_eq!(ByteRange::new(None, 10), ByteRange::Full);
+    assert_eq!(ByteRange::new(Some("items=0-1"), 10), ByteRange::Full);
+    assert_eq!(ByteRange::new(Some("bytes=0-1,4-5"), 10), ByteRange::Full);
+    assert_eq!(ByteRange::new(Some("bytes=5-1"), 10), ByteRange::Full);
+    assert_eq!(ByteRange::new(Some("bytes=a-"), 10), ByteRange::Full);
+    assert_eq!(ByteRange::new(Some("bytes=0"), 10), ByteRange::Full);
+  }
+
+  #[test]
+  fn bounded_range() {
+    assert_eq!(
+      ByteRange::new(Some("bytes=2-5"), 10),
+      ByteRange::Partial { start: 2, end: 5 }
+    );
+    assert_eq!(
+      ByteRange::new(Some("bytes=2-100"), 10),
+      ByteRange::Partial { start: 2, end: 9 }
+    );
+  }
+
+  #[test]
+  fn open_range() {
+    assert_eq!(
+      ByteRange::new(Some("bytes=4-"), 10),
+      ByteRange::Partial { start: 4, end: 9 }
+    );
+  }
+
+  #[test]
+  fn suffix_range() {
+    assert_eq!(
+      ByteRange::new(Some("bytes=-3"), 10),
+      ByteRange::Partial { start: 7, end: 9 }
+    );
+    assert_eq!(
+      ByteRange::new(Some("bytes=-100"), 10),
+      ByteRange::Partial { start: 0, end: 9 }
+    );
+  }
+
+  #[test]
+  fn unsatisfiable_range() {
+    assert_eq!(
+      ByteRange::new(Some("bytes=10-"), 10),
+      ByteRange::Unsatisfiable
+    );
+    assert_eq!(
+      ByteRange::new(Some("bytes=-0"), 10),
+      ByteRange::Unsatisfiable
+    );
+    assert_eq!(
+      ByteRange::new(Some("bytes=-1"), 0),
+      ByteRange::Unsatisfiable
+    );
+  }
+}
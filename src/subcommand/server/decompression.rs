@@ -0,0 +1,190 @@
+use {
+  super::*,
+  brotli::Decompressor,
+  flate2::read::{GzDecoder, ZlibDecoder},
+};
+
+pub(crate) const SUPPORTED_ENCODINGS: &[&str] = &["br", "deflate", "gzip", "zstd"];
+
+const CACHE_CAPACITY: usize = 64 * 1024 * 1024;
+
+/// The supported encoding named by `content_encoding`. Content coding names
+/// are case-insensitive.
+pub(crate) fn supported_encoding(content_encoding: &HeaderValue) -> Option<&'static str> {
+  let name = content_encoding.to_str().ok()?.trim();
+
+  SUPPORTED_ENCODINGS
+    .iter()
+    .copied()
+    .find(|encoding| encoding.eq_ignore_ascii_case(name))
+}
+
+/// Decompresses `body` encoded with `encoding`, streaming the output and
+/// giving up once it would exceed `max_size` bytes or `max_ratio` times the
+/// size of `body`. Returns `None` if the encoding is unsupported or the limit
+/// is exceeded.
+pub(crate) fn decompress(
+  encoding: &str,
+  body: &[u8],
+  max_size: usize,
+  max_ratio: usize,
+) -> Result<Option<Vec<u8>>> {
+  let decoder: Box<dyn Read + '_> = match encoding {
+    "br" => Box::new(Decompressor::new(body, 4096)),
+    "deflate" => Box::new(ZlibDecoder::new(body)),
+    "gzip" => Box::new(GzDecoder::new(body)),
+    "zstd" => Box::new(zstd::stream::read::Decoder::new(body)?),
+    _ => return Ok(None),
+  };
+
+  let limit = max_size.min(body.len().saturating_mul(max_ratio));
+
+  let mut decompressed = Vec::new();
+
+  decoder
+    .take(u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1))
+    .read_to_end(&mut decompressed)?;
+
+  if decompressed.len() > limit {
+    return Ok(None);
+  }
+
+  Ok(Some(decompressed))
+}
+
+/// Decompressed inscription bodies, evicted oldest first once their total
+/// size exceeds the cache capacity.
+pub(crate) struct DecompressionCache(Mutex<BoundedCache<InscriptionId, Arc<Vec<u8>>>>);
+
+impl Default for DecompressionCache {
+  fn default() -> Self {
+    Self(Mutex::new(BoundedCache::new(CACHE_CAPACITY)))
+  }
+}
+
+impl DecompressionCache {
+  pub(crate) fn get(&self, inscription_id: InscriptionId) -> Option<Arc<Vec<u8>>> {
+    self.0.lock().unwrap().get(&inscription_id).cloned()
+  }
+
+  pub(crate) fn insert(&self, inscription_id: InscriptionId, body: Arc<Vec<u8>>) {
+    let size = body.len();
+    self.0.lock().unwrap().insert(inscription_id, body, size);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, std::io::Write};
+
+  fn compress(encoding: &str, body: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::new();
+
+    match encoding {
+      "br" => {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        writer.write_all(body).unwrap();
+      }
+      "deflate" => {
+        let mut writer =
+          flate2::write::ZlibEncoder::new(&mut compressed, flate2::Compression::best());
+        writer.write_all(body).unwrap();
+        writer.finish().unwrap();
+      }
+      "gzip" => {
+        let mut writer =
+          flate2::write::GzEncoder::new(&mut compressed, flate2::Compression::best());
+        writer.write_all(body).unwrap();
+        writer.finish().unwrap();
+      }
+      "zstd" => compressed = zstd::encode_all(body, 0).unwrap(),
+      _ => unreachable!(),
+    }
+
+    compressed
+  }
+
+  #[test]
+  fn supported_encodings_roundtrip() {
+    for encoding in SUPPORTED_ENCODINGS {
+      assert_eq!(
+        decompress(encoding, &compress(encoding, b"hello world"), 1024, 100).unwrap(),
+        Some(b"hello world".to_vec()),
+        "{encoding}",
+      );
+    }
+  }
+
+  #[test]
+  fn encoding_names_are_case_insensitive() {
+    assert_eq!(
+      supported_encoding(&HeaderValue::from_static("GZIP")),
+      Some("gzip")
+    );
+    assert_eq!(
+      supported_encoding(&HeaderValue::from_static(" Br ")),
+      Some("br")
+    );
+    assert_eq!(supported_encoding(&HeaderValue::from_static("bzip2")), None);
+  }
+
+  #[test]
+  fn unsupported_encoding() {
+    assert_eq!(decompress("bzip2", b"foo", 1024, 100).unwrap(), None);
+  }
+
+  #[test]
+  fn output_is_limited_by_size() {
+    let body = vec![0; 1000];
+
+    for encoding in SUPPORTED_ENCODINGS {
+      let compressed = compress(encoding, &body);
+      assert_eq!(
+        decompress(encoding, &compressed, 1000, 1000).unwrap(),
+        Some(body.clone())
+      );
+      assert_eq!(decompress(encoding, &compressed, 999, 1000).unwrap(), None);
+    }
+  }
+
+  #[test]
+  fn output_is_limited_by_ratio() {
+    let body = vec![0; 10_000];
+
+    for encoding in SUPPORTED_ENCODINGS {
+      let compressed = compress(encoding, &body);
+      assert_eq!(
+        decompress(encoding, &compressed, usize::MAX, 10_000).unwrap(),
+        Some(body.clone())
+      );
+      assert_eq!(
+        decompress(encoding, &compressed, usize::MAX, 2).unwrap(),
+        None
+      );
+    }
+  }
+
+  #[test]
+  fn cache_evicts_oldest_bodies() {
+    let cache = DecompressionCache::default();
+
+    cache.insert(inscription_id(1), Arc::new(vec![0; CACHE_CAPACITY / 2]));
+    cache.insert(inscription_id(2), Arc::new(vec![0; CACHE_CAPACITY / 2]));
+
+    assert!(cache.get(inscription_id(1)).is_some());
+    assert!(cache.get(inscription_id(2)).is_some());
+
+    cache.insert(inscription_id(3), Arc::new(vec![0; 1]));
+
+    assert!(cache.get(inscription_id(1)).is_none());
+    assert!(cache.get(inscription_id(2)).is_some());
+    assert!(cache.get(inscription_id(3)).is_some());
+  }
+
+  #[test]
+  fn cache_skips_oversized_bodies() {
+    let cache = DecompressionCache::default();
+    cache.insert(inscription_id(1), Arc::new(vec![0; CACHE_CAPACITY + 1]));
+    assert!(cache.get(inscription_id(1)).is_none());
+  }
+}
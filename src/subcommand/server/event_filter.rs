@@ -0,0 +1,107 @@
+use super::*;
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct EventQuery {
+  pub(crate) address: Option<Address<NetworkUnchecked>>,
+  pub(crate) inscription_id: Option<InscriptionId>,
+  pub(crate) kind: Option<String>,
+  pub(crate) rune_id: Option<RuneId>,
+}
+
+pub(super) struct EventFilter {
+  inscription_id: Option<InscriptionId>,
+  kinds: Option<HashSet<String>>,
+  rune_id: Option<RuneId>,
+  script_pubkey: Option<ScriptBuf>,
+}
+
+impl EventFilter {
+  pub(super) fn new(query: EventQuery, chain: Chain) -> ServerResult<Self> {
+    let kinds = query
+      .kind
+      .map(|kinds| {
+        kinds
+          .split(',')
+          .map(|kind| {
+            if Event::KINDS.contains(&kind) {
+              Ok(kind.to_string())
+            } else {
+              Err(ServerError::BadRequest(format!(
+                "unknown event kind `{kind}`"
+              )))
+            }
+          })
+          .collect::<ServerResult<HashSet<String>>>()
+      })
+      .transpose()?;
+
+    let script_pubkey = query
+      .address
+      .map(|address| {
+        address
+          .require_network(chain.network())
+          .map(|address| address.script_pubkey())
+          .map_err(|err| ServerError::BadRequest(err.to_string()))
+      })
+      .transpose()?;
+
+    Ok(Self {
+      inscription_id: query.inscription_id,
+      kinds,
+      rune_id: query.rune_id,
+      script_pubkey,
+    })
+  }
+
+  pub(super) fn matches(&self, index: &Index, event: &Event) -> Result<bool> {
+    if let Some(kinds) = &self.kinds {
+      if !kinds.contains(event.kind()) {
+        return Ok(false);
+      }
+    }
+
+    // rollbacks invalidate events regardless of what they refer to
+    if let Event::RolledBack { .. } = event {
+      return Ok(true);
+    }
+
+    if self.inscription_id.is_some() && event.inscription_id() != self.inscription_id {
+      return Ok(false);
+    }
+
+    if self.rune_id.is_some() && event.rune_id() != self.rune_id {
+      return Ok(false);
+    }
+
+    let Some(script_pubkey) = &self.script_pubkey else {
+      return Ok(true);
+    };
+
+    for outpoint in event.outpoints() {
+      if Index::is_special_outpoint(outpoint) {
+        continue;
+      }
+
+      // spent outputs have no UTXO entry, so fall back to the transaction
+      let matches = match index.get_script_pubkey_for_output(outpoint)? {
+        Some(output_script_pubkey) => output_script_pubkey == *script_pubkey,
+        None => index
+          .get_transaction(outpoint.txid)?
+          .and_then(|transaction| {
+            transaction
+              .output
+              .into_iter()
+              .nth(outpoint.vout.into_usize())
+          })
+          .is_some_and(|output| output.script_pubkey == *script_pubkey),
+      };
+
+      if matches {
+        return Ok(true);
+      }
+    }
+
+    Ok(false)
+  }
+}
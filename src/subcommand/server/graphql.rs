@@ -0,0 +1,620 @@
+use {
+  super::*,
+  async_graphql::{EmptyMutation, EmptySubscription, Object, SimpleObject},
+};
+
+const MAX_COMPLEXITY: usize = 10_000;
+const MAX_DEPTH: usize = 10;
+const PAGE_SIZE: usize = 100;
+
+pub(crate) type Schema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+
+type GraphQLResult<T> = async_graphql::Result<T>;
+
+pub(crate) fn schema(index: Arc<Index>, chain: Chain) -> Schema {
+  async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription)
+    .data(index)
+    .data(chain)
+    .limit_complexity(MAX_COMPLEXITY)
+    .limit_depth(MAX_DEPTH)
+    .finish()
+}
+
+fn index<'a>(ctx: &async_graphql::Context<'a>) -> &'a Arc<Index> {
+  ctx.data_unchecked::<Arc<Index>>()
+}
+
+fn chain(ctx: &async_graphql::Context) -> Chain {
+  *ctx.data_unchecked::<Chain>()
+}
+
+fn inscriptions(index: &Index, ids: Vec<InscriptionId>) -> GraphQLResult<Vec<InscriptionObject>> {
+  ids
+    .into_iter()
+    .filter_map(|id| InscriptionObject::load(index, query::Inscription::Id(id)).transpose())
+    .collect()
+}
+
+/// Page `page` of `items`, with up to `PAGE_SIZE` items.
+fn paginate<T>(items: &[T], page: usize) -> &[T] {
+  let start = page.saturating_mul(PAGE_SIZE).min(items.len());
+  &items[start..start.saturating_add(PAGE_SIZE).min(items.len())]
+}
+
+pub(crate) struct Query;
+
+#[Object]
+impl Query {
+  /// Inscription by id, number, or the name of the sat it is on.
+  async fn inscription(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    query: String,
+  ) -> GraphQLResult<Option<InscriptionObject>> {
+    InscriptionObject::load(index(ctx), query.parse()?)
+  }
+
+  /// Sat by number, name, decimal, degree or percentile.
+  async fn sat(&self, ctx: &async_graphql::Context<'_>, sat: String) -> GraphQLResult<SatObject> {
+    Ok(SatObject(Sat::parse(&sat, chain(ctx).network())?))
+  }
+
+  async fn output(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    outpoint: String,
+  ) -> GraphQLResult<Option<OutputObject>> {
+    OutputObject::load(index(ctx), outpoint.parse()?)
+  }
+
+  /// Requires an index built with `--index-addresses`.
+  async fn address(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    address: String,
+  ) -> GraphQLResult<AddressObject> {
+    let index = index(ctx);
+
+    if !index.has_address_index() {
+      return Err("this server has no address index".into());
+    }
+
+    let address = address
+      .parse::<Address<NetworkUnchecked>>()?
+      .require_network(chain(ctx).network())?;
+
+    let mut outputs = index.get_address_info(&address)?;
+
+    outputs.sort();
+
+    Ok(AddressObject {
+      address: address.to_string(),
+      outputs,
+    })
+  }
+
+  /// Block by height or hash.
+  async fn block(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    query: String,
+  ) -> GraphQLResult<Option<BlockObject>> {
+    let index = index(ctx);
+
+    let (block, height) = match query.parse()? {
+      query::Block::Height(height) => (index.get_block_by_height(height)?, height),
+      query::Block::Hash(hash) => match index.block_header_info(hash)? {
+        Some(info) => (
+          index.get_block_by_hash(hash)?,
+          u32::try_from(info.height).unwrap(),
+        ),
+        None => return Ok(None),
+      },
+    };
+
+    Ok(block.map(|block| BlockObject { block, height }))
+  }
+
+  /// Rune by name, id or number. Requires an index built with
+  /// `--index-runes`.
+  async fn rune(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    rune: String,
+  ) -> GraphQLResult<Option<RuneObject>> {
+    let index = index(ctx);
+
+    if !index.has_rune_index() {
+      return Err("this server has no rune index".into());
+    }
+
+    let rune = match rune.parse()? {
+      query::Rune::Spaced(spaced_rune) => Some(spaced_rune.rune),
+      query::Rune::Id(rune_id) => index.get_rune_by_id(rune_id)?,
+      query::Rune::Number(number) => index.get_rune_by_number(usize::try_from(number)?)?,
+    };
+
+    let Some(rune) = rune else {
+      return Ok(None);
+    };
+
+    Ok(
+      index
+        .rune(rune)?
+        .map(|(id, entry, parent)| RuneObject { entry, id, parent }),
+    )
+  }
+}
+
+pub(crate) struct InscriptionObject(api::Inscription);
+
+impl InscriptionObject {
+  fn load(index: &Index, query: query::Inscription) -> GraphQLResult<Option<Self>> {
+    Ok(
+      index
+        .inscription_info(query, None)?
+        .map(|(info, _output, _inscription)| Self(info)),
+    )
+  }
+}
+
+#[Object(name = "Inscription")]
+impl InscriptionObject {
+  async fn id(&self) -> String {
+    self.0.id.to_string()
+  }
+
+  async fn number(&self) -> i32 {
+    self.0.number
+  }
+
+  async fn address(&self) -> Option<&str> {
+    self.0.address.as_deref()
+  }
+
+  async fn charms(&self) -> Vec<String> {
+    self.0.charms.iter().map(Charm::to_string).collect()
+  }
+
+  async fn content_length(&self) -> Option<usize> {
+    self.0.content_length
+  }
+
+  async fn content_type(&self) -> Option<&str> {
+    self.0.content_type.as_deref()
+  }
+
+  async fn fee(&self) -> u64 {
+    self.0.fee
+  }
+
+  async fn height(&self) -> u32 {
+    self.0.height
+  }
+
+  async fn satpoint(&self) -> String {
+    self.0.satpoint.to_string()
+  }
+
+  async fn timestamp(&self) -> i64 {
+    self.0.timestamp
+  }
+
+  async fn value(&self) -> Option<u64> {
+    self.0.value
+  }
+
+  /// Page of up to 100 children.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn children(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<InscriptionObject>> {
+    let index = index(ctx);
+
+    let Some(entry) = index.get_inscription_entry(self.0.id)? else {
+      return Ok(Vec::new());
+    };
+
+    let (ids, _more) =
+      index.get_children_by_sequence_number_paginated(entry.sequence_number, PAGE_SIZE, page)?;
+
+    inscriptions(index, ids)
+  }
+
+  async fn output(&self, ctx: &async_graphql::Context<'_>) -> GraphQLResult<Option<OutputObject>> {
+    OutputObject::load(index(ctx), self.0.satpoint.outpoint)
+  }
+
+  /// Page of up to 100 parents.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn parents(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<InscriptionObject>> {
+    let index = index(ctx);
+
+    let Some(entry) = index.get_inscription_entry(self.0.id)? else {
+      return Ok(Vec::new());
+    };
+
+    let (ids, _more) = index.get_parents_by_sequence_number_paginated(entry.parents, page)?;
+
+    inscriptions(index, ids)
+  }
+
+  /// Rune etched in the inscription's reveal transaction.
+  async fn rune(&self, ctx: &async_graphql::Context<'_>) -> GraphQLResult<Option<RuneObject>> {
+    let Some(spaced_rune) = self.0.rune else {
+      return Ok(None);
+    };
+
+    Ok(
+      index(ctx)
+        .rune(spaced_rune.rune)?
+        .map(|(id, entry, parent)| RuneObject { entry, id, parent }),
+    )
+  }
+
+  /// Requires an index built with `--index-sats`.
+  async fn sat(&self) -> Option<SatObject> {
+    self.0.sat.map(SatObject)
+  }
+}
+
+pub(crate) struct SatObject(Sat);
+
+#[Object(name = "Sat")]
+impl SatObject {
+  async fn number(&self) -> u64 {
+    self.0.n()
+  }
+
+  async fn name(&self) -> String {
+    self.0.name()
+  }
+
+  async fn decimal(&self, ctx: &async_graphql::Context<'_>) -> String {
+    self.0.decimal(chain(ctx).network()).to_string()
+  }
+
+  async fn block(&self, ctx: &async_graphql::Context<'_>) -> u32 {
+    self.0.height(chain(ctx).network()).0
+  }
+
+  async fn charms(&self, ctx: &async_graphql::Context<'_>) -> Vec<String> {
+    Charm::charms(self.0.charms(chain(ctx).network()))
+      .iter()
+      .map(Charm::to_string)
+      .collect()
+  }
+
+  async fn rarity(&self, ctx: &async_graphql::Context<'_>) -> String {
+    self.0.rarity(chain(ctx).network()).to_string()
+  }
+
+  /// Page of up to 100 inscriptions. Requires an index built with
+  /// `--index-sats`.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn inscriptions(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<InscriptionObject>> {
+    let index = index(ctx);
+
+    let (ids, _more) =
+      index.get_inscription_ids_by_sat_paginated(self.0, PAGE_SIZE as u64, page as u64)?;
+
+    inscriptions(index, ids)
+  }
+}
+
+pub(crate) struct OutputObject {
+  outpoint: OutPoint,
+  output: api::Output,
+}
+
+impl OutputObject {
+  fn load(index: &Index, outpoint: OutPoint) -> GraphQLResult<Option<Self>> {
+    Ok(
+      index
+        .get_output_info(outpoint)?
+        .map(|(output, _txout)| Self { outpoint, output }),
+    )
+  }
+}
+
+#[Object(name = "Output")]
+impl OutputObject {
+  async fn outpoint(&self) -> String {
+    self.outpoint.to_string()
+  }
+
+  async fn address(&self) -> Option<String> {
+    self
+      .output
+      .address
+      .as_ref()
+      .map(|address| address.clone().assume_checked().to_string())
+  }
+
+  async fn script_pubkey(&self) -> String {
+    hex::encode(self.output.script_pubkey.as_bytes())
+  }
+
+  async fn spent(&self) -> bool {
+    self.output.spent
+  }
+
+  async fn value(&self) -> u64 {
+    self.output.value
+  }
+
+  /// Page of up to 100 inscriptions.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn inscriptions(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<InscriptionObject>> {
+    inscriptions(
+      index(ctx),
+      paginate(&self.output.inscriptions, page).to_vec(),
+    )
+  }
+
+  async fn runes(&self) -> Vec<RuneBalance> {
+    self
+      .output
+      .runes
+      .iter()
+      .map(|(spaced_rune, pile)| RuneBalance {
+        amount: Decimal {
+          value: pile.amount,
+          scale: pile.divisibility,
+        }
+        .to_string(),
+        rune: spaced_rune.to_string(),
+        symbol: pile.symbol.map(String::from),
+      })
+      .collect()
+  }
+}
+
+pub(crate) struct AddressObject {
+  address: String,
+  outputs: Vec<OutPoint>,
+}
+
+#[Object(name = "Address")]
+impl AddressObject {
+  async fn address(&self) -> &str {
+    &self.address
+  }
+
+  /// Page of up to 100 outputs.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn outputs(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<OutputObject>> {
+    let index = index(ctx);
+
+    Ok(
+      paginate(&self.outputs, page)
+        .iter()
+        .map(|outpoint| OutputObject::load(index, *outpoint))
+        .collect::<GraphQLResult<Vec<Option<OutputObject>>>>()?
+        .into_iter()
+        .flatten()
+        .collect(),
+    )
+  }
+
+  /// Page of up to 100 inscriptions.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn inscriptions(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<InscriptionObject>> {
+    let index = index(ctx);
+
+    let ids = index.get_inscriptions_for_outputs(&self.outputs)?;
+
+    inscriptions(index, paginate(&ids, page).to_vec())
+  }
+
+  async fn rune_balances(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+  ) -> GraphQLResult<Vec<RuneBalance>> {
+    Ok(
+      index(ctx)
+        .get_aggregated_rune_balances_for_outputs(&self.outputs)?
+        .into_iter()
+        .map(|(spaced_rune, amount, symbol)| RuneBalance {
+          amount: amount.to_string(),
+          rune: spaced_rune.to_string(),
+          symbol: symbol.map(String::from),
+        })
+        .collect(),
+    )
+  }
+
+  async fn sat_balance(&self, ctx: &async_graphql::Context<'_>) -> GraphQLResult<u64> {
+    Ok(index(ctx).get_sat_balances_for_outputs(&self.outputs)?)
+  }
+}
+
+pub(crate) struct BlockObject {
+  block: Block,
+  height: u32,
+}
+
+#[Object(name = "Block")]
+impl BlockObject {
+  async fn hash(&self) -> String {
+    self.block.header.block_hash().to_string()
+  }
+
+  async fn height(&self) -> u32 {
+    self.height
+  }
+
+  async fn timestamp(&self) -> u32 {
+    self.block.header.time
+  }
+
+  async fn transaction_count(&self) -> usize {
+    self.block.txdata.len()
+  }
+
+  /// Page of up to 100 inscriptions.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn inscriptions(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<InscriptionObject>> {
+    let index = index(ctx);
+
+    let ids = index.get_inscriptions_in_block(self.height)?;
+
+    inscriptions(index, paginate(&ids, page).to_vec())
+  }
+
+  /// Page of up to 100 runes.
+  #[graphql(complexity = "100 * child_complexity")]
+  async fn runes(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+    #[graphql(default)] page: usize,
+  ) -> GraphQLResult<Vec<RuneObject>> {
+    let index = index(ctx);
+
+    let spaced_runes = index.get_runes_in_block(self.height.into())?;
+
+    let mut runes = Vec::new();
+
+    for spaced_rune in paginate(&spaced_runes, page) {
+      if let Some((id, entry, parent)) = index.rune(spaced_rune.rune)? {
+        runes.push(RuneObject { entry, id, parent });
+      }
+    }
+
+    Ok(runes)
+  }
+}
+
+pub(crate) struct RuneObject {
+  entry: RuneEntry,
+  id: RuneId,
+  parent: Option<InscriptionId>,
+}
+
+#[Object(name = "Rune")]
+impl RuneObject {
+  async fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn name(&self) -> String {
+    self.entry.spaced_rune.to_string()
+  }
+
+  async fn number(&self) -> u64 {
+    self.entry.number
+  }
+
+  async fn block(&self) -> u64 {
+    self.entry.block
+  }
+
+  async fn burned(&self) -> String {
+    self.entry.pile(self.entry.burned).to_string()
+  }
+
+  async fn divisibility(&self) -> u8 {
+    self.entry.divisibility
+  }
+
+  async fn etching(&self) -> String {
+    self.entry.etching.to_string()
+  }
+
+  async fn mints(&self) -> String {
+    self.entry.mints.to_string()
+  }
+
+  async fn premine(&self) -> String {
+    self.entry.pile(self.entry.premine).to_string()
+  }
+
+  async fn symbol(&self) -> Option<String> {
+    self.entry.symbol.map(String::from)
+  }
+
+  async fn timestamp(&self) -> u64 {
+    self.entry.timestamp
+  }
+
+  async fn parent(
+    &self,
+    ctx: &async_graphql::Context<'_>,
+  ) -> GraphQLResult<Option<InscriptionObject>> {
+    match self.parent {
+      Some(parent) => InscriptionObject::load(index(ctx), query::Inscription::Id(parent)),
+      None => Ok(None),
+    }
+  }
+}
+
+/// Rune balance, with `amount` formatted according to the rune's
+/// divisibility.
+#[derive(SimpleObject)]
+pub(crate) struct RuneBalance {
+  amount: String,
+  rune: String,
+  symbol: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn schema_covers_index() {
+    let sdl = async_graphql::Schema::new(Query, EmptyMutation, EmptySubscription).sdl();
+
+    for ty in [
+      "Address",
+      "Block",
+      "Inscription",
+      "Output",
+      "Rune",
+      "RuneBalance",
+      "Sat",
+    ] {
+      assert!(sdl.contains(&format!("type {ty} {{")), "{ty}");
+    }
+
+    assert!(sdl.contains("children(page: Int! = 0): [Inscription!]!"));
+    assert!(sdl.contains("outputs(page: Int! = 0): [Output!]!"));
+    assert!(sdl.contains("runes(page: Int! = 0): [Rune!]!"));
+  }
+
+  #[test]
+  fn paginate() {
+    let items = (0..250).collect::<Vec<usize>>();
+
+    assert_eq!(super::paginate(&items, 0), &items[..100]);
+    assert_eq!(super::paginate(&items, 2), &items[200..]);
+    assert!(super::paginate(&items, 3).is_empty());
+    assert!(super::paginate(&items, usize::MAX).is_empty());
+  }
+}
@@ -0,0 +1,365 @@
+use {
+  super::*,
+  axum::routing::MethodRouter,
+  schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    JsonSchema,
+  },
+  serde_json::{json, Map, Value},
+};
+
+/// The description of a JSON endpoint in the OpenAPI spec, with the type of
+/// its request body, if any, and the type its handler serializes.
+pub(super) struct Endpoint {
+  summary: &'static str,
+  request: Option<fn(&mut SchemaGenerator) -> Schema>,
+  response: fn(&mut SchemaGenerator) -> Schema,
+}
+
+impl Endpoint {
+  pub(super) fn new<T: JsonSchema>(summary: &'static str) -> Self {
+    Self {
+      summary,
+      request: None,
+      response: SchemaGenerator::subschema_for::<T>,
+    }
+  }
+
+  pub(super) fn request<T: JsonSchema>(self) -> Self {
+    Self {
+      request: Some(SchemaGenerator::subschema_for::<T>),
+      ..self
+    }
+  }
+}
+
+/// A route registered by `Server::run`, with its description in the OpenAPI
+/// spec if it is part of the JSON API.
+pub(super) struct Route {
+  pub(super) method: &'static str,
+  pub(super) path: &'static str,
+  pub(super) handler: MethodRouter<Arc<ServerConfig>>,
+  pub(super) endpoint: Option<Endpoint>,
+}
+
+macro_rules! route {
+  ($method:ident $path:literal, $handler:expr) => {
+    openapi::Route {
+      method: stringify!($method),
+      path: $path,
+      handler: $method($handler),
+      endpoint: None,
+    }
+  };
+  ($method:ident $path:literal, $handler:expr, $summary:literal, $request:ty => $response:ty) => {
+    openapi::Route {
+      endpoint: Some(openapi::Endpoint::new::<$response>($summary).request::<$request>()),
+      ..route!($method $path, $handler)
+    }
+  };
+  ($method:ident $path:literal, $handler:expr, $summary:literal, $response:ty) => {
+    openapi::Route {
+      endpoint: Some(openapi::Endpoint::new::<$response>($summary)),
+      ..route!($method $path, $handler)
+    }
+  };
+}
+
+pub(super) use route;
+
+/// Routes which respond with JSON, but are not part of the REST API.
+#[cfg(test)]
+pub(super) const UNDESCRIBED: &[(&str, &str)] = &[("post", "/graphql"), ("get", "/openapi.json")];
+
+/// Converts an axum path like `/block/:query` to an OpenAPI path like
+/// `/block/{query}`.
+pub(super) fn path(route: &str) -> String {
+  route
+    .split('/')
+    .map(|segment| match segment.strip_prefix(':') {
+      Some(name) => format!("{{{name}}}"),
+      None => segment.into(),
+    })
+    .collect::<Vec<String>>()
+    .join("/")
+}
+
+/// OpenAPI 3 document describing the routes with an `Endpoint`, with
+/// schemas generated from the `api` types.
+pub(super) fn spec(routes: &[Route]) -> Value {
+  let mut generator = SchemaSettings::openapi3().into_generator();
+
+  let mut paths = Map::new();
+
+  for route in routes {
+    let Some(endpoint) = &route.endpoint else {
+      continue;
+    };
+
+    let schema = (endpoint.response)(&mut generator);
+
+    let parameters = route
+      .path
+      .split('/')
+      .filter_map(|segment| segment.strip_prefix(':'))
+      .map(|name| {
+        json!({
+          "name": name,
+          "in": "path",
+          "required": true,
+          "schema": { "type": "string" },
+        })
+      })
+      .collect::<Vec<Value>>();
+
+    let mut operation = json!({
+      "summary": endpoint.summary,
+      "parameters": parameters,
+      "responses": {
+        "200": {
+          "description": "OK",
+          "content": {
+            "application/json": {
+              "schema": schema,
+            },
+          },
+        },
+      },
+    });
+
+    if let Some(request) = endpoint.request {
+      operation["requestBody"] = json!({
+        "required": true,
+        "content": {
+          "application/json": {
+            "schema": request(&mut generator),
+          },
+        },
+      });
+    }
+
+    paths.entry(path(route.path)).or_insert_with(|| json!({}))[route.method] = operation;
+  }
+
+  json!({
+    "openapi": "3.0.3",
+    "info": {
+      "title": "ord",
+      "description": "Endpoints outside of `/r/` return JSON when requested with `Accept: application/json`.",
+      "version": env!("CARGO_PKG_VERSION"),
+    },
+    "paths": paths,
+    "components": {
+      "schemas": generator.take_definitions(),
+    },
+  })
+}
+
+/// Checks `value` against `schema`, resolving references in `spec`. Objects
+/// may not have properties the schema does not describe, so that fields
+/// added to a response without updating its type are caught.
+#[cfg(test)]
+pub(super) fn validate(spec: &Value, schema: &Value, value: &Value, pointer: &str) -> Result {
+  if schema == &Value::Bool(true) {
+    return Ok(());
+  }
+
+  if value.is_null() && schema["nullable"] == true {
+    return Ok(());
+  }
+
+  if let Some(reference) = schema["$ref"].as_str() {
+    let name = reference
+      .strip_prefix("#/components/schemas/")
+      .ok_or_else(|| anyhow!("unsupported reference `{reference}`"))?;
+
+    return validate(spec, &spec["components"]["schemas"][name], value, pointer);
+  }
+
+  if let Some(schemas) = schema["allOf"].as_array() {
+    for schema in schemas {
+      validate(spec, schema, value, pointer)?;
+    }
+  }
+
+  for key in ["anyOf", "oneOf"] {
+    if let Some(schemas) = schema[key].as_array() {
+      ensure!(
+        schemas
+          .iter()
+          .any(|schema| validate(spec, schema, value, pointer).is_ok()),
+        "{pointer}: {value} does not match any of {key}",
+      );
+    }
+  }
+
+  if let Some(variants) = schema["enum"].as_array() {
+    ensure!(
+      variants.contains(value),
+      "{pointer}: {value} is not one of {variants:?}",
+    );
+  }
+
+  if let Some(ty) = schema["type"].as_str() {
+    let matches = match ty {
+      "array" => value.is_array(),
+      "boolean" => value.is_boolean(),
+      "integer" => value.is_i64() || value.is_u64(),
+      "null" => value.is_null(),
+      "number" => value.is_number(),
+      "object" => value.is_object(),
+      "string" => value.is_string(),
+      _ => bail!("{pointer}: unsupported type `{ty}`"),
+    };
+
+    ensure!(matches, "{pointer}: {value} is not of type `{ty}`");
+  }
+
+  if let Some(object) = value.as_object() {
+    let properties = schema["properties"].as_object();
+
+    if let Some(required) = schema["required"].as_array() {
+      for key in required.iter().filter_map(Value::as_str) {
+        ensure!(
+          object.contains_key(key),
+          "{pointer}: missing property `{key}`"
+        );
+      }
+    }
+
+    for (key, value) in object {
+      let pointer = format!("{pointer}/{key}");
+
+      if let Some(schema) = properties.and_then(|properties| properties.get(key)) {
+        validate(spec, schema, value, &pointer)?;
+      } else if schema["additionalProperties"].is_object() {
+        validate(spec, &schema["additionalProperties"], value, &pointer)?;
+      } else {
+        ensure!(
+          properties.is_none() && schema["additionalProperties"] != false,
+          "{pointer}: property is not in schema",
+        );
+      }
+    }
+  }
+
+  if let Some(array) = value.as_array() {
+    match &schema["items"] {
+      Value::Array(items) => {
+        ensure!(
+          items.len() == array.len(),
+          "{pointer}: expected {} items, found {}",
+          items.len(),
+          array.len(),
+        );
+
+        for (i, (schema, value)) in items.iter().zip(array).enumerate() {
+          validate(spec, schema, value, &format!("{pointer}/{i}"))?;
+        }
+      }
+      Value::Object(_) => {
+        for (i, value) in array.iter().enumerate() {
+          validate(spec, &schema["items"], value, &format!("{pointer}/{i}"))?;
+        }
+      }
+      _ => {}
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_use_openapi_parameter_syntax() {
+    assert_eq!(path("/block/:query"), "/block/{query}");
+    assert_eq!(
+      path("/r/sat/:sat_number/at/:index"),
+      "/r/sat/{sat_number}/at/{index}"
+    );
+    assert_eq!(path("/inscriptions"), "/inscriptions");
+  }
+
+  #[test]
+  fn spec_describes_every_endpoint() {
+    let routes = Server::routes();
+
+    let spec = spec(&routes);
+
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert_eq!(
+      spec["paths"]
+        .as_object()
+        .unwrap()
+        .values()
+        .map(|operations| operations.as_object().unwrap().len())
+        .sum::<usize>(),
+      routes
+        .iter()
+        .filter(|route| route.endpoint.is_some())
+        .count()
+    );
+    assert!(spec["paths"]["/inscriptions"]["get"].is_object());
+    assert_eq!(
+      spec["paths"]["/inscriptions"]["post"]["requestBody"]["content"]["application/json"]
+        ["schema"]["type"],
+      "array"
+    );
+    assert_eq!(
+      spec["paths"]["/block/{query}"]["get"]["parameters"][0]["name"],
+      "query"
+    );
+    assert_eq!(
+      spec["paths"]["/block/{query}"]["get"]["responses"]["200"]["content"]["application/json"]
+        ["schema"]["$ref"],
+      "#/components/schemas/Block"
+    );
+    assert!(spec["components"]["schemas"]["Inscription"].is_object());
+  }
+
+  #[test]
+  fn validate_detects_divergence() {
+    let spec = spec(&Server::routes());
+    let schema = json!({ "$ref": "#/components/schemas/Inscriptions" });
+
+    let inscriptions = serde_json::to_value(api::Inscriptions {
+      ids: vec![inscription_id(1)],
+      more: false,
+      page_index: 0,
+    })
+    .unwrap();
+
+    validate(&spec, &schema, &inscriptions, "").unwrap();
+
+    let mut extra = inscriptions.clone();
+    extra["foo"] = json!(1);
+    assert_eq!(
+      validate(&spec, &schema, &extra, "")
+        .unwrap_err()
+        .to_string(),
+      "/foo: property is not in schema",
+    );
+
+    let mut missing = inscriptions.clone();
+    missing.as_object_mut().unwrap().remove("more");
+    assert_eq!(
+      validate(&spec, &schema, &missing, "")
+        .unwrap_err()
+        .to_string(),
+      ": missing property `more`",
+    );
+
+    let mut wrong = inscriptions;
+    wrong["page_index"] = json!("0");
+    assert_eq!(
+      validate(&spec, &schema, &wrong, "")
+        .unwrap_err()
+        .to_string(),
+      "/page_index: \"0\" is not of type `integer`",
+    );
+  }
+}
@@ -5,10 +5,17 @@ pub(crate) struct ServerConfig {
   pub(crate) chain: Chain,
   pub(crate) csp_origin: Option<String>,
   pub(crate) decompress: bool,
+  pub(crate) decompress_max_ratio: usize,
+  pub(crate) decompress_max_size: usize,
+  pub(crate) decompression_cache: DecompressionCache,
   pub(crate) domain: Option<String>,
+  pub(crate) event_sender: Option<broadcast::Sender<Event>>,
+  pub(crate) graphql: Option<graphql::Schema>,
   pub(crate) index_sats: bool,
   pub(crate) json_api_enabled: bool,
+  pub(crate) openapi: serde_json::Value,
   pub(crate) proxy: Option<Url>,
+  pub(crate) thumbnail_cache: ThumbnailCache,
 }
 
 impl ServerConfig {
@@ -0,0 +1,332 @@
+use {
+  super::*,
+  image::{imageops::FilterType, ImageFormat, ImageReader, Limits},
+  std::path::Path,
+  tokio::sync::Semaphore,
+};
+
+const CACHE_CAPACITY: usize = 1024 * 1024 * 1024;
+const MAX_ALLOC: u64 = 256 * 1024 * 1024;
+const MAX_DIMENSION: u32 = 8192;
+
+/// Thumbnails rendered at once, since decoding an image may allocate up to
+/// `MAX_ALLOC` bytes.
+const MAX_RENDERS: usize = 4;
+
+pub(super) const DEFAULT_SIZE: u32 = 256;
+pub(super) const MAX_SIZE: u32 = 1024;
+
+/// Thumbnail sizes that are rendered and cached, so that the number of
+/// thumbnails per inscription stays small.
+const SIZES: [u32; 5] = [64, 128, 256, 512, MAX_SIZE];
+
+#[derive(Deserialize)]
+pub(super) struct ThumbnailQuery {
+  pub(super) size: Option<u32>,
+}
+
+/// Decodes a raster image and scales it to fit within `size` by `size`
+/// pixels, encoded as PNG. Pixelated images are scaled with nearest-neighbor
+/// filtering and may be scaled up, other images are only ever scaled down.
+pub(super) fn render(body: &[u8], size: u32, image_rendering: ImageRendering) -> Result<Vec<u8>> {
+  let mut limits = Limits::default();
+  limits.max_alloc = Some(MAX_ALLOC);
+  limits.max_image_height = Some(MAX_DIMENSION);
+  limits.max_image_width = Some(MAX_DIMENSION);
+
+  let mut reader = ImageReader::new(Cursor::new(body)).with_guessed_format()?;
+  reader.limits(limits);
+
+  let image = reader.decode()?;
+
+  let image = match image_rendering {
+    ImageRendering::Pixelated => image.resize(size, size, FilterType::Nearest),
+    ImageRendering::Auto if image.width() > size || image.height() > size => {
+      image.resize(size, size, FilterType::Lanczos3)
+    }
+    ImageRendering::Auto => image,
+  };
+
+  let mut png = Vec::new();
+  image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+  Ok(png)
+}
+
+/// SVG placeholder naming the kind of media, for inscriptions that cannot be
+/// rendered as a raster thumbnail.
+pub(super) fn placeholder(media: Media, size: u32) -> String {
+  let label = match media {
+    Media::Audio => "audio".to_string(),
+    Media::Code(language) => language.to_string(),
+    Media::Font => "font".to_string(),
+    Media::Iframe => "html".to_string(),
+    Media::Image(_) => "image".to_string(),
+    Media::Markdown => "markdown".to_string(),
+    Media::Model => "model".to_string(),
+    Media::Pdf => "pdf".to_string(),
+    Media::Text => "text".to_string(),
+    Media::Unknown => "unknown".to_string(),
+    Media::Video => "video".to_string(),
+  };
+
+  format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 100 100\">\
+<rect width=\"100\" height=\"100\" fill=\"#131516\"/>\
+<text x=\"50\" y=\"50\" fill=\"#98a3ad\" font-family=\"sans-serif\" font-size=\"12\" text-anchor=\"middle\" dominant-baseline=\"middle\">{label}</text>\
+</svg>"
+  )
+}
+
+/// The smallest rendered size at least as large as `size`, or `None` if
+/// `size` is zero or too large.
+pub(super) fn bucket(size: u32) -> Option<u32> {
+  if size == 0 {
+    return None;
+  }
+
+  SIZES.into_iter().find(|bucket| *bucket >= size)
+}
+
+/// Where the thumbnail of `inscription_id` at `size` is cached.
+fn path(dir: &Path, inscription_id: InscriptionId, size: u32) -> PathBuf {
+  dir.join(format!("{inscription_id}-{size}.png"))
+}
+
+/// Thumbnails cached in a directory, evicted oldest first once their total
+/// size exceeds the cache capacity. Thumbnails left by earlier runs are
+/// accounted for when the first thumbnail is saved.
+pub(crate) struct ThumbnailCache {
+  capacity: usize,
+  files: Mutex<Option<BoundedCache<PathBuf, ()>>>,
+  renders: Semaphore,
+}
+
+impl Default for ThumbnailCache {
+  fn default() -> Self {
+    Self {
+      capacity: CACHE_CAPACITY,
+      files: default(),
+      renders: Semaphore::new(MAX_RENDERS),
+    }
+  }
+}
+
+impl ThumbnailCache {
+  pub(super) fn get(
+    &self,
+    dir: &Path,
+    inscription_id: InscriptionId,
+    size: u32,
+  ) -> Result<Option<Vec<u8>>> {
+    match fs::read(path(dir, inscription_id, size)) {
+      Ok(png) => Ok(Some(png)),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  /// Renders a thumbnail once fewer than `MAX_RENDERS` others are in
+  /// progress.
+  pub(super) fn render(
+    &self,
+    body: &[u8],
+    size: u32,
+    image_rendering: ImageRendering,
+  ) -> Result<Vec<u8>> {
+    let _permit = futures::executor::block_on(self.renders.acquire())?;
+    render(body, size, image_rendering)
+  }
+
+  /// Writes `png` through a temporary file, so concurrent readers never
+  /// observe a partially written thumbnail.
+  pub(super) fn insert(
+    &self,
+    dir: &Path,
+    inscription_id: InscriptionId,
+    size: u32,
+    png: &[u8],
+  ) -> Result {
+    let mut files = self.files.lock().unwrap();
+
+    let files = match files.as_mut() {
+      Some(files) => files,
+      None => files.insert(self.load(dir)?),
+    };
+
+    let path = path(dir, inscription_id, size);
+
+    if files.contains(&path) {
+      return Ok(());
+    }
+
+    let evicted = files.insert(path.clone(), (), png.len());
+
+    if !files.contains(&path) {
+      return Ok(());
+    }
+
+    fs::create_dir_all(dir)?;
+
+    let tmp = path.with_extension(format!("{}.tmp", process::id()));
+
+    fs::write(&tmp, png)?;
+    fs::rename(&tmp, &path)?;
+
+    Self::remove(&evicted)
+  }
+
+  /// Thumbnails already in `dir`, oldest first, removing those that do not
+  /// fit in the cache.
+  fn load(&self, dir: &Path) -> Result<BoundedCache<PathBuf, ()>> {
+    let mut files = BoundedCache::new(self.capacity);
+
+    let entries = match fs::read_dir(dir) {
+      Ok(entries) => entries,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(files),
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut thumbnails = Vec::new();
+
+    for entry in entries {
+      let entry = entry?;
+      let path = entry.path();
+
+      if path.extension().and_then(|extension| extension.to_str()) != Some("png") {
+        continue;
+      }
+
+      let metadata = entry.metadata()?;
+
+      thumbnails.push((metadata.modified()?, path, metadata.len()));
+    }
+
+    thumbnails.sort();
+
+    for (_modified, path, len) in thumbnails {
+      let len = usize::try_from(len).unwrap_or(usize::MAX);
+
+      let evicted = files.insert(path.clone(), (), len);
+
+      Self::remove(&evicted)?;
+
+      if !files.contains(&path) {
+        Self::remove(&[path])?;
+      }
+    }
+
+    Ok(files)
+  }
+
+  fn remove(paths: &[PathBuf]) -> Result {
+    for path in paths {
+      match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, image::RgbaImage};
+
+  fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut png = Vec::new();
+    RgbaImage::new(width, height)
+      .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+      .unwrap();
+    png
+  }
+
+  fn dimensions(png: &[u8]) -> (u32, u32) {
+    let image = image::load_from_memory(png).unwrap();
+    (image.width(), image.height())
+  }
+
+  #[test]
+  fn large_images_are_scaled_down_preserving_aspect_ratio() {
+    assert_eq!(
+      dimensions(&render(&png(400, 200), 100, ImageRendering::Auto).unwrap()),
+      (100, 50)
+    );
+    assert_eq!(
+      dimensions(&render(&png(400, 200), 100, ImageRendering::Pixelated).unwrap()),
+      (100, 50)
+    );
+  }
+
+  #[test]
+  fn only_pixelated_images_are_scaled_up() {
+    assert_eq!(
+      dimensions(&render(&png(10, 20), 100, ImageRendering::Auto).unwrap()),
+      (10, 20)
+    );
+    assert_eq!(
+      dimensions(&render(&png(10, 20), 100, ImageRendering::Pixelated).unwrap()),
+      (50, 100)
+    );
+  }
+
+  #[test]
+  fn invalid_images_are_errors() {
+    assert!(render(b"foo", 100, ImageRendering::Auto).is_err());
+  }
+
+  #[test]
+  fn placeholder_names_media() {
+    assert!(placeholder(Media::Video, 64).contains(">video</text>"));
+    assert!(placeholder(Media::Code(media::Language::Css), 64).contains(">css</text>"));
+    assert!(placeholder(Media::Unknown, 64).contains("width=\"64\" height=\"64\""));
+  }
+
+  #[test]
+  fn sizes_are_snapped_to_buckets() {
+    assert_eq!(bucket(0), None);
+    assert_eq!(bucket(1), Some(64));
+    assert_eq!(bucket(64), Some(64));
+    assert_eq!(bucket(100), Some(128));
+    assert_eq!(bucket(MAX_SIZE), Some(MAX_SIZE));
+    assert_eq!(bucket(MAX_SIZE + 1), None);
+  }
+
+  #[test]
+  fn saved_thumbnails_can_be_read() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let dir = tempdir.path().join("thumbnails");
+    let cache = ThumbnailCache::default();
+    assert_eq!(cache.get(&dir, inscription_id(1), 64).unwrap(), None);
+    cache.insert(&dir, inscription_id(1), 64, b"foo").unwrap();
+    assert_eq!(
+      cache.get(&dir, inscription_id(1), 64).unwrap(),
+      Some(b"foo".to_vec())
+    );
+  }
+
+  #[test]
+  fn cache_evicts_oldest_thumbnails() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let dir = tempdir.path();
+
+    fs::write(path(dir, inscription_id(1), 64), b"foo").unwrap();
+
+    let cache = ThumbnailCache {
+      capacity: 6,
+      ..default()
+    };
+
+    cache.insert(dir, inscription_id(2), 64, b"bar").unwrap();
+
+    assert!(cache.get(dir, inscription_id(1), 64).unwrap().is_some());
+
+    cache.insert(dir, inscription_id(3), 64, b"baz").unwrap();
+
+    assert_eq!(cache.get(dir, inscription_id(1), 64).unwrap(), None);
+    assert!(cache.get(dir, inscription_id(2), 64).unwrap().is_some());
+    assert!(cache.get(dir, inscription_id(3), 64).unwrap().is_some());
+  }
+}
@@ -0,0 +1,450 @@
+use {
+  super::*,
+  bellscoin::hashes::{hmac, sha256, HashEngine},
+};
+
+/// Delivery progress. Cursors are only resumed for the URL they were written
+/// for, so that a new endpoint receives every event.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Cursor {
+  hashes: Vec<(u32, BlockHash)>,
+  next_height: u32,
+  #[serde(default)]
+  url: String,
+}
+
+pub(crate) struct Webhook {
+  backoff: Duration,
+  client: reqwest::blocking::Client,
+  cursor: Cursor,
+  cursor_path: PathBuf,
+  secret: Option<String>,
+  url: Url,
+}
+
+impl Webhook {
+  pub(crate) const SIGNATURE_HEADER: &'static str = "X-Ord-Signature";
+
+  const MAX_BACKOFF: Duration = Duration::from_secs(60);
+  const PAGE_SIZE: usize = 1000;
+
+  pub(crate) fn new(url: Url, secret: Option<String>, cursor_path: PathBuf) -> Result<Self> {
+    let cursor = match fs::read(&cursor_path) {
+      Ok(cursor) => serde_json::from_slice::<Cursor>(&cursor)
+        .with_context(|| format!("failed to parse webhook cursor `{}`", cursor_path.display()))?,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Cursor::default(),
+      Err(err) => return Err(err.into()),
+    };
+
+    let cursor = if cursor.url == url.as_str() {
+      cursor
+    } else {
+      Cursor {
+        url: url.to_string(),
+        ..default()
+      }
+    };
+
+    Ok(Self {
+      backoff: Duration::from_secs(1),
+      client: reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?,
+      cursor,
+      cursor_path,
+      secret,
+      url,
+    })
+  }
+
+  pub(crate) fn run(mut self, index: &Index, polling_interval: Duration) {
+    while !SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+      match self.deliver(index) {
+        Ok(true) => continue,
+        Ok(false) => {}
+        Err(err) => log::warn!("Delivering webhook: {err}"),
+      }
+
+      thread::sleep(polling_interval);
+    }
+  }
+
+  pub(crate) fn signature(secret: &str, payload: &[u8]) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(payload);
+    hmac::Hmac::from_engine(engine).to_string()
+  }
+
+  /// Delivers logged events past the cursor, one batch per block, and
+  /// returns whether more events remain.
+  fn deliver(&mut self, index: &Index) -> Result<bool> {
+    self.check_for_rollback(index)?;
+
+    let block_count = index.block_count()?;
+
+    // one more than the deepest recoverable reorg, so that a common ancestor
+    // is always among the tracked blocks
+    let tracked_blocks = index.max_recoverable_reorg_depth().saturating_add(1);
+
+    let hashes = (block_count.saturating_sub(tracked_blocks)..block_count)
+      .map(|height| {
+        index
+          .block_hash(Some(height))?
+          .map(|hash| (height, hash))
+          .ok_or_else(|| anyhow!("missing block hash at height {height}"))
+      })
+      .collect::<Result<Vec<(u32, BlockHash)>>>()?;
+
+    let (events, more, next_height) = index.get_events(self.cursor.next_height, Self::PAGE_SIZE)?;
+
+    let next_height = next_height.min(block_count).max(self.cursor.next_height);
+
+    let mut batches = Vec::<api::EventBatch>::new();
+
+    for event in events {
+      let block_height = event.block_height();
+
+      // rollbacks are detected from block hashes, so that they are delivered
+      // even if they happen below the cursor
+      if block_height >= next_height || matches!(event, Event::RolledBack { .. }) {
+        continue;
+      }
+
+      match batches.last_mut() {
+        Some(batch) if batch.block_height == block_height => batch.events.push(event),
+        _ => batches.push(api::EventBatch {
+          block_height,
+          events: vec![event],
+        }),
+      }
+    }
+
+    for batch in batches {
+      self.post(&batch)?;
+      self.advance(batch.block_height + 1, &hashes, tracked_blocks)?;
+    }
+
+    self.advance(next_height, &hashes, tracked_blocks)?;
+
+    Ok(more)
+  }
+
+  fn check_for_rollback(&mut self, index: &Index) -> Result {
+    let Some(&(height, hash)) = self.cursor.hashes.last() else {
+      return Ok(());
+    };
+
+    if index.block_hash(Some(height))? == Some(hash) {
+      return Ok(());
+    }
+
+    // if no tracked block survived, receivers must resync from genesis
+    let mut fork = 0;
+
+    for &(height, hash) in self.cursor.hashes.iter().rev() {
+      if index.block_hash(Some(height))? == Some(hash) {
+        fork = height + 1;
+        break;
+      }
+    }
+
+    self.post(&api::EventBatch {
+      block_height: fork,
+      events: vec![Event::RolledBack {
+        block_height: fork,
+        depth: self.cursor.next_height - fork,
+      }],
+    })?;
+
+    self.cursor.hashes.retain(|(height, _hash)| *height < fork);
+    self.cursor.next_height = fork;
+
+    self.save()
+  }
+
+  fn advance(
+    &mut self,
+    next_height: u32,
+    hashes: &[(u32, BlockHash)],
+    tracked_blocks: u32,
+  ) -> Result {
+    let cursor = &mut self.cursor;
+
+    cursor.hashes.extend(
+      hashes
+        .iter()
+        .filter(|(height, _hash)| *height >= cursor.next_height && *height < next_height),
+    );
+
+    cursor
+      .hashes
+      .retain(|(height, _hash)| *height + tracked_blocks >= next_height);
+
+    cursor.next_height = next_height;
+
+    self.save()
+  }
+
+  fn post(&self, batch: &api::EventBatch) -> Result {
+    let payload = serde_json::to_vec(batch)?;
+
+    let mut backoff = self.backoff;
+
+    loop {
+      let mut request = self
+        .client
+        .post(self.url.clone())
+        .header(header::CONTENT_TYPE, "application/json");
+
+      if let Some(secret) = &self.secret {
+        request = request.header(
+          Self::SIGNATURE_HEADER,
+          format!("sha256={}", Self::signature(secret, &payload)),
+        );
+      }
+
+      let err = match request
+        .body(payload.clone())
+        .send()
+        .and_then(|response| response.error_for_status())
+      {
+        Ok(_) => return Ok(()),
+        Err(err) => err,
+      };
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        return Err(err.into());
+      }
+
+      log::warn!(
+        "Delivering events for block {} failed, retrying in {}: {err}",
+        batch.block_height,
+        humantime::format_duration(backoff),
+      );
+
+      thread::sleep(backoff);
+
+      backoff = (backoff * 2).min(Self::MAX_BACKOFF);
+    }
+  }
+
+  fn save(&self) -> Result {
+    let tmp = self.cursor_path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_vec(&self.cursor)?)?;
+    fs::rename(tmp, &self.cursor_path)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::index::testing::Context,
+    std::{
+      io::{BufRead, Read, Write},
+      net::TcpListener,
+      sync::mpsc::{channel, Receiver},
+    },
+  };
+
+  fn stand_in(statuses: Vec<u16>) -> (Url, Receiver<(String, Vec<u8>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let url = format!("http://{}/hook", listener.local_addr().unwrap())
+      .parse()
+      .unwrap();
+
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || {
+      for (stream, status) in listener.incoming().zip(statuses) {
+        let mut reader = io::BufReader::new(stream.unwrap());
+
+        let mut headers = String::new();
+        loop {
+          let mut line = String::new();
+          reader.read_line(&mut line).unwrap();
+          if line == "\r\n" {
+            break;
+          }
+          headers.push_str(&line.to_lowercase());
+        }
+
+        let content_length = headers
+          .lines()
+          .find_map(|line| line.strip_prefix("content-length: "))
+          .unwrap()
+          .trim()
+          .parse()
+          .unwrap();
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        reader
+          .get_mut()
+          .write_all(
+            format!("HTTP/1.1 {status} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+              .as_bytes(),
+          )
+          .unwrap();
+
+        sender.send((headers, body)).unwrap();
+      }
+    });
+
+    (url, receiver)
+  }
+
+  fn webhook(url: Url, context: &Context) -> Webhook {
+    let mut webhook = Webhook::new(
+      url,
+      Some("secret".into()),
+      context.tempdir.path().join("webhook-cursor.json"),
+    )
+    .unwrap();
+    webhook.backoff = Duration::from_millis(10);
+    webhook
+  }
+
+  fn batch(request: (String, Vec<u8>)) -> api::EventBatch {
+    let (headers, body) = request;
+
+    assert!(headers.contains(&format!(
+      "x-ord-signature: sha256={}\r\n",
+      Webhook::signature("secret", &body)
+    )));
+
+    serde_json::from_slice(&body).unwrap()
+  }
+
+  #[test]
+  fn signature() {
+    assert_eq!(
+      Webhook::signature("Jefe", b"what do ya want for nothing?"),
+      "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+    );
+  }
+
+  #[test]
+  fn batches_are_retried_signed_and_cursor_is_persisted() {
+    let context = Context::builder().arg("--index-events").build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let (url, requests) = stand_in(vec![500, 200]);
+
+    let mut webhook = webhook(url.clone(), &context);
+
+    assert!(!webhook.deliver(&context.index).unwrap());
+
+    let expected = api::EventBatch {
+      block_height: 2,
+      events: vec![Event::InscriptionCreated {
+        block_height: 2,
+        charms: 0,
+        inscription_id: InscriptionId { txid, index: 0 },
+        location: Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        }),
+        parent_inscription_ids: Vec::new(),
+        sequence_number: 0,
+      }],
+    };
+
+    assert_eq!(batch(requests.recv().unwrap()), expected);
+    assert_eq!(batch(requests.recv().unwrap()), expected);
+
+    let mut webhook = Webhook::new(url, None, webhook.cursor_path).unwrap();
+
+    assert_eq!(webhook.cursor.next_height, 3);
+
+    assert!(!webhook.deliver(&context.index).unwrap());
+
+    assert!(requests.try_recv().is_err());
+  }
+
+  #[test]
+  fn cursor_is_reset_when_url_changes() {
+    let context = Context::builder().arg("--index-events").build();
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let (url, requests) = stand_in(vec![200]);
+
+    let mut webhook = webhook(url, &context);
+
+    assert!(!webhook.deliver(&context.index).unwrap());
+
+    assert_eq!(batch(requests.recv().unwrap()).block_height, 2);
+
+    let (url, requests) = stand_in(vec![200]);
+
+    let mut webhook = Webhook::new(url, Some("secret".into()), webhook.cursor_path).unwrap();
+
+    assert_eq!(webhook.cursor.next_height, 0);
+
+    assert!(!webhook.deliver(&context.index).unwrap());
+
+    assert_eq!(batch(requests.recv().unwrap()).block_height, 2);
+  }
+
+  #[test]
+  fn rollbacks_are_delivered() {
+    let mut context = Context::builder().arg("--index-events").build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+    let (url, requests) = stand_in(vec![200, 200]);
+
+    let mut webhook = webhook(url, &context);
+
+    webhook.deliver(&context.index).unwrap();
+
+    assert_eq!(batch(requests.recv().unwrap()).block_height, 2);
+
+    assert_eq!(webhook.cursor.next_height, 3);
+
+    context.core.invalidate_tip();
+    context.mine_blocks_with_subsidy(2, 2 * COIN_VALUE);
+
+    webhook.deliver(&context.index).unwrap();
+
+    assert_eq!(
+      batch(requests.recv().unwrap()),
+      api::EventBatch {
+        block_height: 2,
+        events: vec![Event::RolledBack {
+          block_height: 2,
+          depth: 1,
+        }],
+      }
+    );
+
+    assert_eq!(webhook.cursor.next_height, 4);
+  }
+}
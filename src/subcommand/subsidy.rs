@@ -2,8 +2,29 @@ use super::*;
 
 #[derive(Debug, Parser)]
 pub(crate) struct Subsidy {
-  #[arg(help = "List sats in subsidy at <HEIGHT>.")]
-  height: Height,
+  #[arg(
+    help = "List sats in subsidy at <HEIGHT>.",
+    required_unless_present = "verify",
+    conflicts_with = "verify"
+  )]
+  height: Option<Height>,
+  #[arg(
+    long,
+    help = "Verify that each block's coinbase claims exactly its subsidy plus fees."
+  )]
+  verify: bool,
+  #[arg(
+    long,
+    requires = "verify",
+    help = "Verify blocks from <FROM_HEIGHT>. [default: 0]"
+  )]
+  from_height: Option<u32>,
+  #[arg(
+    long,
+    requires = "verify",
+    help = "Verify blocks up to and including <TO_HEIGHT>. [default: chain tip]"
+  )]
+  to_height: Option<u32>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -13,14 +34,36 @@ pub struct Output {
   pub name: String,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct VerifyOutput {
+  pub from_height: u32,
+  pub to_height: u32,
+  pub mismatches: Vec<Mismatch>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mismatch {
+  pub height: u32,
+  pub subsidy: u64,
+  pub fees: u64,
+  pub expected: u64,
+  pub coinbase: u64,
+}
+
 impl Subsidy {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
-    let first = self.height.starting_sat();
+    if self.verify {
+      return self.verify(settings);
+    }
+
+    let height = self.height.unwrap();
 
-    let subsidy = self.height.subsidy(settings.chain().network());
+    let first = height.starting_sat(settings.chain().network());
+
+    let subsidy = height.subsidy(settings.chain().network());
 
     if subsidy == 0 {
-      bail!("block {} has no subsidy", self.height);
+      bail!("block {} has no subsidy", height);
     }
 
     Ok(Some(Box::new(Output {
@@ -29,4 +72,69 @@ impl Subsidy {
       name: first.name(),
     })))
   }
+
+  fn verify(self, settings: Settings) -> SubcommandResult {
+    let client = settings.bitcoin_rpc_client(None)?;
+
+    let network = settings.chain().network();
+
+    let from_height = self.from_height.unwrap_or_default();
+
+    let to_height = match self.to_height {
+      Some(to_height) => to_height,
+      None => client.get_block_count()?.try_into()?,
+    };
+
+    ensure!(
+      from_height <= to_height,
+      "--from-height {from_height} is greater than --to-height {to_height}"
+    );
+
+    let mut mismatches = Vec::new();
+    let mut verified = from_height;
+
+    for height in from_height..=to_height {
+      let block = client.get_block(&client.get_block_hash(height.into())?)?;
+
+      let coinbase = block
+        .txdata
+        .first()
+        .ok_or_else(|| anyhow!("block {height} has no coinbase transaction"))?
+        .output
+        .iter()
+        .map(|txout| txout.value)
+        .sum::<u64>();
+
+      // the genesis block has no undo data, so the node can't report its fees
+      let fees = if height == 0 {
+        0
+      } else {
+        client.get_block_stats(height.into())?.total_fee.to_sat()
+      };
+
+      let subsidy = Height(height).subsidy(network);
+
+      if coinbase != subsidy + fees {
+        mismatches.push(Mismatch {
+          height,
+          subsidy,
+          fees,
+          expected: subsidy + fees,
+          coinbase,
+        });
+      }
+
+      verified = height;
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+
+    Ok(Some(Box::new(VerifyOutput {
+      from_height,
+      to_height: verified,
+      mismatches,
+    })))
+  }
 }
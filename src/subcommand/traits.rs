@@ -24,11 +24,11 @@ impl Traits {
       number: self.sat.n(),
       decimal: self.sat.decimal(settings.chain().network()).to_string(),
       name: self.sat.name(),
-      height: self.sat.height().0,
+      height: self.sat.height(settings.chain().network()).0,
       cycle: self.sat.cycle(),
       epoch: self.sat.epoch().0,
       offset: self.sat.third(settings.chain().network()),
-      rarity: self.sat.rarity(),
+      rarity: self.sat.rarity(settings.chain().network()),
     })))
   }
 }
@@ -263,4 +263,31 @@ inscriptions:
       .to_string()
       .contains("unknown field `unknown`"));
   }
+
+  #[test]
+  fn partial_batch_requires_exactly_one_inscription() {
+    let tempdir = TempDir::new().unwrap();
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      "mode: partial\ninscriptions:\n- file: meow.wav\n- file: woof.wav",
+    )
+    .unwrap();
+
+    assert_eq!(
+      batch::File::load(&batch_path).unwrap_err().to_string(),
+      "`partial` mode batchfiles must contain exactly one inscription",
+    );
+
+    fs::write(
+      &batch_path,
+      "mode: partial\ninscriptions:\n- file: meow.wav",
+    )
+    .unwrap();
+
+    assert_eq!(
+      batch::File::load(&batch_path).unwrap().mode,
+      batch::Mode::Partial
+    );
+  }
 }
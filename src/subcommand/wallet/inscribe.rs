@@ -33,6 +33,11 @@ pub(crate) struct Inscribe {
   pub(crate) json_metadata: Option<PathBuf>,
   #[clap(long, help = "Set inscription metaprotocol to <METAPROTOCOL>.")]
   pub(crate) metaprotocol: Option<String>,
+  #[arg(
+    long,
+    help = "Inscribe using <MODE>, either `separate-outputs` or `partial`. Partial inscriptions are split across a chain of P2SH scriptSigs. [default: separate-outputs]"
+  )]
+  pub(crate) mode: Option<batch::Mode>,
   #[clap(long, help = "Make inscription a child of <PARENT>.")]
   pub(crate) parent: Option<InscriptionId>,
   #[arg(
@@ -52,6 +57,13 @@ impl Inscribe {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
     let chain = wallet.chain();
 
+    let mode = self.mode.unwrap_or_default();
+
+    ensure!(
+      matches!(mode, batch::Mode::SeparateOutputs | batch::Mode::Partial),
+      "`ord wallet inscribe` only supports `separate-outputs` and `partial` modes, use `ord wallet batch` for other modes"
+    );
+
     if let Some(delegate) = self.delegate {
       ensure! {
         wallet.inscription_exists(delegate)?,
@@ -78,7 +90,7 @@ impl Inscribe {
         None,
         None,
       )?],
-      mode: batch::Mode::SeparateOutputs,
+      mode,
       no_backup: self.shared.no_backup,
       no_limit: self.shared.no_limit,
       parent_info: wallet.get_parent_info(self.parent.as_slice())?,
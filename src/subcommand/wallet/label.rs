@@ -53,7 +53,7 @@ pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
         first_sat: SatLabel {
           name: sat.name(),
           number: sat.n(),
-          rarity: sat.rarity(),
+          rarity: sat.rarity(wallet.chain().network()),
         },
         inscriptions,
       })?,
@@ -3,6 +3,7 @@ use {super::*, crate::wallet::Maturity};
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ResumeOutput {
   pub etchings: Vec<batch::Output>,
+  pub partials: Vec<batch::Output>,
 }
 #[derive(Debug, Parser)]
 pub(crate) struct Resume {
@@ -14,6 +15,22 @@ pub(crate) struct Resume {
 
 impl Resume {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let mut partials = Vec::new();
+
+    if self.rune.is_none() {
+      for (inscription_id, entry) in wallet.pending_partials()? {
+        if self.dry_run {
+          partials.push(batch::Output {
+            reveal_broadcast: false,
+            ..entry.output
+          });
+          continue;
+        }
+
+        partials.push(wallet.send_partial(inscription_id, entry)?);
+      }
+    }
+
     let mut etchings = Vec::new();
     loop {
       if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
@@ -69,6 +86,8 @@ impl Resume {
       }
     }
 
-    Ok(Some(Box::new(ResumeOutput { etchings }) as Box<dyn Output>))
+    Ok(Some(
+      Box::new(ResumeOutput { etchings, partials }) as Box<dyn Output>
+    ))
   }
 }
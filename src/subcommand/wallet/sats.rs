@@ -62,7 +62,7 @@ impl Sats {
       let tsv = fs::read_to_string(path)
         .with_context(|| format!("I/O error reading `{}`", path.display()))?;
 
-      let needles = Self::needles(&tsv)?;
+      let needles = Self::needles(&tsv, wallet.chain().network())?;
 
       let found = Self::find(&needles, &haystacks);
 
@@ -75,7 +75,7 @@ impl Sats {
       Ok(Some(Box::new(OutputTsv { found, lost })))
     } else {
       let mut output = Vec::new();
-      for (outpoint, sat, offset, rarity) in Self::rare_sats(haystacks) {
+      for (outpoint, sat, offset, rarity) in Self::rare_sats(haystacks, wallet.chain().network()) {
         output.push(OutputRare {
           sat,
           output: outpoint,
@@ -131,19 +131,21 @@ impl Sats {
     results
   }
 
-  fn needles(tsv: &str) -> Result<Vec<(Sat, &str)>> {
+  fn needles(tsv: &str, network: Network) -> Result<Vec<(Sat, &str)>> {
     let mut needles = tsv
       .lines()
       .enumerate()
       .filter(|(_i, line)| !line.starts_with('#') && !line.is_empty())
       .filter_map(|(i, line)| {
         line.split('\t').next().map(|value| {
-          Sat::from_str(value).map(|sat| (sat, value)).map_err(|err| {
-            anyhow!(
-              "failed to parse sat from string \"{value}\" on line {}: {err}",
-              i + 1,
-            )
-          })
+          Sat::parse(value, network)
+            .map(|sat| (sat, value))
+            .map_err(|err| {
+              anyhow!(
+                "failed to parse sat from string \"{value}\" on line {}: {err}",
+                i + 1,
+              )
+            })
         })
       })
       .collect::<Result<Vec<(Sat, &str)>>>()?;
@@ -153,14 +155,17 @@ impl Sats {
     Ok(needles)
   }
 
-  fn rare_sats(haystacks: Vec<(OutPoint, Vec<(u64, u64)>)>) -> Vec<(OutPoint, Sat, u64, Rarity)> {
+  fn rare_sats(
+    haystacks: Vec<(OutPoint, Vec<(u64, u64)>)>,
+    network: Network,
+  ) -> Vec<(OutPoint, Sat, u64, Rarity)> {
     haystacks
       .into_iter()
       .flat_map(|(outpoint, sat_ranges)| {
         let mut offset = 0;
         sat_ranges.into_iter().filter_map(move |(start, end)| {
           let sat = Sat(start);
-          let rarity = sat.rarity();
+          let rarity = sat.rarity(network);
           let start_offset = offset;
           offset += end - start;
           if rarity > Rarity::Common {
@@ -181,10 +186,13 @@ mod tests {
   #[test]
   fn identify_no_rare_sats() {
     assert_eq!(
-      Sats::rare_sats(vec![(
-        outpoint(1),
-        vec![(51 * COIN_VALUE, 100 * COIN_VALUE), (1234, 5678)],
-      )]),
+      Sats::rare_sats(
+        vec![(
+          outpoint(1),
+          vec![(51 * COIN_VALUE, 100 * COIN_VALUE), (1234, 5678)],
+        )],
+        Network::Bellscoin
+      ),
       Vec::new()
     )
   }
@@ -192,10 +200,13 @@ mod tests {
   #[test]
   fn identify_one_rare_sat() {
     assert_eq!(
-      Sats::rare_sats(vec![(
-        outpoint(1),
-        vec![(10, 80), (50 * COIN_VALUE, 100 * COIN_VALUE)],
-      )]),
+      Sats::rare_sats(
+        vec![(
+          outpoint(1),
+          vec![(10, 80), (50 * COIN_VALUE, 100 * COIN_VALUE)],
+        )],
+        Network::Bellscoin
+      ),
       vec![(outpoint(1), Sat(50 * COIN_VALUE), 70, Rarity::Uncommon)]
     )
   }
@@ -203,10 +214,13 @@ mod tests {
   #[test]
   fn identify_two_rare_sats() {
     assert_eq!(
-      Sats::rare_sats(vec![(
-        outpoint(1),
-        vec![(0, 100), (1050000000000000, 1150000000000000)],
-      )]),
+      Sats::rare_sats(
+        vec![(
+          outpoint(1),
+          vec![(0, 100), (1050000000000000, 1150000000000000)],
+        )],
+        Network::Bellscoin
+      ),
       vec![
         (outpoint(1), Sat(0), 0, Rarity::Mythic),
         (outpoint(1), Sat(1050000000000000), 100, Rarity::Epic)
@@ -217,10 +231,13 @@ mod tests {
   #[test]
   fn identify_rare_sats_in_different_outpoints() {
     assert_eq!(
-      Sats::rare_sats(vec![
-        (outpoint(1), vec![(50 * COIN_VALUE, 55 * COIN_VALUE)]),
-        (outpoint(2), vec![(100 * COIN_VALUE, 111 * COIN_VALUE)],),
-      ]),
+      Sats::rare_sats(
+        vec![
+          (outpoint(1), vec![(50 * COIN_VALUE, 55 * COIN_VALUE)]),
+          (outpoint(2), vec![(100 * COIN_VALUE, 111 * COIN_VALUE)],),
+        ],
+        Network::Bellscoin
+      ),
       vec![
         (outpoint(1), Sat(50 * COIN_VALUE), 0, Rarity::Uncommon),
         (outpoint(2), Sat(100 * COIN_VALUE), 0, Rarity::Uncommon)
@@ -231,7 +248,7 @@ mod tests {
   #[track_caller]
   fn case(tsv: &str, haystacks: &[(OutPoint, Vec<(u64, u64)>)], expected: &[(&str, SatPoint)]) {
     assert_eq!(
-      Sats::find(&Sats::needles(tsv).unwrap(), haystacks),
+      Sats::find(&Sats::needles(tsv, Network::Bellscoin).unwrap(), haystacks),
       expected
         .iter()
         .map(|(sat, satpoint)| (sat.to_string(), *satpoint))
@@ -328,7 +345,7 @@ mod tests {
   #[test]
   fn parse_error_reports_line_and_value() {
     assert_eq!(
-      Sats::needles("0\n===\n")
+      Sats::needles("0\n===\n", Network::Bellscoin)
         .unwrap_err()
         .to_string(),
       "failed to parse sat from string \"===\" on line 2: failed to parse sat `===`: invalid integer: invalid digit found in string",
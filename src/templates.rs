@@ -3,6 +3,7 @@ use {super::*, boilerplate::Boilerplate};
 pub(crate) use {
   crate::subcommand::server::ServerConfig,
   address::AddressHtml,
+  address_history::AddressHistoryHtml,
   block::BlockHtml,
   children::ChildrenHtml,
   collections::CollectionsHtml,
@@ -10,8 +11,11 @@ pub(crate) use {
   iframe::Iframe,
   input::InputHtml,
   inscription::InscriptionHtml,
+  inscription_history::InscriptionHistoryHtml,
   inscriptions::InscriptionsHtml,
   inscriptions_block::InscriptionsBlockHtml,
+  inscriptions_hash::InscriptionsHashHtml,
+  inscriptions_search::InscriptionsSearchHtml,
   metadata::MetadataHtml,
   output::OutputHtml,
   parents::ParentsHtml,
@@ -24,11 +28,12 @@ pub(crate) use {
 };
 
 pub use {
-  blocks::BlocksHtml, rune::RuneHtml, runes::RunesHtml, status::StatusHtml,
-  transaction::TransactionHtml,
+  blocks::BlocksHtml, rune::RuneHtml, rune_holders::RuneHoldersHtml, runes::RunesHtml,
+  status::StatusHtml, transaction::TransactionHtml,
 };
 
 pub mod address;
+mod address_history;
 pub mod block;
 pub mod blocks;
 mod children;
@@ -37,14 +42,18 @@ mod home;
 mod iframe;
 mod input;
 pub mod inscription;
+mod inscription_history;
 pub mod inscriptions;
 mod inscriptions_block;
+mod inscriptions_hash;
+mod inscriptions_search;
 mod metadata;
 pub mod output;
 mod parents;
 mod preview;
 mod rare;
 pub mod rune;
+pub mod rune_holders;
 pub mod runes;
 pub mod sat;
 pub mod status;
@@ -0,0 +1,92 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct AddressHistoryHtml {
+  pub(crate) address: Address,
+  pub(crate) transactions: Vec<api::AddressTransaction>,
+  pub(crate) prev_page: Option<usize>,
+  pub(crate) next_page: Option<usize>,
+}
+
+impl PageContent for AddressHistoryHtml {
+  fn title(&self) -> String {
+    format!("Address {} History", self.address)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn address() -> Address {
+    Chain::Mainnet
+      .address_from_script(&ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap()
+  }
+
+  #[test]
+  fn empty() {
+    assert_regex_match!(
+      AddressHistoryHtml {
+        address: address(),
+        transactions: Vec::new(),
+        prev_page: None,
+        next_page: None,
+      },
+      "<h1><a href=/address/.*>Address .*</a> History</h1>
+<h3>No transactions</h3>
+"
+    );
+  }
+
+  #[test]
+  fn transactions() {
+    assert_regex_match!(
+      AddressHistoryHtml {
+        address: address(),
+        transactions: vec![api::AddressTransaction {
+          height: 7,
+          inscriptions: vec![inscription_id(1)],
+          received: 100,
+          runes: vec![(
+            SpacedRune {
+              rune: Rune(26),
+              spacers: 1,
+            },
+            Pile {
+              amount: 11,
+              divisibility: 1,
+              symbol: Some('%'),
+            },
+          )]
+          .into_iter()
+          .collect(),
+          sent: 50,
+          txid: txid(2),
+        }],
+        prev_page: Some(0),
+        next_page: Some(2),
+      },
+      "
+        <h1>.*</h1>
+        <dl>
+          <dt>transaction</dt><dd><a class=monospace href=/tx/2{64}>2{64}</a></dd>
+          <dt>block</dt><dd><a href=/block/7>7</a></dd>
+          <dt>received</dt><dd>100</dd>
+          <dt>sent</dt><dd>50</dd>
+          <dt>inscriptions</dt>
+          <dd class=thumbnails>
+            <a href=/inscription/1{64}i1>.*</a>
+          </dd>
+          <dt>runes</dt>
+          <dd><a class=monospace href=/rune/A•A>A•A</a>: 1.1\u{A0}%</dd>
+        </dl>
+        <div class=center>
+          <a class=prev href=/address/.*/history/0>prev</a>
+          <a class=next href=/address/.*/history/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -2,6 +2,7 @@ use super::*;
 
 #[derive(Boilerplate)]
 pub(crate) struct BlockHtml {
+  auxpow: Option<api::AuxPow>,
   best_height: Height,
   block: Block,
   featured_inscriptions: Vec<InscriptionId>,
@@ -22,6 +23,7 @@ impl BlockHtml {
     runes: Vec<SpacedRune>,
   ) -> Self {
     Self {
+      auxpow: block.header.auxpow.clone().map(api::AuxPow::new),
       hash: block.header.block_hash(),
       target: target_as_block_hash(block.header.target()),
       block,
@@ -111,6 +113,49 @@ mod tests {
     );
   }
 
+  #[test]
+  fn auxpow() {
+    let mut block = Chain::Mainnet.genesis_block();
+
+    let parent = bellscoin::block::HeaderWithoutAuxPow {
+      version: block.header.version,
+      prev_blockhash: block.header.prev_blockhash,
+      merkle_root: block.header.merkle_root,
+      time: block.header.time,
+      bits: block.header.bits,
+      nonce: block.header.nonce,
+    };
+
+    let coinbase = block.txdata[0].clone();
+
+    block.header.version = bellscoin::block::Version::from_consensus(1 << 8);
+    block.header.auxpow = Some(bellscoin::block::AuxPow {
+      coinbase_tx: coinbase.clone(),
+      block_hash: BlockHash::all_zeros(),
+      coinbase_branch: Vec::new(),
+      n_index: 0,
+      blockchain_branch: vec![TxMerkleNode::all_zeros()],
+      chain_index: 3,
+      parent_block_hash: parent,
+    });
+
+    assert_regex_match!(
+      BlockHtml::new(block, Height(1), Height(1), 0, Vec::new(), Vec::new()),
+      format!(
+        ".*
+  <dt>parent block hash</dt><dd class=monospace>{}</dd>
+  <dt>parent coinbase</dt><dd class=monospace>{}</dd>
+  <dt>chain index</dt><dd>3</dd>
+  <dt>chain merkle branch</dt>
+  <dd class=monospace>{}</dd>
+</dl>.*",
+        Chain::Mainnet.genesis_block().block_hash(),
+        coinbase.txid(),
+        TxMerkleNode::all_zeros(),
+      ),
+    );
+  }
+
   #[test]
   fn block_hash_serializes_as_hex_string() {
     assert_eq!(
@@ -5,6 +5,7 @@ pub(crate) struct InscriptionHtml {
   pub(crate) chain: Chain,
   pub(crate) charms: u16,
   pub(crate) children: Vec<InscriptionId>,
+  pub(crate) duplicate_of: Option<InscriptionId>,
   pub(crate) fee: u64,
   pub(crate) height: u32,
   pub(crate) inscription: Inscription,
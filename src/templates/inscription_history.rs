@@ -0,0 +1,83 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct InscriptionHistoryHtml {
+  pub(crate) id: InscriptionId,
+  pub(crate) transfers: Vec<api::InscriptionTransfer>,
+  pub(crate) prev_page: Option<usize>,
+  pub(crate) next_page: Option<usize>,
+}
+
+impl PageContent for InscriptionHistoryHtml {
+  fn title(&self) -> String {
+    format!("Inscription {} History", self.id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty() {
+    assert_regex_match!(
+      InscriptionHistoryHtml {
+        id: inscription_id(1),
+        transfers: Vec::new(),
+        prev_page: None,
+        next_page: None,
+      },
+      "<h1><a href=/inscription/1{64}i1>Inscription 1{64}i1</a> History</h1>
+<h3>No transfers</h3>
+"
+    );
+  }
+
+  #[test]
+  fn transfers() {
+    assert_regex_match!(
+      InscriptionHistoryHtml {
+        id: inscription_id(1),
+        transfers: vec![
+          api::InscriptionTransfer {
+            address: Some("foo".into()),
+            height: 7,
+            new_satpoint: satpoint(3, 0),
+            old_satpoint: satpoint(2, 5),
+            txid: txid(3),
+          },
+          api::InscriptionTransfer {
+            address: None,
+            height: 8,
+            new_satpoint: satpoint(4, 0),
+            old_satpoint: satpoint(3, 0),
+            txid: txid(4),
+          },
+        ],
+        prev_page: Some(0),
+        next_page: Some(2),
+      },
+      "
+        <h1>.*</h1>
+        <dl>
+          <dt>transaction</dt><dd><a class=monospace href=/tx/3{64}>3{64}</a></dd>
+          <dt>block</dt><dd><a href=/block/7>7</a></dd>
+          <dt>from</dt><dd><a class=monospace href=/satpoint/2{64}:2:5>2{64}:2:5</a></dd>
+          <dt>to</dt><dd><a class=monospace href=/satpoint/3{64}:3:0>3{64}:3:0</a></dd>
+          <dt>address</dt><dd><a class=monospace href=/address/foo>foo</a></dd>
+        </dl>
+        <dl>
+          <dt>transaction</dt><dd><a class=monospace href=/tx/4{64}>4{64}</a></dd>
+          <dt>block</dt><dd><a href=/block/8>8</a></dd>
+          <dt>from</dt><dd><a class=monospace href=/satpoint/3{64}:3:0>3{64}:3:0</a></dd>
+          <dt>to</dt><dd><a class=monospace href=/satpoint/4{64}:4:0>4{64}:4:0</a></dd>
+        </dl>
+        <div class=center>
+          <a class=prev href=/inscription/1{64}i1/history/0>prev</a>
+          <a class=next href=/inscription/1{64}i1/history/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
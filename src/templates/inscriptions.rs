@@ -2,6 +2,7 @@ use super::*;
 
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionsHtml {
+  pub(crate) filter: InscriptionFilter,
   pub(crate) inscriptions: Vec<InscriptionId>,
   pub(crate) prev: Option<u32>,
   pub(crate) next: Option<u32>,
@@ -21,6 +22,7 @@ mod tests {
   fn without_prev_and_next() {
     assert_regex_match!(
       InscriptionsHtml {
+        filter: InscriptionFilter::default(),
         inscriptions: vec![inscription_id(1), inscription_id(2)],
         prev: None,
         next: None,
@@ -44,6 +46,7 @@ mod tests {
   fn with_prev_and_next() {
     assert_regex_match!(
       InscriptionsHtml {
+        filter: InscriptionFilter::default(),
         inscriptions: vec![inscription_id(1), inscription_id(2)],
         prev: Some(1),
         next: Some(2),
@@ -62,4 +65,28 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn with_filter() {
+    assert_regex_match!(
+      InscriptionsHtml {
+        filter: InscriptionFilter {
+          charm: Some(Charm::Cursed),
+          content_type: Some("text/plain".into()),
+          ..default()
+        },
+        inscriptions: vec![inscription_id(1)],
+        prev: Some(1),
+        next: Some(2),
+      },
+      "
+        <h1>All Inscriptions</h1>
+        .*
+        <a class=prev href=/inscriptions/1\\?charm=cursed&amp;content_type=text%2Fplain>prev</a>
+        <a class=next href=/inscriptions/2\\?charm=cursed&amp;content_type=text%2Fplain>next</a>
+        .*
+      "
+      .unindent()
+    );
+  }
 }
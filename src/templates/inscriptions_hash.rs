@@ -0,0 +1,44 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct InscriptionsHashHtml {
+  pub(crate) hash: sha256::Hash,
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) prev: Option<usize>,
+  pub(crate) next: Option<usize>,
+}
+
+impl PageContent for InscriptionsHashHtml {
+  fn title(&self) -> String {
+    format!("Inscriptions with Content {}", self.hash)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      InscriptionsHashHtml {
+        hash: sha256::Hash::all_zeros(),
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        prev: Some(1),
+        next: Some(3),
+      },
+      "
+        <h1>Inscriptions with Content <span class=monospace>0{64}</span></h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        .*
+        <a class=prev href=/inscriptions/hash/0{64}/1>prev</a>
+        <a class=next href=/inscriptions/hash/0{64}/3>next</a>
+        .*
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct InscriptionsSearchHtml {
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) next: Option<usize>,
+  pub(crate) prev: Option<usize>,
+  pub(crate) query: String,
+}
+
+impl InscriptionsSearchHtml {
+  fn encoded_query(&self) -> String {
+    urlencoding::encode(&self.query).into_owned()
+  }
+}
+
+impl PageContent for InscriptionsSearchHtml {
+  fn title(&self) -> String {
+    format!("Inscriptions Matching {}", self.query)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_results() {
+    assert_regex_match!(
+      InscriptionsSearchHtml {
+        inscriptions: Vec::new(),
+        next: None,
+        prev: None,
+        query: "foo".into(),
+      },
+      "
+        <h1>Inscriptions Matching foo</h1>
+        <h3>No inscriptions</h3>
+        <div class=center>
+        prev
+        next
+        </div>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      InscriptionsSearchHtml {
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        next: Some(3),
+        prev: Some(1),
+        query: "foo bar".into(),
+      },
+      "
+        <h1>Inscriptions Matching foo bar</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <div class=center>
+        <a class=prev href=/inscriptions/search/1\\?query=foo%20bar>prev</a>
+        <a class=next href=/inscriptions/search/3\\?query=foo%20bar>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
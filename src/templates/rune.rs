@@ -1,26 +1,69 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq)]
 pub struct RuneHtml {
+  pub activity: Vec<api::RuneBlockActivity>,
+  pub activity_start: u32,
   pub entry: RuneEntry,
   pub id: RuneId,
   pub mintable: bool,
   pub parent: Option<InscriptionId>,
 }
 
+impl RuneHtml {
+  /// Points of a polyline plotting supply against height, from
+  /// `activity_start` to the last block with activity, in a 100 by 20 view
+  /// box.
+  fn supply_chart(&self) -> Option<String> {
+    let last = self.activity.last()?;
+
+    let start = f64::from(self.activity_start);
+    let span = (f64::from(last.height) - start).max(1.0);
+    let max = self.entry.max_supply().max(1) as f64;
+
+    let point = |height: f64, supply: u128| {
+      format!(
+        "{:.2},{:.2}",
+        (height - start) / span * 100.0,
+        20.0 - supply as f64 / max * 20.0
+      )
+    };
+
+    // `activity` may not reach back to the etching, so work back from the
+    // current supply
+    let mut supply = self.entry.supply().saturating_sub(
+      self
+        .activity
+        .iter()
+        .map(|activity| activity.minted)
+        .sum::<u128>(),
+    );
+
+    let mut points = vec![point(start, supply)];
+
+    for activity in &self.activity {
+      supply = supply.saturating_add(activity.minted);
+      points.push(point(activity.height.into(), supply));
+    }
+
+    Some(points.join(" "))
+  }
+}
+
 impl PageContent for RuneHtml {
   fn title(&self) -> String {
     format!("Rune {}", self.entry.spaced_rune)
   }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RuneOutputJson {
+  #[schemars(with = "String")]
   pub(crate) dune: SpacedRune,
+  #[schemars(with = "crate::api::schema::Pile")]
   pub(crate) balances: Pile,
 }
 
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -29,6 +72,8 @@ mod tests {
   fn display() {
     assert_regex_match!(
       RuneHtml {
+        activity: Vec::new(),
+        activity_start: 0,
         entry: RuneEntry {
           block: 1,
           burned: 123456789123456789,
@@ -100,6 +145,8 @@ mod tests {
   <dd>0.12%</dd>
   <dt>burned</dt>
   <dd>123456789.123456789\u{A0}%</dd>
+  <dt>holders</dt>
+  <dd><a href=/rune/B•CGDENLQRQWDSLRUGSNLBTMFIJAV/holders>holders</a></dd>
   <dt>divisibility</dt>
   <dd>9</dd>
   <dt>symbol</dt>
@@ -119,6 +166,8 @@ mod tests {
   fn display_no_mint() {
     assert_regex_match!(
       RuneHtml {
+        activity: Vec::new(),
+        activity_start: 0,
         entry: RuneEntry {
           block: 0,
           burned: 123456789123456789,
@@ -153,6 +202,8 @@ mod tests {
   fn display_no_turbo() {
     assert_regex_match!(
       RuneHtml {
+        activity: Vec::new(),
+        activity_start: 0,
         entry: RuneEntry {
           block: 0,
           burned: 123456789123456789,
@@ -187,6 +238,8 @@ mod tests {
   fn display_empty_mint() {
     assert_regex_match!(
       RuneHtml {
+        activity: Vec::new(),
+        activity_start: 0,
         entry: RuneEntry {
           block: 0,
           burned: 123456789123456789,
@@ -238,4 +291,69 @@ mod tests {
 "
     );
   }
+
+  #[test]
+  fn supply_chart() {
+    assert_regex_match!(
+      RuneHtml {
+        activity: vec![api::RuneBlockActivity {
+          burned: 0,
+          height: 12,
+          minted: 50,
+          mints: 1,
+          transferred: 0,
+        }],
+        activity_start: 10,
+        entry: RuneEntry {
+          block: 10,
+          mints: 1,
+          premine: 50,
+          terms: Some(Terms {
+            amount: Some(50),
+            cap: Some(2),
+            ..default()
+          }),
+          ..default()
+        },
+        id: RuneId { block: 10, tx: 1 },
+        mintable: true,
+        parent: None,
+      },
+      ".*
+  <dt>supply chart</dt>
+  <dd><svg class=chart viewBox=\"0 0 100 20\" preserveAspectRatio=none><polyline points=\"0.00,13.33 100.00,6.67\"/></svg></dd>
+.*"
+    );
+  }
+
+  #[test]
+  fn supply_chart_starts_at_activity_start() {
+    assert_regex_match!(
+      RuneHtml {
+        activity: vec![api::RuneBlockActivity {
+          burned: 0,
+          height: 2000,
+          minted: 50,
+          mints: 1,
+          transferred: 0,
+        }],
+        activity_start: 1500,
+        entry: RuneEntry {
+          block: 10,
+          mints: 2,
+          premine: 50,
+          terms: Some(Terms {
+            amount: Some(50),
+            cap: Some(3),
+            ..default()
+          }),
+          ..default()
+        },
+        id: RuneId { block: 10, tx: 1 },
+        mintable: true,
+        parent: None,
+      },
+      ".*<polyline points=\"0.00,10.00 100.00,5.00\"/>.*"
+    );
+  }
 }
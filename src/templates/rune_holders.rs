@@ -0,0 +1,107 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RuneHoldersHtml {
+  pub addresses: Option<u64>,
+  #[schemars(with = "Vec<(api::RuneHolder, api::schema::Pile)>")]
+  pub holders: Vec<(api::RuneHolder, Pile)>,
+  #[schemars(with = "String")]
+  pub id: RuneId,
+  pub more: bool,
+  pub next: Option<usize>,
+  pub outputs: u64,
+  pub prev: Option<usize>,
+  #[schemars(with = "String")]
+  pub rune: SpacedRune,
+}
+
+impl PageContent for RuneHoldersHtml {
+  fn title(&self) -> String {
+    format!("Rune {} Holders", self.rune)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty() {
+    assert_regex_match!(
+      RuneHoldersHtml {
+        addresses: None,
+        holders: Vec::new(),
+        id: RuneId { block: 1, tx: 0 },
+        more: false,
+        next: None,
+        outputs: 0,
+        prev: None,
+        rune: SpacedRune {
+          rune: Rune(26),
+          spacers: 1
+        },
+      },
+      "<h1><a href=/rune/A•A>A•A</a> Holders</h1>
+<dl>
+  <dt>outputs</dt>
+  <dd>0</dd>
+</dl>
+<h3>No holders</h3>
+"
+    );
+  }
+
+  #[test]
+  fn holders() {
+    let pile = |amount| Pile {
+      amount,
+      divisibility: 1,
+      symbol: Some('%'),
+    };
+
+    assert_regex_match!(
+      RuneHoldersHtml {
+        addresses: Some(3),
+        holders: vec![
+          (api::RuneHolder::Address("foo".into()), pile(300)),
+          (api::RuneHolder::Output(outpoint(1)), pile(20)),
+          (
+            api::RuneHolder::ScriptPubkey(ScriptBuf::from_bytes(vec![0x51])),
+            pile(1)
+          ),
+        ],
+        id: RuneId { block: 1, tx: 0 },
+        more: true,
+        next: Some(2),
+        outputs: 4,
+        prev: Some(0),
+        rune: SpacedRune {
+          rune: Rune(26),
+          spacers: 0
+        },
+      },
+      "
+        <h1><a href=/rune/AA>AA</a> Holders</h1>
+        <dl>
+          <dt>outputs</dt>
+          <dd>4</dd>
+          <dt>addresses</dt>
+          <dd>3</dd>
+        </dl>
+        <dl>
+          <dt><a class=monospace href=/address/foo>foo</a></dt>
+          <dd>30\u{A0}%</dd>
+          <dt><a class=monospace href=/output/1{64}:1>1{64}:1</a></dt>
+          <dd>2\u{A0}%</dd>
+          <dt><span class=monospace>OP_PUSHNUM_1</span></dt>
+          <dd>0.1\u{A0}%</dd>
+        </dl>
+        <div class=center>
+          <a class=prev href=/rune/AA/holders/0>prev</a>
+          <a class=next href=/rune/AA/holders/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -1,7 +1,8 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RunesHtml {
+  #[schemars(with = "Vec<(String, RuneEntry)>")]
   pub entries: Vec<(RuneId, RuneEntry)>,
   pub more: bool,
   pub prev: Option<usize>,
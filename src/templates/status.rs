@@ -1,23 +1,28 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct StatusHtml {
   pub address_index: bool,
   pub blessed_inscriptions: u64,
+  #[schemars(with = "String")]
   pub chain: Chain,
   pub cursed_inscriptions: u64,
   pub height: Option<u32>,
+  #[schemars(with = "api::schema::Duration")]
   pub initial_sync_time: Duration,
   pub inscription_index: bool,
   pub inscriptions: u64,
   pub lost_sats: u64,
+  #[schemars(with = "String")]
   pub minimum_rune_for_next_block: Rune,
   pub rune_index: bool,
   pub runes: u64,
   pub sat_index: bool,
+  #[schemars(with = "String")]
   pub started: DateTime<Utc>,
   pub transaction_index: bool,
   pub unrecoverably_reorged: bool,
+  #[schemars(with = "api::schema::Duration")]
   pub uptime: Duration,
 }
 
@@ -1,11 +1,15 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct TransactionHtml {
+  #[schemars(with = "String")]
   pub chain: Chain,
+  #[schemars(with = "Option<String>")]
   pub etching: Option<SpacedRune>,
   pub inscription_count: u32,
+  #[schemars(with = "serde_json::Value")]
   pub transaction: Transaction,
+  #[schemars(with = "String")]
   pub txid: Txid,
 }
 
@@ -9,12 +9,15 @@ use {
     psbt::Psbt,
   },
   bellscoincore_rpc::bellscoincore_rpc_json::{ImportDescriptors, Timestamp},
-  entry::{EtchingEntry, EtchingEntryValue},
+  entry::{EtchingEntry, EtchingEntryValue, PartialEntry, PartialEntryValue},
   fee_rate::FeeRate,
-  index::entry::Entry,
+  index::entry::{Entry, InscriptionIdValue},
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
-  redb::{Database, DatabaseError, ReadableTable, RepairSession, StorageError, TableDefinition},
+  redb::{
+    Database, DatabaseError, ReadableTable, RepairSession, StorageError, TableDefinition,
+    TableError,
+  },
   reqwest::header,
   std::sync::Once,
   transaction_builder::TransactionBuilder,
@@ -27,6 +30,7 @@ pub mod wallet_constructor;
 
 const SCHEMA_VERSION: u64 = 1;
 
+define_table! { INSCRIPTION_ID_TO_PARTIAL, InscriptionIdValue, PartialEntryValue }
 define_table! { RUNE_TO_ETCHING, u128, EtchingEntryValue }
 define_table! { STATISTICS, u64, u64 }
 
@@ -667,6 +671,7 @@ impl Wallet {
 
         let tx = database.begin_write()?;
 
+        tx.open_table(INSCRIPTION_ID_TO_PARTIAL)?;
         tx.open_table(RUNE_TO_ETCHING)?;
 
         tx.open_table(STATISTICS)?
@@ -682,6 +687,48 @@ impl Wallet {
     Ok(database)
   }
 
+  pub(crate) fn send_partial(
+    &self,
+    inscription_id: InscriptionId,
+    mut entry: PartialEntry,
+  ) -> Result<batch::Output> {
+    let total = entry.transactions.len();
+
+    while usize::try_from(entry.broadcast).unwrap() < total {
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        eprintln!("Suspending partial inscription. Run `ord wallet resume` to continue.");
+        return Ok(entry.output);
+      }
+
+      let tx = &entry.transactions[usize::try_from(entry.broadcast).unwrap()];
+
+      if let Err(err) = self.bitcoin_client().send_raw_transaction(tx) {
+        // the transaction may have been broadcast before progress was saved
+        if self
+          .bitcoin_client()
+          .get_tx_out(&tx.txid(), 0, Some(true))?
+          .is_none()
+        {
+          bail!(
+            "Failed to send partial inscription transaction {} of {total}: {err}\nRun `ord wallet resume` to retry",
+            entry.broadcast + 1,
+          );
+        }
+      }
+
+      entry.broadcast += 1;
+
+      self.save_partial(inscription_id, &entry)?;
+    }
+
+    self.clear_partial(inscription_id)?;
+
+    Ok(batch::Output {
+      reveal_broadcast: true,
+      ..entry.output
+    })
+  }
+
   pub(crate) fn save_etching(
     &self,
     rune: &Rune,
@@ -740,6 +787,54 @@ impl Wallet {
     )
   }
 
+  pub(crate) fn save_partial(&self, inscription_id: InscriptionId, entry: &PartialEntry) -> Result {
+    let wtx = self.database.begin_write()?;
+
+    wtx
+      .open_table(INSCRIPTION_ID_TO_PARTIAL)?
+      .insert(&inscription_id.store(), entry.clone().store())?;
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  pub(crate) fn clear_partial(&self, inscription_id: InscriptionId) -> Result {
+    let wtx = self.database.begin_write()?;
+
+    wtx
+      .open_table(INSCRIPTION_ID_TO_PARTIAL)?
+      .remove(&inscription_id.store())?;
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  pub(crate) fn pending_partials(&self) -> Result<Vec<(InscriptionId, PartialEntry)>> {
+    let rtx = self.database.begin_read()?;
+
+    // wallets created before partial inscriptions were supported lack the table
+    let table = match rtx.open_table(INSCRIPTION_ID_TO_PARTIAL) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+      Err(err) => return Err(err.into()),
+    };
+
+    Ok(
+      table
+        .iter()?
+        .map(|result| {
+          result.map(|(key, value)| {
+            (
+              InscriptionId::load(key.value()),
+              PartialEntry::load(value.value()),
+            )
+          })
+        })
+        .collect::<Result<Vec<(InscriptionId, PartialEntry)>, StorageError>>()?,
+    )
+  }
+
   pub(super) fn sign_transaction(
     &self,
     unsigned_transaction: Transaction,
@@ -26,6 +26,7 @@ pub mod entry;
 mod etching;
 pub mod file;
 pub mod mode;
+mod partial;
 pub mod plan;
 mod range;
 mod terms;
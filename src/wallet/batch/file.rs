@@ -77,6 +77,23 @@ impl File {
       );
     }
 
+    if batchfile.mode == Mode::Partial {
+      ensure!(
+        batchfile.inscriptions.len() == 1,
+        "`partial` mode batchfiles must contain exactly one inscription"
+      );
+
+      ensure!(
+        batchfile.parents.is_empty(),
+        "`parents` cannot be set in `partial` mode"
+      );
+
+      ensure!(
+        batchfile.etching.is_none(),
+        "`etching` cannot be set in `partial` mode"
+      );
+    }
+
     if batchfile.mode == Mode::SatPoints {
       ensure!(
         batchfile.postage.is_none(),
@@ -139,7 +156,7 @@ impl File {
         entry.metaprotocol.clone(),
         self.parents.clone(),
         entry.file.clone(),
-        Some(pointer),
+        (self.mode != Mode::Partial).then_some(pointer),
         self
           .etching
           .and_then(|etch| (i == 0).then_some(etch.rune.rune)),
@@ -176,7 +193,7 @@ impl File {
 
     let destinations = match self.mode {
       Mode::SharedOutput | Mode::SameSat => vec![wallet.get_change_address()?],
-      Mode::SeparateOutputs | Mode::SatPoints => self
+      Mode::Partial | Mode::SeparateOutputs | Mode::SatPoints => self
         .inscriptions
         .iter()
         .map(|entry| {
@@ -1,10 +1,13 @@
 use super::*;
 
-#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize, Default)]
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize, Default, clap::ValueEnum)]
 pub enum Mode {
+  #[serde(rename = "partial")]
+  Partial,
   #[serde(rename = "same-sat")]
   SameSat,
   #[serde(rename = "satpoints")]
+  #[value(name = "satpoints")]
   SatPoints,
   #[default]
   #[serde(rename = "separate-outputs")]
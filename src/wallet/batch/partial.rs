@@ -0,0 +1,681 @@
+use {
+  super::*,
+  bellscoin::{
+    ecdsa,
+    script::PushBytes,
+    secp256k1::{Message, SecretKey},
+    sighash::EcdsaSighashType,
+    PublicKey,
+  },
+  std::iter,
+};
+
+/// The commit transaction and the chain of transactions that reveal a partial
+/// inscription. Each transaction in the chain spends output zero of the
+/// previous one, revealing as many pieces of the body in its P2SH scriptSig as
+/// fit under the standardness limit.
+#[derive(Debug)]
+pub(crate) struct PartialTransactions {
+  pub(crate) chain: Vec<Transaction>,
+  pub(crate) commit_tx: Transaction,
+  pub(crate) total_fees: u64,
+}
+
+impl PartialTransactions {
+  pub(crate) const MAX_CHUNK_SIZE: usize = 240;
+  pub(crate) const MAX_SCRIPT_SIG_SIZE: usize = 1650;
+
+  // DER signatures are at most 72 bytes, plus the sighash type
+  const DUMMY_SIGNATURE: [u8; 73] = [0; 73];
+
+  fn chunks(body: &[u8]) -> Result<Vec<Vec<u8>>> {
+    if body.is_empty() {
+      return Ok(vec![Vec::new()]);
+    }
+
+    let mut chunks = body
+      .chunks(Self::MAX_CHUNK_SIZE)
+      .map(<[u8]>::to_vec)
+      .collect::<Vec<Vec<u8>>>();
+
+    // a lone 0x81 must be pushed with OP_1NEGATE, which the parser does not
+    // recognize, so borrow a byte from the previous chunk
+    if chunks.last().unwrap() == &[0x81] {
+      ensure!(
+        chunks.len() > 1,
+        "partial inscription body cannot be the single byte 0x81"
+      );
+
+      let penultimate = chunks.len() - 2;
+      let byte = chunks[penultimate].pop().unwrap();
+      chunks.last_mut().unwrap().insert(0, byte);
+    }
+
+    Ok(chunks)
+  }
+
+  fn push_data(builder: script::Builder, data: &[u8]) -> script::Builder {
+    match data {
+      [n @ 1..=16] => builder.push_int((*n).into()),
+      _ => builder.push_slice(<&PushBytes>::try_from(data).unwrap()),
+    }
+  }
+
+  fn redeem_script(public_key: &PublicKey, pushes: usize) -> ScriptBuf {
+    let mut builder = script::Builder::new()
+      .push_key(public_key)
+      .push_opcode(opcodes::all::OP_CHECKSIGVERIFY);
+
+    for _ in 0..pushes {
+      builder = builder.push_opcode(opcodes::all::OP_DROP);
+    }
+
+    builder.push_opcode(opcodes::OP_TRUE).into_script()
+  }
+
+  fn script_sig(data: &[u8], signature: &[u8], redeem_script: &Script) -> ScriptBuf {
+    let mut script_sig = data.to_vec();
+
+    script_sig.extend_from_slice(
+      script::Builder::new()
+        .push_slice(<&PushBytes>::try_from(signature).unwrap())
+        .push_slice(<&PushBytes>::try_from(redeem_script.as_bytes()).unwrap())
+        .as_bytes(),
+    );
+
+    ScriptBuf::from(script_sig)
+  }
+
+  /// Splits the pushes that make up a partial inscription into groups, one per
+  /// chain transaction, returning each group's pushes and their count.
+  fn groups(inscription: &Inscription, public_key: &PublicKey) -> Result<Vec<(Vec<u8>, usize)>> {
+    let content_type = inscription.content_type.as_deref().unwrap_or_default();
+
+    let chunks = Self::chunks(inscription.body.as_deref().unwrap_or_default())?;
+
+    let mut data = Self::push_data(
+      script::Builder::new()
+        .push_slice(PROTOCOL_ID)
+        .push_int(chunks.len().try_into().unwrap()),
+      content_type,
+    )
+    .into_bytes();
+
+    let mut pushes = 3;
+
+    let mut groups = Vec::new();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+      let pair = Self::push_data(
+        script::Builder::new().push_int((chunks.len() - i - 1).try_into().unwrap()),
+        chunk,
+      )
+      .into_bytes();
+
+      let size = Self::script_sig(
+        &[data.as_slice(), pair.as_slice()].concat(),
+        &Self::DUMMY_SIGNATURE,
+        &Self::redeem_script(public_key, pushes + 2),
+      )
+      .len();
+
+      if size > Self::MAX_SCRIPT_SIG_SIZE && pushes > 0 {
+        groups.push((mem::take(&mut data), pushes));
+        pushes = 0;
+      }
+
+      data.extend(pair);
+      pushes += 2;
+    }
+
+    groups.push((data, pushes));
+
+    Ok(groups)
+  }
+
+  fn transaction(previous_output: OutPoint, output: TxOut) -> Transaction {
+    Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![output],
+    }
+  }
+}
+
+impl Plan {
+  pub(crate) fn create_partial_transactions(
+    &self,
+    wallet_inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
+    chain: Chain,
+    locked_utxos: BTreeSet<OutPoint>,
+    runic_utxos: BTreeSet<OutPoint>,
+    mut utxos: BTreeMap<OutPoint, TxOut>,
+    commit_change: [Address; 2],
+  ) -> Result<PartialTransactions> {
+    assert_eq!(self.mode, Mode::Partial);
+
+    ensure!(
+      self.inscriptions.len() == 1,
+      "partial mode requires exactly one inscription"
+    );
+
+    assert_eq!(
+      self.destinations.len(),
+      1,
+      "invariant: partial has only one destination"
+    );
+
+    assert_eq!(
+      self.postages.len(),
+      1,
+      "invariant: partial has only one postage"
+    );
+
+    ensure!(
+      self.etching.is_none(),
+      "partial inscriptions cannot etch runes"
+    );
+
+    ensure!(
+      self.parent_info.is_empty(),
+      "partial inscriptions cannot have parents"
+    );
+
+    let inscription = &self.inscriptions[0];
+
+    ensure!(
+      inscription.content_encoding.is_none(),
+      "partial inscriptions cannot have a content encoding"
+    );
+
+    ensure!(
+      inscription.delegate.is_none(),
+      "partial inscriptions cannot have a delegate"
+    );
+
+    ensure!(
+      inscription.metadata.is_none(),
+      "partial inscriptions cannot have metadata"
+    );
+
+    ensure!(
+      inscription.metaprotocol.is_none(),
+      "partial inscriptions cannot have a metaprotocol"
+    );
+
+    ensure!(
+      inscription.pointer.is_none(),
+      "partial inscriptions cannot have a pointer"
+    );
+
+    ensure!(
+      inscription.rune.is_none(),
+      "partial inscriptions cannot have a rune commitment"
+    );
+
+    let Some(content_type) = &inscription.content_type else {
+      bail!("partial inscriptions must have a content type");
+    };
+
+    ensure!(
+      content_type.len() <= PartialTransactions::MAX_CHUNK_SIZE,
+      "partial inscription content type longer than {} bytes",
+      PartialTransactions::MAX_CHUNK_SIZE,
+    );
+
+    let satpoint =
+      self.select_satpoint(&wallet_inscriptions, &locked_utxos, &runic_utxos, &utxos)?;
+
+    let secp256k1 = Secp256k1::new();
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let public_key = PublicKey::new(secret_key.public_key(&secp256k1));
+
+    let groups = PartialTransactions::groups(inscription, &public_key)?;
+
+    let redeem_scripts = groups
+      .iter()
+      .map(|(_data, pushes)| PartialTransactions::redeem_script(&public_key, *pushes))
+      .collect::<Vec<ScriptBuf>>();
+
+    let script_pubkeys = redeem_scripts
+      .iter()
+      .skip(1)
+      .map(|redeem_script| ScriptBuf::new_p2sh(&redeem_script.script_hash()))
+      .chain(iter::once(self.destinations[0].script_pubkey()))
+      .collect::<Vec<ScriptBuf>>();
+
+    let fees = groups
+      .iter()
+      .zip(&redeem_scripts)
+      .zip(&script_pubkeys)
+      .map(|(((data, _pushes), redeem_script), script_pubkey)| {
+        let mut tx = PartialTransactions::transaction(
+          OutPoint::null(),
+          TxOut {
+            script_pubkey: script_pubkey.clone(),
+            value: 0,
+          },
+        );
+
+        tx.input[0].script_sig = PartialTransactions::script_sig(
+          data,
+          &PartialTransactions::DUMMY_SIGNATURE,
+          redeem_script,
+        );
+
+        self.reveal_fee_rate.fee(tx.vsize()).to_sat()
+      })
+      .collect::<Vec<u64>>();
+
+    let commit_script_pubkey = ScriptBuf::new_p2sh(&redeem_scripts[0].script_hash());
+
+    let unsigned_commit_tx = TransactionBuilder::new(
+      satpoint,
+      wallet_inscriptions,
+      utxos.clone(),
+      locked_utxos,
+      runic_utxos,
+      commit_script_pubkey.clone(),
+      commit_change,
+      self.commit_fee_rate,
+      Target::Value(Amount::from_sat(
+        self.postages[0].to_sat() + fees.iter().sum::<u64>(),
+      )),
+      chain.network(),
+    )
+    .build_transaction()?;
+
+    let (vout, commit_output) = unsigned_commit_tx
+      .output
+      .iter()
+      .enumerate()
+      .find(|(_vout, output)| output.script_pubkey == commit_script_pubkey)
+      .expect("should find partial commit output");
+
+    let mut previous_output = OutPoint {
+      txid: unsigned_commit_tx.txid(),
+      vout: vout.try_into().unwrap(),
+    };
+
+    let mut value = commit_output.value;
+
+    let mut transactions = Vec::new();
+
+    for ((((data, _pushes), redeem_script), script_pubkey), fee) in groups
+      .iter()
+      .zip(&redeem_scripts)
+      .zip(script_pubkeys)
+      .zip(&fees)
+    {
+      value -= fee;
+
+      let mut tx = PartialTransactions::transaction(
+        previous_output,
+        TxOut {
+          script_pubkey,
+          value,
+        },
+      );
+
+      let sighash = SighashCache::new(&tx)
+        .legacy_signature_hash(0, redeem_script, EcdsaSighashType::All.to_u32())
+        .expect("signature hash should compute");
+
+      let signature = secp256k1.sign_ecdsa(
+        &Message::from_slice(sighash.as_ref()).expect("should be cryptographically secure hash"),
+        &secret_key,
+      );
+
+      tx.input[0].script_sig = PartialTransactions::script_sig(
+        data,
+        &ecdsa::Signature::sighash_all(signature).to_vec(),
+        redeem_script,
+      );
+
+      previous_output = OutPoint {
+        txid: tx.txid(),
+        vout: 0,
+      };
+
+      transactions.push(tx);
+    }
+
+    let last = transactions.last().unwrap();
+
+    ensure!(
+      last.output[0].value >= last.output[0].script_pubkey.dust_value().to_sat(),
+      "partial inscription output would be dust"
+    );
+
+    utxos.insert(
+      transactions[0].input[0].previous_output,
+      commit_output.clone(),
+    );
+
+    Ok(PartialTransactions {
+      total_fees: Self::calculate_fee(&unsigned_commit_tx, &utxos) + fees.iter().sum::<u64>(),
+      chain: transactions,
+      commit_tx: unsigned_commit_tx,
+    })
+  }
+
+  pub(crate) fn inscribe_partial(
+    &self,
+    locked_utxos: &BTreeSet<OutPoint>,
+    runic_utxos: BTreeSet<OutPoint>,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+    wallet: &Wallet,
+  ) -> SubcommandResult {
+    let PartialTransactions {
+      chain,
+      commit_tx,
+      total_fees,
+    } = self.create_partial_transactions(
+      wallet.inscriptions().clone(),
+      wallet.chain(),
+      locked_utxos.clone(),
+      runic_utxos,
+      utxos.clone(),
+      [wallet.get_change_address()?, wallet.get_change_address()?],
+    )?;
+
+    let output = self.partial_output(&commit_tx, &chain, total_fees);
+
+    if self.dry_run {
+      let commit_psbt = wallet
+        .bitcoin_client()
+        .wallet_process_psbt(
+          &base64::engine::general_purpose::STANDARD
+            .encode(Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?.serialize()),
+          Some(false),
+          None,
+          None,
+        )?
+        .psbt;
+
+      return Ok(Some(Box::new(Output {
+        commit_psbt: Some(commit_psbt),
+        ..output
+      })));
+    }
+
+    let signed_commit_tx = consensus::encode::deserialize::<Transaction>(
+      &wallet
+        .bitcoin_client()
+        .sign_raw_transaction_with_wallet(&commit_tx, None, None)?
+        .hex,
+    )?;
+
+    ensure!(
+      signed_commit_tx.txid() == commit_tx.txid(),
+      "signing partial commit transaction changed its txid, wallet inputs must be segwit"
+    );
+
+    let inscription_id = output.inscriptions[0].id;
+
+    let entry = PartialEntry {
+      broadcast: 0,
+      output,
+      transactions: iter::once(signed_commit_tx).chain(chain).collect(),
+    };
+
+    wallet.save_partial(inscription_id, &entry)?;
+
+    Ok(Some(Box::new(wallet.send_partial(inscription_id, entry)?)))
+  }
+
+  fn partial_output(
+    &self,
+    commit_tx: &Transaction,
+    chain: &[Transaction],
+    total_fees: u64,
+  ) -> Output {
+    let reveal = chain.last().unwrap().txid();
+
+    Output {
+      commit: commit_tx.txid(),
+      commit_psbt: None,
+      inscriptions: vec![InscriptionInfo {
+        destination: uncheck(&self.destinations[0]),
+        id: InscriptionId {
+          txid: chain[0].txid(),
+          index: 0,
+        },
+        location: SatPoint {
+          outpoint: OutPoint {
+            txid: reveal,
+            vout: 0,
+          },
+          offset: 0,
+        },
+      }],
+      parents: Vec::new(),
+      reveal,
+      reveal_broadcast: false,
+      reveal_psbt: None,
+      rune: None,
+      total_fees,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::partials::{InscriptionParser, ParsedInscription},
+    bellscoin::WPubkeyHash,
+  };
+
+  // the bech32 fixtures in `crate::test` don't parse on this chain
+  fn p2wpkh(n: u8) -> Address {
+    Chain::Signet
+      .address_from_script(&ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::from_byte_array(
+        [n; 20],
+      )))
+      .unwrap()
+  }
+
+  fn partial_plan(inscription: Inscription) -> Plan {
+    Plan {
+      destinations: vec![p2wpkh(0)],
+      inscriptions: vec![inscription],
+      mode: Mode::Partial,
+      postages: vec![TARGET_POSTAGE],
+      satpoint: Some(satpoint(1, 0)),
+      ..default()
+    }
+  }
+
+  fn create_partial_transactions(plan: &Plan) -> Result<PartialTransactions> {
+    plan.create_partial_transactions(
+      BTreeMap::new(),
+      Chain::Signet,
+      BTreeSet::new(),
+      BTreeSet::new(),
+      [(outpoint(1), tx_out(1_000_000, p2wpkh(1)))]
+        .into_iter()
+        .collect(),
+      [p2wpkh(2), p2wpkh(3)],
+    )
+  }
+
+  #[test]
+  fn partial_inscription_chain_is_parsed_and_signed() {
+    let body = (0..5000u32)
+      .map(|i| u8::try_from(i % 251).unwrap())
+      .collect::<Vec<u8>>();
+
+    let PartialTransactions {
+      chain,
+      commit_tx,
+      total_fees,
+    } = create_partial_transactions(&partial_plan(inscription("text/plain", &body))).unwrap();
+
+    assert!(chain.len() > 1);
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut previous_output = OutPoint {
+      txid: commit_tx.txid(),
+      vout: 0,
+    };
+
+    let mut value = commit_tx.output[0].value;
+
+    for tx in &chain {
+      assert_eq!(tx.input.len(), 1);
+      assert_eq!(tx.output.len(), 1);
+      assert_eq!(tx.input[0].previous_output, previous_output);
+      assert!(tx.is_explicitly_rbf());
+
+      let script_sig = &tx.input[0].script_sig;
+
+      assert!(script_sig.len() <= PartialTransactions::MAX_SCRIPT_SIG_SIZE);
+
+      let pushes = script_sig
+        .instructions()
+        .map(|instruction| match instruction.unwrap() {
+          script::Instruction::PushBytes(push) => push.as_bytes().to_vec(),
+          script::Instruction::Op(_) => Vec::new(),
+        })
+        .collect::<Vec<Vec<u8>>>();
+
+      let redeem_script = ScriptBuf::from(pushes[pushes.len() - 1].clone());
+      let signature = &pushes[pushes.len() - 2];
+
+      let sighash = SighashCache::new(tx)
+        .legacy_signature_hash(0, &redeem_script, 1)
+        .unwrap();
+
+      secp256k1
+        .verify_ecdsa(
+          &secp256k1::Message::from_slice(sighash.as_ref()).unwrap(),
+          &secp256k1::ecdsa::Signature::from_der(&signature[..signature.len() - 1]).unwrap(),
+          &secp256k1::PublicKey::from_slice(&redeem_script.as_bytes()[1..34]).unwrap(),
+        )
+        .unwrap();
+
+      assert_eq!(signature.last(), Some(&1));
+
+      assert!(tx.output[0].value < value);
+
+      previous_output = OutPoint {
+        txid: tx.txid(),
+        vout: 0,
+      };
+      value = tx.output[0].value;
+    }
+
+    let last = chain.last().unwrap();
+
+    assert_eq!(last.output[0].script_pubkey, p2wpkh(0).script_pubkey());
+    assert_eq!(last.output[0].value, TARGET_POSTAGE.to_sat());
+
+    assert_eq!(
+      total_fees,
+      1_000_000
+        - commit_tx
+          .output
+          .iter()
+          .map(|output| output.value)
+          .sum::<u64>()
+        + commit_tx.output[0].value
+        - TARGET_POSTAGE.to_sat(),
+    );
+
+    let scripts = chain
+      .iter()
+      .map(|tx| tx.input[0].script_sig.as_script())
+      .collect::<Vec<&Script>>();
+
+    for i in 1..scripts.len() {
+      assert_eq!(
+        InscriptionParser::parse(scripts[..i].to_vec()),
+        ParsedInscription::Partial,
+      );
+    }
+
+    assert_eq!(
+      InscriptionParser::parse(scripts),
+      ParsedInscription::Complete(inscription("text/plain", &body)),
+    );
+  }
+
+  #[test]
+  fn partial_inscription_with_small_body_fits_in_one_transaction() {
+    for body in [&b""[..], &[0], &[1], &[16], &[0x81, 0x81]] {
+      let PartialTransactions { chain, .. } =
+        create_partial_transactions(&partial_plan(inscription("text/plain", body))).unwrap();
+
+      assert_eq!(chain.len(), 1);
+
+      assert_eq!(
+        InscriptionParser::parse(vec![chain[0].input[0].script_sig.as_script()]),
+        ParsedInscription::Complete(inscription("text/plain", body)),
+      );
+    }
+  }
+
+  #[test]
+  fn partial_inscription_chunks_avoid_lone_negative_one() {
+    let chunks = PartialTransactions::chunks(&[0x81; 241]).unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].len(), 239);
+    assert_eq!(chunks[1], [0x81, 0x81]);
+
+    assert_eq!(
+      PartialTransactions::chunks(&[0x81])
+        .unwrap_err()
+        .to_string(),
+      "partial inscription body cannot be the single byte 0x81",
+    );
+
+    assert_eq!(
+      PartialTransactions::chunks(&[]).unwrap(),
+      [Vec::<u8>::new()]
+    );
+  }
+
+  #[test]
+  fn partial_inscription_rejects_unsupported_fields() {
+    assert_eq!(
+      create_partial_transactions(&partial_plan(Inscription {
+        metaprotocol: Some(b"foo".to_vec()),
+        ..inscription("text/plain", "ord")
+      }))
+      .unwrap_err()
+      .to_string(),
+      "partial inscriptions cannot have a metaprotocol",
+    );
+
+    assert_eq!(
+      create_partial_transactions(&partial_plan(Inscription {
+        content_type: None,
+        ..inscription("text/plain", "ord")
+      }))
+      .unwrap_err()
+      .to_string(),
+      "partial inscriptions must have a content type",
+    );
+
+    assert_eq!(
+      create_partial_transactions(&Plan {
+        inscriptions: vec![
+          inscription("text/plain", "a"),
+          inscription("text/plain", "b")
+        ],
+        ..partial_plan(inscription("text/plain", "ord"))
+      })
+      .unwrap_err()
+      .to_string(),
+      "partial mode requires exactly one inscription",
+    );
+  }
+}
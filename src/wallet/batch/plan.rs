@@ -46,6 +46,10 @@ impl Plan {
     utxos: &BTreeMap<OutPoint, TxOut>,
     wallet: &Wallet,
   ) -> SubcommandResult {
+    if self.mode == Mode::Partial {
+      return self.inscribe_partial(locked_utxos, runic_utxos, utxos, wallet);
+    }
+
     let Transactions {
       commit_tx,
       commit_vout,
@@ -182,7 +186,7 @@ impl Plan {
     }
   }
 
-  fn remove_witnesses(mut transaction: Transaction) -> Transaction {
+  pub(crate) fn remove_witnesses(mut transaction: Transaction) -> Transaction {
     for txin in transaction.input.iter_mut() {
       txin.witness = Witness::new();
     }
@@ -207,7 +211,7 @@ impl Plan {
 
       let vout = match self.mode {
         Mode::SharedOutput | Mode::SameSat => self.parent_info.len().try_into().unwrap(),
-        Mode::SeparateOutputs | Mode::SatPoints => {
+        Mode::Partial | Mode::SeparateOutputs | Mode::SatPoints => {
           index + u32::try_from(self.parent_info.len()).unwrap()
         }
       };
@@ -217,12 +221,12 @@ impl Plan {
           .iter()
           .map(|amount| amount.to_sat())
           .sum(),
-        Mode::SeparateOutputs | Mode::SameSat | Mode::SatPoints => 0,
+        Mode::Partial | Mode::SeparateOutputs | Mode::SameSat | Mode::SatPoints => 0,
       };
 
       let destination = match self.mode {
         Mode::SameSat | Mode::SharedOutput => &self.destinations[0],
-        Mode::SatPoints | Mode::SeparateOutputs => &self.destinations[i],
+        Mode::Partial | Mode::SatPoints | Mode::SeparateOutputs => &self.destinations[i],
       };
 
       inscriptions_output.push(InscriptionInfo {
@@ -273,6 +277,9 @@ impl Plan {
     }
 
     match self.mode {
+      Mode::Partial => {
+        panic!("invariant: partial inscriptions are built by `create_partial_transactions`")
+      }
       Mode::SameSat => {
         assert_eq!(
           self.postages.len(),
@@ -311,57 +318,8 @@ impl Plan {
       }
     }
 
-    let satpoint = if let Some(satpoint) = self.satpoint {
-      satpoint
-    } else {
-      let inscribed_utxos = wallet_inscriptions
-        .keys()
-        .map(|satpoint| satpoint.outpoint)
-        .collect::<BTreeSet<OutPoint>>();
-
-      utxos
-        .iter()
-        .find(|(outpoint, txout)| {
-          txout.value > 0
-            && !inscribed_utxos.contains(outpoint)
-            && !locked_utxos.contains(outpoint)
-            && !runic_utxos.contains(outpoint)
-        })
-        .map(|(outpoint, _amount)| SatPoint {
-          outpoint: *outpoint,
-          offset: 0,
-        })
-        .ok_or_else(|| anyhow!("wallet contains no cardinal utxos"))?
-    };
-
-    let mut reinscription = false;
-
-    for (inscribed_satpoint, inscription_ids) in &wallet_inscriptions {
-      if *inscribed_satpoint == satpoint {
-        reinscription = true;
-        if self.reinscribe {
-          continue;
-        }
-
-        bail!("sat at {} already inscribed", satpoint);
-      }
-
-      if inscribed_satpoint.outpoint == satpoint.outpoint {
-        bail!(
-          "utxo {} with sat {inscribed_satpoint} already inscribed with the following inscriptions:\n{}",
-          satpoint.outpoint,
-          inscription_ids
-            .iter()
-            .map(ToString::to_string)
-            .collect::<Vec<String>>()
-            .join("\n"),
-        );
-      }
-    }
-
-    if self.reinscribe && !reinscription {
-      bail!("reinscribe flag set but this would not be a reinscription");
-    }
+    let satpoint =
+      self.select_satpoint(&wallet_inscriptions, &locked_utxos, &runic_utxos, &utxos)?;
 
     let secp256k1 = Secp256k1::new();
     let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
@@ -417,7 +375,7 @@ impl Plan {
       reveal_outputs.push(TxOut {
         script_pubkey: destination.script_pubkey(),
         value: match self.mode {
-          Mode::SeparateOutputs | Mode::SatPoints => self.postages[i].to_sat(),
+          Mode::Partial | Mode::SeparateOutputs | Mode::SatPoints => self.postages[i].to_sat(),
           Mode::SharedOutput | Mode::SameSat => total_postage,
         },
       });
@@ -671,6 +629,68 @@ impl Plan {
     })
   }
 
+  pub(crate) fn select_satpoint(
+    &self,
+    wallet_inscriptions: &BTreeMap<SatPoint, Vec<InscriptionId>>,
+    locked_utxos: &BTreeSet<OutPoint>,
+    runic_utxos: &BTreeSet<OutPoint>,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result<SatPoint> {
+    let satpoint = if let Some(satpoint) = self.satpoint {
+      satpoint
+    } else {
+      let inscribed_utxos = wallet_inscriptions
+        .keys()
+        .map(|satpoint| satpoint.outpoint)
+        .collect::<BTreeSet<OutPoint>>();
+
+      utxos
+        .iter()
+        .find(|(outpoint, txout)| {
+          txout.value > 0
+            && !inscribed_utxos.contains(outpoint)
+            && !locked_utxos.contains(outpoint)
+            && !runic_utxos.contains(outpoint)
+        })
+        .map(|(outpoint, _amount)| SatPoint {
+          outpoint: *outpoint,
+          offset: 0,
+        })
+        .ok_or_else(|| anyhow!("wallet contains no cardinal utxos"))?
+    };
+
+    let mut reinscription = false;
+
+    for (inscribed_satpoint, inscription_ids) in wallet_inscriptions {
+      if *inscribed_satpoint == satpoint {
+        reinscription = true;
+        if self.reinscribe {
+          continue;
+        }
+
+        bail!("sat at {} already inscribed", satpoint);
+      }
+
+      if inscribed_satpoint.outpoint == satpoint.outpoint {
+        bail!(
+          "utxo {} with sat {inscribed_satpoint} already inscribed with the following inscriptions:\n{}",
+          satpoint.outpoint,
+          inscription_ids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join("\n"),
+        );
+      }
+    }
+
+    if self.reinscribe && !reinscription {
+      bail!("reinscribe flag set but this would not be a reinscription");
+    }
+
+    Ok(satpoint)
+  }
+
   fn backup_recovery_key(wallet: &Wallet, recovery_key_pair: TweakedKeyPair) -> Result {
     let recovery_private_key = PrivateKey::new(
       recovery_key_pair.to_inner().secret_key(),
@@ -754,7 +774,7 @@ impl Plan {
     (reveal_tx, fee)
   }
 
-  fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, TxOut>) -> u64 {
+  pub(crate) fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, TxOut>) -> u64 {
     tx.input
       .iter()
       .map(|txin| utxos.get(&txin.previous_output).unwrap().value)
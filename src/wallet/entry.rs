@@ -36,6 +36,42 @@ impl Entry for EtchingEntry {
   }
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PartialEntry {
+  pub broadcast: u32,
+  pub output: batch::Output,
+  pub transactions: Vec<Transaction>,
+}
+
+pub(super) type PartialEntryValue = (
+  u32,     // broadcast
+  Vec<u8>, // output
+  Vec<u8>, // transactions
+);
+
+impl Entry for PartialEntry {
+  type Value = PartialEntryValue;
+
+  fn load((broadcast, output, transactions): PartialEntryValue) -> Self {
+    Self {
+      broadcast,
+      output: serde_json::from_slice(&output).unwrap(),
+      transactions: consensus::encode::deserialize::<Vec<Transaction>>(&transactions).unwrap(),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.broadcast,
+      serde_json::to_string(&self.output)
+        .unwrap()
+        .as_bytes()
+        .to_owned(),
+      consensus::encode::serialize(&self.transactions),
+    )
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -102,4 +138,62 @@ mod tests {
     assert_eq!(entry.clone().store(), value);
     assert_eq!(EtchingEntry::load(value), entry);
   }
+
+  #[test]
+  fn partial_entry() {
+    let transactions = vec![
+      Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+          previous_output: OutPoint::null(),
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: Witness::new(),
+        }],
+        output: Vec::new(),
+      },
+      Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+          previous_output: OutPoint::null(),
+          script_sig: ScriptBuf::from_bytes(vec![0x03, b'o', b'r', b'd']),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        }],
+        output: Vec::new(),
+      },
+    ];
+
+    let output = batch::Output {
+      commit: transactions[0].txid(),
+      commit_psbt: None,
+      inscriptions: Vec::new(),
+      parents: Vec::new(),
+      reveal: transactions[1].txid(),
+      reveal_broadcast: false,
+      reveal_psbt: None,
+      rune: None,
+      total_fees: 0,
+    };
+
+    let value = (
+      1,
+      serde_json::to_string(&output)
+        .unwrap()
+        .as_bytes()
+        .to_owned(),
+      consensus::encode::serialize(&transactions),
+    );
+
+    let entry = PartialEntry {
+      broadcast: 1,
+      output,
+      transactions,
+    };
+
+    assert_eq!(entry.clone().store(), value);
+    assert_eq!(PartialEntry::load(value), entry);
+  }
 }
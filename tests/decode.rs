@@ -5,7 +5,9 @@ use {
     TxIn, TxOut, Witness,
   },
   ord::{
-    subcommand::decode::{CompactInscription, CompactOutput, RawOutput},
+    subcommand::decode::{
+      CompactInscription, CompactOutput, CompactPartial, PartialStatus, RawOutput, RawPartial,
+    },
     Envelope, Inscription,
   },
 };
@@ -67,6 +69,7 @@ fn from_file() {
         pushnum: false,
         stutter: false,
       }],
+      partial: None,
       runestone: Some(Artifact::Runestone(Runestone::default())),
     },
   );
@@ -90,6 +93,7 @@ fn from_stdin() {
         pushnum: false,
         stutter: false,
       }],
+      partial: None,
       runestone: Some(Artifact::Runestone(Runestone::default())),
     },
   );
@@ -122,6 +126,7 @@ fn from_core() {
         pushnum: false,
         stutter: false,
       }],
+      partial: None,
       runestone: None,
     },
   );
@@ -146,7 +151,123 @@ fn compact() {
         pointer: None,
         unrecognized_even_field: false,
       }],
+      partial: None,
       runestone: Some(Artifact::Runestone(Runestone::default())),
     },
   );
 }
+
+fn partial_transactions() -> (Transaction, Transaction) {
+  let first = Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint::null(),
+      script_sig: script::Builder::new()
+        .push_slice(b"ord")
+        .push_int(2)
+        .push_slice(b"text/plain")
+        .push_int(1)
+        .push_slice(b"foo")
+        .into_script(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    }],
+    output: vec![TxOut {
+      script_pubkey: ScriptBuf::new(),
+      value: 0,
+    }],
+  };
+
+  let second = Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint {
+        txid: first.txid(),
+        vout: 0,
+      },
+      script_sig: script::Builder::new()
+        .push_int(0)
+        .push_slice(b"bar")
+        .into_script(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    }],
+    output: vec![TxOut {
+      script_pubkey: ScriptBuf::new(),
+      value: 0,
+    }],
+  };
+
+  (first, second)
+}
+
+fn encode(transaction: &Transaction) -> Vec<u8> {
+  let mut buffer = Vec::new();
+  transaction.consensus_encode(&mut buffer).unwrap();
+  buffer
+}
+
+#[test]
+fn partial_inscription_from_files() {
+  let (first, second) = partial_transactions();
+
+  pretty_assert_eq!(
+    CommandBuilder::new("decode --file first.bin --file second.bin")
+      .write("first.bin", encode(&first))
+      .write("second.bin", encode(&second))
+      .run_and_deserialize_output::<RawOutput>(),
+    RawOutput {
+      inscriptions: Vec::new(),
+      partial: Some(RawPartial {
+        body: b"foobar".into(),
+        content_type: "text/plain".into(),
+        pieces: 2,
+        pieces_remaining: 0,
+        status: PartialStatus::Complete,
+        transactions: vec![first.txid(), second.txid()],
+      }),
+      runestone: None,
+    },
+  );
+}
+
+#[test]
+fn incomplete_partial_inscription_compact() {
+  let (first, _second) = partial_transactions();
+
+  pretty_assert_eq!(
+    CommandBuilder::new("decode --compact --file first.bin")
+      .write("first.bin", encode(&first))
+      .run_and_deserialize_output::<CompactOutput>(),
+    CompactOutput {
+      inscriptions: Vec::new(),
+      partial: Some(CompactPartial {
+        body: "666f6f".into(),
+        content_type: "text/plain".into(),
+        pieces: 2,
+        pieces_remaining: 1,
+        status: PartialStatus::Partial,
+        transactions: vec![first.txid()],
+      }),
+      runestone: None,
+    },
+  );
+}
+
+#[test]
+fn partial_inscription_files_must_form_a_chain() {
+  let (first, _second) = partial_transactions();
+
+  CommandBuilder::new("decode --file first.bin --file second.bin")
+    .write("first.bin", encode(&first))
+    .write("second.bin", encode(&first))
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: transaction {} does not spend {}:0\n",
+      first.txid(),
+      first.txid()
+    ))
+    .run_and_extract_stdout();
+}
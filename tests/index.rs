@@ -90,3 +90,170 @@ fn export_inscription_number_to_id_tsv() {
     &ord::Object::InscriptionId(inscription),
   );
 }
+
+#[test]
+fn export_inscriptions_as_jsonl_and_csv() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (first, _) = inscribe(&core, &ord);
+
+  let height = core.height();
+
+  let (second, _) = inscribe(&core, &ord);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let jsonl = tempdir.path().join("inscriptions.jsonl");
+
+  let output = CommandBuilder::new(format!(
+    "index export --output {} --format jsonl",
+    jsonl.display()
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<ord::subcommand::index::export::Output>();
+
+  assert_eq!(output.records, 2);
+
+  let records = fs::read_to_string(&jsonl)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str::<ord::index::InscriptionRecord>(line).unwrap())
+    .collect::<Vec<ord::index::InscriptionRecord>>();
+
+  assert_eq!(records[0].id, first);
+  assert_eq!(
+    records[0].content_type.as_deref(),
+    Some("text/plain;charset=utf-8")
+  );
+  assert_eq!(records[1].id, second);
+
+  let csv = tempdir.path().join("inscriptions.csv");
+
+  let output = CommandBuilder::new(format!(
+    "index export --output {} --format csv --from-height {}",
+    csv.display(),
+    height + 1,
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<ord::subcommand::index::export::Output>();
+
+  assert_eq!(output.records, 1);
+
+  let csv = fs::read_to_string(&csv).unwrap();
+  let mut lines = csv.lines();
+
+  assert_eq!(
+    lines.next().unwrap(),
+    "id,number,sequence_number,height,timestamp,content_type,content_length,charms,fee,sat,satpoint,parents,address",
+  );
+  assert!(lines.next().unwrap().starts_with(&format!("{second},1,1,")));
+  assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn export_rune_balances_cannot_be_filtered_by_height() {
+  let core = mockcore::spawn();
+
+  let tempdir = TempDir::new().unwrap();
+
+  CommandBuilder::new(format!(
+    "index export --output {} --dataset rune-balances --from-height 1",
+    tempdir.path().join("balances.jsonl").display()
+  ))
+  .core(&core)
+  .expected_stderr(
+    "error: rune balances are a snapshot of the current UTXO set and cannot be exported by height\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot_create_and_restore() {
+  let core = mockcore::spawn();
+
+  core.mine_blocks(3);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let snapshot_path = tempdir.path().join("index.snapshot");
+
+  let created = CommandBuilder::new(format!(
+    "index snapshot create --output {}",
+    snapshot_path.display()
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<ord::index::Snapshot>();
+
+  assert_eq!(created.height, 3);
+
+  let index_path = tempdir.path().join("restored.redb");
+
+  let restored = CommandBuilder::new(format!(
+    "--index {} index snapshot restore --input {}",
+    index_path.display(),
+    snapshot_path.display()
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<ord::index::Snapshot>();
+
+  assert_eq!(restored, created);
+
+  let info = CommandBuilder::new(format!("--index {} index info", index_path.display()))
+    .core(&core)
+    .run_and_deserialize_output::<serde_json::Value>();
+
+  assert_eq!(info["blocks_indexed"], 4);
+}
+
+#[test]
+fn rollback_removes_blocks_and_update_resyncs() {
+  let core = mockcore::spawn();
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("index.redb");
+
+  for _ in 0..5 {
+    core.mine_blocks(1);
+
+    CommandBuilder::new(format!("--index {} index update", index_path.display()))
+      .core(&core)
+      .run_and_extract_stdout();
+  }
+
+  let rollback = CommandBuilder::new(format!(
+    "--index {} index rollback --height 5",
+    index_path.display()
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<ord::index::Rollback>();
+
+  assert_eq!(rollback.blocks_indexed, 5);
+  assert_eq!(rollback.blocks_removed, 1);
+  assert_eq!(
+    rollback.tables["HEIGHT_TO_BLOCK_HEADER"],
+    ord::index::TableChange {
+      entries_before: 6,
+      entries_after: 5,
+    }
+  );
+
+  CommandBuilder::new(format!(
+    "--index {} index rollback --height 6",
+    index_path.display()
+  ))
+  .core(&core)
+  .expected_stderr("error: cannot roll back to height 6, index only contains 5 blocks\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  let info = CommandBuilder::new(format!("--index {} index info", index_path.display()))
+    .core(&core)
+    .run_and_deserialize_output::<serde_json::Value>();
+
+  assert_eq!(info["blocks_indexed"], 6);
+}
@@ -13,10 +13,14 @@ fn json_with_satoshi_index() {
   "index_file_size": \d+,
   "index_path": ".*\.redb",
   "leaf_pages": \d+,
+  "max_savepoints": 2,
   "metadata_bytes": \d+,
   "outputs_traversed": 1,
   "page_size": \d+,
   "sat_ranges": 1,
+  "savepoint_bytes_upper_bound": \d+,
+  "savepoint_interval": 10,
+  "savepoints": \d+,
   "stored_bytes": \d+,
   "tables": .*,
   "total_bytes": \d+,
@@ -27,6 +31,9 @@ fn json_with_satoshi_index() {
     \}
   \],
   "tree_height": \d+,
+  "undo_log_bytes": \d+,
+  "undo_log_depth": 0,
+  "undo_log_records": 0,
   "utxos_indexed": 1
 \}
 "#,
@@ -47,10 +54,14 @@ fn json_without_satoshi_index() {
   "index_file_size": \d+,
   "index_path": ".*\.redb",
   "leaf_pages": \d+,
+  "max_savepoints": 2,
   "metadata_bytes": \d+,
   "outputs_traversed": 0,
   "page_size": \d+,
   "sat_ranges": 0,
+  "savepoint_bytes_upper_bound": \d+,
+  "savepoint_interval": 10,
+  "savepoints": \d+,
   "stored_bytes": \d+,
   "tables": .*,
   "total_bytes": \d+,
@@ -61,6 +72,9 @@ fn json_without_satoshi_index() {
     \}
   \],
   "tree_height": \d+,
+  "undo_log_bytes": \d+,
+  "undo_log_depth": 0,
+  "undo_log_records": 0,
   "utxos_indexed": 1
 \}
 "#,
@@ -155,6 +155,7 @@ fn get_inscription() {
       children: Vec::new(),
       content_length: Some(3),
       content_type: Some("text/plain;charset=utf-8".to_string()),
+      duplicate_of: None,
       effective_content_type: Some("text/plain;charset=utf-8".to_string()),
       fee: 138,
       height: 2,
@@ -381,6 +382,7 @@ fn get_block() {
   assert_eq!(
     block_json,
     api::Block {
+      auxpow: None,
       hash: "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
         .parse::<BlockHash>()
         .unwrap(),
@@ -745,6 +747,7 @@ fn get_decode_tx() {
     serde_json::from_str::<api::Decode>(&response.text().unwrap()).unwrap(),
     api::Decode {
       inscriptions,
+      partial: None,
       runestone,
     }
   );
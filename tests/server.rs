@@ -63,6 +63,32 @@ fn address_page_shows_outputs_and_sat_balance() {
   );
 }
 
+#[test]
+fn address_history_page_shows_sends() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_args(&core, &["--index-address-history"]);
+
+  create_wallet(&core, &ord);
+  core.mine_blocks(1);
+
+  let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+  let send = CommandBuilder::new(format!("wallet send --fee-rate 13.3 {address} 2btc"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/address/{address}/history"),
+    format!(
+      ".*History</h1>.*<a class=monospace href=/tx/{0}>{0}</a>.*<dt>received</dt><dd>200000000</dd>.*",
+      send.txid,
+    ),
+  );
+}
+
 #[test]
 fn address_page_shows_single_rune() {
   let core = mockcore::builder().network(Network::Regtest).build();
@@ -385,6 +411,34 @@ fn inscription_page_after_send() {
   )
 }
 
+#[test]
+fn inscription_history_after_send() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, reveal) = inscribe(&core, &ord);
+
+  let txid = CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .stdout_regex(".*")
+  .run_and_deserialize_output::<Output>()
+  .txid;
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{inscription}/history"),
+    format!(
+      r".*History</h1>.*<a class=monospace href=/tx/{txid}>{txid}</a>.*<dt>from</dt><dd><a class=monospace href=/satpoint/{reveal}:0:0>.*<dt>address</dt><dd><a class=monospace href=/address/bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv>.*",
+    ),
+  );
+}
+
 #[test]
 fn inscription_content() {
   let core = mockcore::spawn();
@@ -21,16 +21,24 @@ fn default() {
   "hidden": \[\],
   "http_port": null,
   "index": ".*index\.redb",
+  "index_address_history": false,
   "index_addresses": false,
+  "index_auxpow": false,
   "index_cache_size": \d+,
+  "index_events": false,
+  "index_partials": false,
   "index_runes": false,
   "index_sats": false,
+  "index_text": false,
   "index_transactions": false,
   "integration_test": false,
+  "max_savepoints": 2,
   "no_index_inscriptions": false,
+  "savepoint_interval": 10,
   "server_password": null,
   "server_url": null,
-  "server_username": null
+  "server_username": null,
+  "undo_log_depth": 0
 \}
 "#,
     )
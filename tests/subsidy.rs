@@ -1,4 +1,7 @@
-use {super::*, ord::subcommand::subsidy::Output};
+use {
+  super::*,
+  ord::subcommand::subsidy::{Mismatch, Output, VerifyOutput},
+};
 
 #[test]
 fn genesis() {
@@ -55,3 +58,44 @@ fn first_block_without_subsidy() {
     .expected_exit_code(1)
     .run_and_extract_stdout();
 }
+
+#[test]
+fn verify_reports_coinbase_mismatches() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  core.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0, default())],
+    fee: 1000,
+    ..default()
+  });
+
+  core.mine_blocks_with_subsidy(1, 2 * COIN_VALUE);
+  core.mine_blocks_with_subsidy(1, 50 * COIN_VALUE);
+
+  assert_eq!(
+    CommandBuilder::new("--regtest subsidy --verify --from-height 1")
+      .core(&core)
+      .run_and_deserialize_output::<VerifyOutput>(),
+    VerifyOutput {
+      from_height: 1,
+      to_height: 3,
+      mismatches: vec![Mismatch {
+        height: 3,
+        subsidy: 2 * COIN_VALUE,
+        fees: 0,
+        expected: 2 * COIN_VALUE,
+        coinbase: 50 * COIN_VALUE,
+      }],
+    }
+  );
+}
+
+#[test]
+fn verify_conflicts_with_height() {
+  CommandBuilder::new("subsidy 1 --verify")
+    .stderr_regex("error: the argument .* cannot be used with .*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}